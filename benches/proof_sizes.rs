@@ -0,0 +1,42 @@
+// Proof sizes of FRI and STIR at equal conjectured security, over Goldilocks with SHA-256.
+// Run with `cargo bench --bench proof_sizes`. The FRI prover evaluates its levels naively, which bounds the degrees.
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_serialize::CanonicalSerialize;
+use ark_std::UniformRand;
+use fri_commitment::fields::goldilocks_field::Fq;
+use fri_commitment::fri::prover::generate_fri_proof;
+use fri_commitment::fri::security::SoundnessRegime;
+use fri_commitment::fri::types::FriConfig;
+use fri_commitment::fri::verifier::verify_fri_proof;
+use fri_commitment::hashing::hasher::Sha256_;
+use fri_commitment::stir::prover::generate_stir_proof;
+use fri_commitment::stir::types::StirConfig;
+use fri_commitment::stir::verifier::verify_stir_proof;
+use rand::{rngs::StdRng, SeedableRng};
+
+// Goldilocks challenges cap conjectured security a little above 40 bits for these domains
+const SECURITY_BITS: u32 = 40;
+const BLOW_UP_FACTOR: u32 = 4;
+const STIR_FOLDING_FACTOR_BITS: u32 = 4;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    println!("{:>8} {:>10} {:>10} {:>12} {:>12}", "log2 d", "FRI bytes", "STIR bytes", "FRI queries", "STIR queries");
+    for log_degree in (10..=14).step_by(2) {
+        let degree = (1u32 << log_degree) - 1;
+        let polynomial = DensePolynomial::from_coefficients_vec((0..=degree).map(|_| Fq::rand(&mut rng)).collect());
+
+        let fri_config = FriConfig::for_security::<Fq>(SECURITY_BITS, degree, BLOW_UP_FACTOR, 0, SoundnessRegime::Conjectured).unwrap();
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(polynomial.clone(), fri_config.clone());
+        let fri_size = fri_proof.compressed_size();
+        assert!(verify_fri_proof(fri_config.clone(), degree, fri_proof));
+
+        let stir_config = StirConfig::for_security::<Fq>(SECURITY_BITS, degree, BLOW_UP_FACTOR, STIR_FOLDING_FACTOR_BITS).unwrap();
+        let stir_proof = generate_stir_proof::<Fq, Sha256_<Fq>>(polynomial, stir_config.clone());
+        let stir_size = stir_proof.compressed_size();
+        assert!(verify_stir_proof(stir_config.clone(), degree, stir_proof));
+
+        let stir_queries: u32 = stir_config.num_queries.iter().sum();
+        println!("{:>8} {:>10} {:>10} {:>12} {:>12}", log_degree, fri_size, stir_size, fri_config.num_query, stir_queries);
+    }
+}
@@ -0,0 +1,80 @@
+// Fold consistency checks of the FRI verifier: naive Lagrange interpolation against the coset iFFT with
+// batch inverted shifts, per arity, then full verification times for schedules of increasing arity.
+// Run with `cargo bench --bench verifier_fold`.
+use std::time::{Duration, Instant};
+
+use ark_ff::{batch_inversion, Field};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
+use ark_std::UniformRand;
+use fri_commitment::fields::goldilocks_field::Fq;
+use fri_commitment::fri::prover::generate_fri_proof;
+use fri_commitment::fri::types::FriConfig;
+use fri_commitment::fri::verifier::{calcualate_next_level_value, fold_coset_evaluations, verify_fri_proof};
+use fri_commitment::hashing::hasher::Sha256_;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const LOG_DOMAIN: u32 = 14;
+const NUM_QUERIES: usize = 64;
+const REPETITIONS: u32 = 20;
+
+fn time(mut f: impl FnMut(), repetitions: u32) -> Duration {
+    let start = Instant::now();
+    for _ in 0..repetitions {
+        f();
+    }
+    start.elapsed() / repetitions
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let domain_size = 1usize << LOG_DOMAIN;
+    let offset = Fq::from(7u64);
+    let domain = GeneralEvaluationDomain::<Fq>::new(domain_size).unwrap();
+    let eval_domain = domain.get_coset(offset).unwrap();
+
+    println!("one level, {} queries over a domain of 2^{}", NUM_QUERIES, LOG_DOMAIN);
+    println!("{:>8} {:>14} {:>14} {:>8}", "arity", "lagrange us", "coset fft us", "speedup");
+    for log_arity in 1..=5 {
+        let reduction = 1usize << log_arity;
+        let query_addition_factor = domain_size / reduction;
+        let coset_domain = GeneralEvaluationDomain::<Fq>::new(reduction).unwrap();
+        let leaves: Vec<usize> = (0..NUM_QUERIES).map(|_| rng.gen_range(0..query_addition_factor)).collect();
+        let evaluations: Vec<Vec<Fq>> = (0..NUM_QUERIES).map(|_| (0..reduction).map(|_| Fq::rand(&mut rng)).collect()).collect();
+        let challenge = Fq::rand(&mut rng);
+
+        let mut lagrange = Vec::new();
+        let naive = time(|| {
+            lagrange = leaves.iter().zip(evaluations.iter()).map(|(j, evals)| {
+                let points = (0..reduction).map(|i| j + i * query_addition_factor).collect();
+                calcualate_next_level_value(points, evals.clone(), challenge, eval_domain)
+            }).collect();
+        }, REPETITIONS);
+
+        let mut folded = Vec::new();
+        let generator = domain.group_gen();
+        let fast = time(|| {
+            let mut shifts: Vec<Fq> = leaves.iter().map(|j| offset * generator.pow([*j as u64])).collect();
+            batch_inversion(&mut shifts);
+            folded = evaluations.iter().zip(shifts.iter())
+                .map(|(evals, shift_inv)| fold_coset_evaluations(evals, challenge, *shift_inv, &coset_domain))
+                .collect();
+        }, REPETITIONS);
+        assert_eq!(lagrange, folded);
+
+        println!("{:>8} {:>14.1} {:>14.1} {:>7.1}x", reduction, naive.as_secs_f64() * 1e6, fast.as_secs_f64() * 1e6,
+            naive.as_secs_f64() / fast.as_secs_f64());
+    }
+
+    // The FRI prover evaluates its levels naively, which bounds the degree
+    let degree = (1u32 << 11) - 1;
+    let polynomial = DensePolynomial::from_coefficients_vec((0..=degree).map(|_| Fq::rand(&mut rng)).collect());
+    println!();
+    println!("verify_fri_proof, degree 2^11 - 1, blow up 4, {} queries", NUM_QUERIES);
+    println!("{:>32} {:>12}", "schedule", "verify us");
+    for schedule in [vec![1; 10], vec![2; 5], vec![3, 3, 4], vec![5, 5]] {
+        let fri_config = FriConfig { num_query: NUM_QUERIES as u32, blow_up_factor: 4, last_polynomial_degree: 1, merkle_cap_bits: 0, level_reductions_bits: schedule.clone(), pow_bits: 0 };
+        let proof = generate_fri_proof::<Fq, Sha256_<Fq>>(polynomial.clone(), fri_config.clone());
+        let elapsed = time(|| assert!(verify_fri_proof(fri_config.clone(), degree, proof.clone())), REPETITIONS);
+        println!("{:>32} {:>12.1}", format!("{:?}", schedule), elapsed.as_secs_f64() * 1e6);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod types;
+pub mod sumcheck;
+pub mod prover;
+pub mod verifier;
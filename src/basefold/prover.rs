@@ -0,0 +1,121 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseMultilinearExtension, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, MultilinearExtension};
+use tracing::debug_span;
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::prover::{fold_polynomial, generate_query_eval_proofs, leaf_groupings};
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::MerkleTree;
+
+use super::sumcheck::{bind, eq_evaluations, round_message};
+use super::types::{BasefoldCommitment, BasefoldConfig, BasefoldProof, BasefoldProverData};
+
+// Monomial coefficients of the multilinear polynomial with the given hypercube evaluations
+pub fn multilinear_coefficients<F: PrimeField>(evaluations: &[F]) -> Vec<F> {
+    let mut coeffs = evaluations.to_vec();
+    let mut bit = 1;
+    while bit < coeffs.len() {
+        for idx in 0..coeffs.len() {
+            if idx & bit != 0 {
+                coeffs[idx] = coeffs[idx] - coeffs[idx ^ bit];
+            }
+        }
+        bit <<= 1;
+    }
+    coeffs
+}
+
+// Commits to the evaluations of the polynomial with coefficients `coeffs`, at most `coeffs_length` of them,
+// over the coset offset * <w> of size blow_up * coeffs_length, grouping the leaves as FRI does for a 1 bit reduction
+fn commit_oracle<F: PrimeField, H: Hasher_<F>>(coeffs: &[F], coeffs_length: usize, offset: F, blow_up_factor: usize, merkle_cap_bits: u32) -> MerkleTree<F, H> {
+    let domain_size = coeffs_length * blow_up_factor;
+    let domain = GeneralEvaluationDomain::<F>::new(domain_size).unwrap().get_coset(offset).expect("Error in getting coset");
+    let evaluations = domain.fft(coeffs);
+    let mut tree = MerkleTree::<F, H>::new(merkle_cap_bits);
+    tree.insert(leaf_groupings(vec![1], domain_size, 1)[0].chunks(2)
+        .map(|leaf| leaf.iter().map(|l| evaluations[*l]).collect())
+        .collect()).expect("Tree is memory backed");
+    tree.compute_tree().expect("Tree is memory backed");
+    tree
+}
+
+// Commits to `polynomial` through the univariate encoding sum c_i x^i of its monomial coefficients
+pub fn basefold_commit<F: PrimeField, H: Hasher_<F>>(polynomial: &DenseMultilinearExtension<F>, config: &BasefoldConfig) -> BasefoldProverData<F, H> {
+    let num_vars = polynomial.num_vars();
+    if let Err(e) = config.fri_config(num_vars).validate::<F>((1u32 << num_vars) - 1) {
+        panic!("Invalid BaseFold config: {}", e);
+    }
+    assert!(num_vars > 0, "BaseFold needs at least one variable");
+
+    let _commit_span = debug_span!("basefold_commit", num_vars).entered();
+    let evaluations = polynomial.to_evaluations();
+    let coeffs = multilinear_coefficients(&evaluations);
+    let tree = commit_oracle::<F, H>(&coeffs, coeffs.len(), F::GENERATOR, config.blow_up_factor as usize, config.merkle_cap_bits);
+    BasefoldProverData {
+        commitment: BasefoldCommitment { num_vars, root: tree.root_cap.clone().unwrap() },
+        evaluations,
+        coeffs,
+        tree,
+    }
+}
+
+// Binds the commitment, point and claimed value to the transcript
+pub fn observe_basefold_statement<F: PrimeField, H: Hasher_<F>>(transcript: &mut impl TranscriptProtocol<F>, config: &BasefoldConfig, commitment: &BasefoldCommitment<F, H>, point: &[F], value: F) {
+    transcript.observe_u64(b"num query", config.num_query as u64);
+    transcript.observe_u64(b"blow up factor", config.blow_up_factor as u64);
+    transcript.observe_u64(b"merkle cap bits", config.merkle_cap_bits as u64);
+    transcript.observe_u64(b"num vars", commitment.num_vars as u64);
+    let root_field: Vec<F> = commitment.root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"merkle_root", &root_field);
+    transcript.observe_elements(b"point", point);
+    transcript.observe_element(b"value", &value);
+}
+
+// Proves the value of the committed polynomial at `point`: sumcheck of sum f(b) eq(point, b), where each
+// round's challenge also folds the encoding, so that it folds down to f at the sumcheck challenges.
+// Returns the value and its proof.
+pub fn basefold_open<F: PrimeField, H: Hasher_<F>>(data: &BasefoldProverData<F, H>, point: &[F], config: &BasefoldConfig, transcript: &mut impl TranscriptProtocol<F>) -> (F, BasefoldProof<F, H>) {
+    let num_vars = data.commitment.num_vars;
+    assert_eq!(point.len(), num_vars, "Point does not match the number of variables");
+
+    let _open_span = debug_span!("basefold_open", num_vars).entered();
+    let value = DenseMultilinearExtension::from_evaluations_slice(num_vars, &data.evaluations).evaluate(point).unwrap();
+    observe_basefold_statement(transcript, config, &data.commitment, point, value);
+
+    let mut f_table = data.evaluations.clone();
+    let mut eq_table = eq_evaluations(point);
+    let mut polynomial = DensePolynomial::from_coefficients_vec(data.coeffs.clone());
+    let mut offset = F::GENERATOR;
+    let mut sumcheck_messages = Vec::with_capacity(num_vars);
+    let mut folded_trees: Vec<MerkleTree<F, H>> = Vec::with_capacity(num_vars - 1);
+    let mut level_roots = Vec::with_capacity(num_vars - 1);
+    for round in 0..num_vars {
+        let message = round_message(&f_table, &eq_table);
+        transcript.observe_elements(b"sumcheck round", &message);
+        sumcheck_messages.push(message);
+        let r: F = transcript.get_challenge(b"alpha");
+
+        f_table = bind(&f_table, r);
+        eq_table = bind(&eq_table, r);
+        polynomial = fold_polynomial(polynomial, r, 2);
+        offset = offset.square();
+
+        if round < num_vars - 1 {
+            let tree = commit_oracle::<F, H>(&polynomial.coeffs, 1 << (num_vars - round - 1), offset, config.blow_up_factor as usize, config.merkle_cap_bits);
+            let root = tree.root_cap.clone().unwrap();
+            let root_field: Vec<F> = root.iter().map(|r| H::hash_as_field(*r)).collect();
+            transcript.observe_elements(b"merkle_root", &root_field);
+            level_roots.push(root);
+            folded_trees.push(tree);
+        }
+    }
+    let final_value = polynomial.coeffs.first().copied().unwrap_or(F::ZERO);
+    transcript.observe_element(b"final value", &final_value);
+
+    let queries = transcript.get_challenge_indices(b"challenge indices", config.num_query as usize);
+    let trees: Vec<&MerkleTree<F, H>> = std::iter::once(&data.tree).chain(folded_trees.iter()).collect();
+    let domain_size = data.coeffs.len() * config.blow_up_factor as usize;
+    let query_eval_proofs = generate_query_eval_proofs::<F, H>(queries, domain_size, vec![1; num_vars], &trees);
+
+    (value, BasefoldProof { sumcheck_messages, level_roots, final_value, query_eval_proofs })
+}
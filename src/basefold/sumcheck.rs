@@ -0,0 +1,76 @@
+use ark_ff::PrimeField;
+
+// eq(z, b) over the hypercube, bit i of b is variable i
+pub fn eq_evaluations<F: PrimeField>(z: &[F]) -> Vec<F> {
+    let mut evaluations = vec![F::ONE];
+    for z_i in z.iter() {
+        evaluations = evaluations.iter().map(|e| *e * (F::ONE - z_i))
+            .chain(evaluations.iter().map(|e| *e * z_i))
+            .collect();
+    }
+    evaluations
+}
+
+pub fn eq_eval<F: PrimeField>(z: &[F], r: &[F]) -> F {
+    z.iter().zip(r.iter()).map(|(z_i, r_i)| *z_i * r_i + (F::ONE - z_i) * (F::ONE - r_i)).product()
+}
+
+// Round polynomial of sum f(b) * eq(b) with the lowest variable free, at 0, 1 and 2
+pub fn round_message<F: PrimeField>(f: &[F], eq: &[F]) -> [F; 3] {
+    let mut message = [F::ZERO; 3];
+    for (f_pair, eq_pair) in f.chunks(2).zip(eq.chunks(2)) {
+        message[0] += f_pair[0] * eq_pair[0];
+        message[1] += f_pair[1] * eq_pair[1];
+        message[2] += (f_pair[1].double() - f_pair[0]) * (eq_pair[1].double() - eq_pair[0]);
+    }
+    message
+}
+
+// Fixes the lowest variable of a table of hypercube evaluations to r
+pub fn bind<F: PrimeField>(table: &[F], r: F) -> Vec<F> {
+    table.chunks(2).map(|pair| pair[0] + r * (pair[1] - pair[0])).collect()
+}
+
+// Value at r of the degree 2 polynomial through (0, m0), (1, m1), (2, m2)
+pub fn interpolate_round<F: PrimeField>(message: &[F; 3], r: F) -> F {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    let l0 = (r - F::ONE) * (r - F::from(2u64)) * two_inv;
+    let l1 = -r * (r - F::from(2u64));
+    let l2 = r * (r - F::ONE) * two_inv;
+    message[0] * l0 + message[1] * l1 + message[2] * l2
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::fields::goldilocks_field::Fq;
+    use super::{bind, eq_eval, eq_evaluations, interpolate_round, round_message};
+
+    #[test]
+    fn test_sumcheck_rounds() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let num_vars = 4;
+        let f: Vec<Fq> = (0..1 << num_vars).map(|_| Fq::rand(&mut rng)).collect();
+        let z: Vec<Fq> = (0..num_vars).map(|_| Fq::rand(&mut rng)).collect();
+        let r: Vec<Fq> = (0..num_vars).map(|_| Fq::rand(&mut rng)).collect();
+
+        let eq = eq_evaluations(&z);
+        assert_eq!(eq_eval(&z, &r), DenseMultilinearExtension::from_evaluations_slice(num_vars, &eq).evaluate(&r).unwrap());
+
+        // f(z) = sum f(b) eq(z, b), reduced round by round to f(r) eq(z, r)
+        let mle = DenseMultilinearExtension::from_evaluations_slice(num_vars, &f);
+        let mut claim = mle.evaluate(&z).unwrap();
+        let (mut f_table, mut eq_table) = (f.clone(), eq);
+        for r_i in r.iter() {
+            let message = round_message(&f_table, &eq_table);
+            assert_eq!(message[0] + message[1], claim);
+            claim = interpolate_round(&message, *r_i);
+            f_table = bind(&f_table, *r_i);
+            eq_table = bind(&eq_table, *r_i);
+        }
+        assert_eq!(claim, mle.evaluate(&r).unwrap() * eq_eval(&z, &r));
+    }
+}
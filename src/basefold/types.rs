@@ -0,0 +1,48 @@
+use ark_ff::PrimeField;
+
+use crate::fri::types::{FriConfig, QueryEvalProofs};
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::MerkleTree;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasefoldConfig {
+    pub num_query: u32,
+    pub blow_up_factor: u32,
+    pub merkle_cap_bits: u32,
+}
+
+impl BasefoldConfig {
+    // FRI config of the folding phase for `num_vars` variables: one variable folded per level, down to a constant
+    pub fn fri_config(&self, num_vars: usize) -> FriConfig {
+        FriConfig {
+            num_query: self.num_query,
+            blow_up_factor: self.blow_up_factor,
+            last_polynomial_degree: 0,
+            merkle_cap_bits: self.merkle_cap_bits,
+            level_reductions_bits: vec![1; num_vars],
+            pow_bits: 0,
+        }
+    }
+}
+
+// Root of the Reed-Solomon encoding of the multilinear polynomial, leaves grouped as FRI's first level
+#[derive(Clone, Debug)]
+pub struct BasefoldCommitment<F: PrimeField, H: Hasher_<F>> {
+    pub num_vars: usize,
+    pub root: Vec<H::Hash>,
+}
+
+pub struct BasefoldProverData<F: PrimeField, H: Hasher_<F>> {
+    pub commitment: BasefoldCommitment<F, H>,
+    pub(crate) evaluations: Vec<F>, // over the hypercube, bit i of the index is variable i
+    pub(crate) coeffs: Vec<F>, // monomial coefficients, coefficient i is that of the univariate encoding's x^i
+    pub(crate) tree: MerkleTree<F, H>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BasefoldProof<F: PrimeField, H: Hasher_<F>> {
+    pub sumcheck_messages: Vec<[F; 3]>, // round polynomial at 0, 1 and 2, one per variable
+    pub level_roots: Vec<Vec<H::Hash>>, // folded oracles, the first level is the commitment
+    pub final_value: F, // the encoding folded down to a constant, f at the sumcheck challenges
+    pub query_eval_proofs: Vec<QueryEvalProofs<F, H>>,
+}
@@ -0,0 +1,182 @@
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use tracing::{debug, debug_span};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::verifier::calcualate_next_level_value;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::verify_multi_proof;
+
+use super::prover::observe_basefold_statement;
+use super::sumcheck::{eq_eval, interpolate_round};
+use super::types::{BasefoldCommitment, BasefoldConfig, BasefoldProof};
+
+// Checks that the polynomial committed in `commitment` evaluates to `value` at `point`
+pub fn basefold_verify<F: PrimeField + From<i32>, H: Hasher_<F>>(commitment: &BasefoldCommitment<F, H>, point: &[F], value: F, proof: BasefoldProof<F, H>, config: &BasefoldConfig, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    let num_vars = commitment.num_vars;
+    if num_vars == 0 || num_vars > 31 || point.len() != num_vars
+        || config.fri_config(num_vars).validate::<F>((1u32 << num_vars) - 1).is_err() {
+        return false;
+    }
+    if proof.sumcheck_messages.len() != num_vars || proof.level_roots.len() != num_vars - 1 || proof.query_eval_proofs.len() != num_vars {
+        return false;
+    }
+
+    let _verify_span = debug_span!("basefold_verify", num_vars).entered();
+    observe_basefold_statement(transcript, config, commitment, point, value);
+
+    // Sumcheck, with the folded oracles committed between rounds
+    let mut claim = value;
+    let mut challenges = Vec::with_capacity(num_vars);
+    for (round, message) in proof.sumcheck_messages.iter().enumerate() {
+        if message[0] + message[1] != claim {
+            debug!(round, "sumcheck round does not match the claim");
+            return false;
+        }
+        transcript.observe_elements(b"sumcheck round", message);
+        let r: F = transcript.get_challenge(b"alpha");
+        claim = interpolate_round(message, r);
+        challenges.push(r);
+        if round < num_vars - 1 {
+            let root_field: Vec<F> = proof.level_roots[round].iter().map(|r| H::hash_as_field(*r)).collect();
+            transcript.observe_elements(b"merkle_root", &root_field);
+        }
+    }
+    transcript.observe_element(b"final value", &proof.final_value);
+    // the encoding folds down to f(r), the sumcheck ends on f(r) eq(point, r)
+    if claim != proof.final_value * eq_eval(point, &challenges) {
+        debug!("final sumcheck claim does not match the folded value");
+        return false;
+    }
+    let queries = transcript.get_challenge_indices(b"challenge indices", config.num_query as usize);
+
+    // Folding consistency, as in FRI with a 1 bit reduction per level
+    let domain_size = (1usize << num_vars) * config.blow_up_factor as usize;
+    let roots: Vec<&Vec<H::Hash>> = std::iter::once(&commitment.root).chain(proof.level_roots.iter()).collect();
+    for (l, (root, eval_proof)) in roots.iter().zip(proof.query_eval_proofs.iter()).enumerate() {
+        if !verify_multi_proof::<F, H>(root, (domain_size >> l).trailing_zeros() - 1, &eval_proof.merkle_proof) {
+            return false;
+        }
+    }
+    for q_start in queries {
+        let q_init = q_start as usize % (domain_size / 2);
+        let mut next_level_value = F::ZERO;
+        let mut offset = F::GENERATOR;
+        for (l, eval_proof) in proof.query_eval_proofs.iter().enumerate() {
+            let level_domain_size = domain_size >> l;
+            let half = level_domain_size / 2;
+            let q = q_init % level_domain_size;
+            let evaluations = match eval_proof.merkle_proof.leaf(q % half) {
+                Some(leaf) if leaf.len() == 2 => leaf.clone(),
+                _ => return false,
+            };
+            if l != 0 && next_level_value != evaluations[q / half] {
+                debug!(q, level = l, "consistency check failed with the previous level");
+                return false;
+            }
+            let eval_domain = GeneralEvaluationDomain::<F>::new(level_domain_size).unwrap().get_coset(offset).expect("Error in getting coset");
+            next_level_value = calcualate_next_level_value(vec![q % half, q % half + half], evaluations, challenges[l], eval_domain);
+            offset = offset.square();
+        }
+        if next_level_value != proof.final_value {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::UniformRand;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::basefold::prover::{basefold_commit, basefold_open, multilinear_coefficients};
+    use crate::basefold::types::{BasefoldCommitment, BasefoldConfig, BasefoldProof};
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::utils::new_fri_transcript;
+    use crate::hashing::hasher::Sha256_;
+    use super::basefold_verify;
+
+    const NUM_VARS: usize = 6;
+
+    fn config() -> BasefoldConfig {
+        BasefoldConfig { num_query: 8, blow_up_factor: 4, merkle_cap_bits: 1 }
+    }
+
+    fn random_polynomial(rng: &mut StdRng) -> DenseMultilinearExtension<Fq> {
+        DenseMultilinearExtension::rand(NUM_VARS, rng)
+    }
+
+    fn verify(commitment: &BasefoldCommitment<Fq, Sha256_<Fq>>, point: &[Fq], value: Fq, proof: BasefoldProof<Fq, Sha256_<Fq>>) -> bool {
+        basefold_verify(commitment, point, value, proof, &config(), &mut new_fri_transcript(b"basefold"))
+    }
+
+    #[test]
+    fn test_multilinear_coefficients() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let polynomial = random_polynomial(&mut rng);
+        let coeffs = multilinear_coefficients(&polynomial.to_evaluations());
+        let point: Vec<Fq> = (0..NUM_VARS).map(|_| Fq::rand(&mut rng)).collect();
+        // sum c_b prod_{i in b} x_i
+        let expected: Fq = coeffs.iter().enumerate()
+            .map(|(b, c)| *c * (0..NUM_VARS).filter(|i| b >> i & 1 == 1).map(|i| point[i]).product::<Fq>())
+            .sum();
+        assert_eq!(polynomial.evaluate(&point).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_basefold_open() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let polynomial = random_polynomial(&mut rng);
+        let data = basefold_commit::<Fq, Sha256_<Fq>>(&polynomial, &config());
+        for _ in 0..3 {
+            let point: Vec<Fq> = (0..NUM_VARS).map(|_| Fq::rand(&mut rng)).collect();
+            let (value, proof) = basefold_open(&data, &point, &config(), &mut new_fri_transcript(b"basefold"));
+            assert_eq!(value, polynomial.evaluate(&point).unwrap());
+            assert!(verify(&data.commitment, &point, value, proof));
+        }
+
+        // hypercube points and the zero polynomial
+        let zero = DenseMultilinearExtension::from_evaluations_vec(NUM_VARS, vec![Fq::from(0u64); 1 << NUM_VARS]);
+        let data = basefold_commit::<Fq, Sha256_<Fq>>(&zero, &config());
+        let point = vec![Fq::from(1u64); NUM_VARS];
+        let (value, proof) = basefold_open(&data, &point, &config(), &mut new_fri_transcript(b"basefold"));
+        assert!(verify(&data.commitment, &point, value, proof));
+    }
+
+    #[test]
+    fn test_basefold_soundness() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let polynomial = random_polynomial(&mut rng);
+        let data = basefold_commit::<Fq, Sha256_<Fq>>(&polynomial, &config());
+        let point: Vec<Fq> = (0..NUM_VARS).map(|_| Fq::rand(&mut rng)).collect();
+        let (value, proof) = basefold_open(&data, &point, &config(), &mut new_fri_transcript(b"basefold"));
+
+        // wrong value, other point
+        assert!(!verify(&data.commitment, &point, value + Fq::from(1u64), proof.clone()));
+        let mut other_point = point.clone();
+        other_point[3] += Fq::from(1u64);
+        assert!(!verify(&data.commitment, &other_point, value, proof.clone()));
+
+        // sumcheck message shifted consistently with the claim
+        let mut bad_message = proof.clone();
+        bad_message.sumcheck_messages[0][0] += Fq::from(1u64);
+        bad_message.sumcheck_messages[0][1] -= Fq::from(1u64);
+        assert!(!verify(&data.commitment, &point, value, bad_message));
+
+        let mut bad_final = proof.clone();
+        bad_final.final_value += Fq::from(1u64);
+        assert!(!verify(&data.commitment, &point, value, bad_final));
+
+        let mut bad_leaf = proof.clone();
+        bad_leaf.query_eval_proofs[2].merkle_proof.leaves[0][1] += Fq::from(1u64);
+        assert!(!verify(&data.commitment, &point, value, bad_leaf));
+
+        // an honest opening of another polynomial does not open this commitment
+        let other = basefold_commit::<Fq, Sha256_<Fq>>(&random_polynomial(&mut rng), &config());
+        let (other_value, other_proof) = basefold_open(&other, &point, &config(), &mut new_fri_transcript(b"basefold"));
+        assert!(verify(&other.commitment, &point, other_value, other_proof.clone()));
+        assert!(!verify(&data.commitment, &point, other_value, other_proof));
+    }
+}
@@ -0,0 +1,116 @@
+use super::point::{subgroup_generator, CircleField, CirclePoint};
+
+// x coordinates of initial * step^j, step generating the subgroup of order 2^log_size.
+// Elements j and j + size/2 are opposite (x and -x) and share the same square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineDomain<F: CircleField> {
+    pub initial: CirclePoint<F>,
+    pub log_size: u32,
+}
+
+impl<F: CircleField> LineDomain<F> {
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn point(&self, j: usize) -> CirclePoint<F> {
+        self.initial * subgroup_generator::<F>(self.log_size).pow(j as u64)
+    }
+
+    pub fn element(&self, j: usize) -> F {
+        self.point(j).x
+    }
+
+    pub fn points(&self) -> Vec<CirclePoint<F>> {
+        let step = subgroup_generator::<F>(self.log_size);
+        let mut point = self.initial;
+        (0..self.size()).map(|_| {
+            let current = point;
+            point = point * step;
+            current
+        }).collect()
+    }
+
+    pub fn elements(&self) -> Vec<F> {
+        self.points().iter().map(|p| p.x).collect()
+    }
+
+    // Image under the squaring map, element j is the square of elements j and j + size/2
+    pub fn double(&self) -> Self {
+        LineDomain { initial: self.initial.double(), log_size: self.log_size - 1 }
+    }
+}
+
+// Twin coset Q<s> u Q^-1<s>, s generating the subgroup of order 2^(log_size-1).
+// Point j < size/2 is Q s^j, point j + size/2 its conjugate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircleDomain<F: CircleField> {
+    pub half_coset_initial: CirclePoint<F>,
+    pub log_size: u32,
+}
+
+impl<F: CircleField> CircleDomain<F> {
+    // Standard position coset of size 2^log_size, the odd powers of the generator of order 2^(log_size+1)
+    pub fn standard(log_size: u32) -> Self {
+        assert!(log_size >= 1, "Circle domains have at least 2 points");
+        CircleDomain { half_coset_initial: subgroup_generator::<F>(log_size + 1), log_size }
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn points(&self) -> Vec<CirclePoint<F>> {
+        let half: Vec<CirclePoint<F>> = self.line_domain().points();
+        half.iter().copied().chain(half.iter().map(|p| p.conjugate())).collect()
+    }
+
+    pub fn point(&self, j: usize) -> CirclePoint<F> {
+        let half = self.size() / 2;
+        let point = self.line_domain().point(j % half);
+        if j < half { point } else { point.conjugate() }
+    }
+
+    // x coordinates of the domain, conjugate points sharing the same one
+    pub fn line_domain(&self) -> LineDomain<F> {
+        LineDomain { initial: self.half_coset_initial, log_size: self.log_size - 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use ark_ff::Zero;
+
+    use crate::circle::point::squaring_map;
+    use crate::fields::mersenne31_field::M31;
+    use super::CircleDomain;
+
+    #[test]
+    fn test_domains() {
+        let domain = CircleDomain::<M31>::standard(5);
+        let points = domain.points();
+        assert_eq!(points.len(), 32);
+        assert!(points.iter().all(|p| p.is_on_circle() && !p.y.is_zero()));
+        assert_eq!(points.iter().map(|p| (p.x, p.y)).collect::<HashSet<_>>().len(), 32);
+        for j in 0..16 {
+            assert_eq!(domain.point(j), points[j]);
+            assert_eq!(points[j + 16], points[j].conjugate());
+        }
+
+        // closed under the antipode, element j + size/2 of a line domain is -x_j and squares to the same element
+        let mut line = domain.line_domain();
+        while line.log_size > 0 {
+            let elements = line.elements();
+            let doubled = line.double().elements();
+            let half = line.size() / 2;
+            for j in 0..half {
+                assert_eq!(elements[j + half], -elements[j]);
+                assert!(!elements[j].is_zero());
+                assert_eq!(squaring_map(elements[j]), doubled[j]);
+            }
+            line = line.double();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use ark_ff::{Field, PrimeField};
+
+use super::domain::{CircleDomain, LineDomain};
+use super::point::{squaring_map, CircleField, CirclePoint};
+
+// Circle polynomials are written in the basis y^j0 * x^j1 * pi(x)^j2 * pi(pi(x))^j3 ..., coefficient j
+// multiplying the monomial given by the bits of j. A polynomial with 2^k coefficients is determined by its
+// evaluations over a circle domain of size 2^k.
+
+// Even and odd parts at t of a function from its values at t and -t (or at P and its conjugate, with t = y):
+// f = even + t * odd
+pub fn inverse_butterfly<F: PrimeField>(a: F, b: F, t: F) -> (F, F) {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    ((a + b) * two_inv, (a - b) * two_inv * t.inverse().expect("Twiddle is zero"))
+}
+
+// One FRI fold, even + challenge * odd, for values and challenge in an extension of the twiddle's field
+pub fn fold_pair<F: PrimeField, E: Field<BasePrimeField = F>>(a: E, b: E, t: F, challenge: E) -> E {
+    let two_inv = E::from_base_prime_field(F::from(2u64).inverse().unwrap());
+    let t_inv = E::from_base_prime_field(t.inverse().expect("Twiddle is zero"));
+    (a + b) * two_inv + challenge * (a - b) * two_inv * t_inv
+}
+
+fn interleave<F: PrimeField>(even: Vec<F>, odd: Vec<F>) -> Vec<F> {
+    even.into_iter().zip(odd).flat_map(|(e, o)| [e, o]).collect()
+}
+
+fn split<F: PrimeField>(coeffs: &[F]) -> (Vec<F>, Vec<F>) {
+    (coeffs.iter().step_by(2).copied().collect(), coeffs.iter().skip(1).step_by(2).copied().collect())
+}
+
+pub fn line_interpolate<F: CircleField>(domain: &LineDomain<F>, evaluations: &[F]) -> Vec<F> {
+    assert_eq!(evaluations.len(), domain.size());
+    if evaluations.len() == 1 {
+        return evaluations.to_vec();
+    }
+    let half = evaluations.len() / 2;
+    let (even, odd): (Vec<F>, Vec<F>) = domain.elements()[..half].iter().enumerate()
+        .map(|(j, x)| inverse_butterfly(evaluations[j], evaluations[j + half], *x))
+        .unzip();
+    let doubled = domain.double();
+    interleave(line_interpolate(&doubled, &even), line_interpolate(&doubled, &odd))
+}
+
+pub fn line_evaluate<F: CircleField>(domain: &LineDomain<F>, coeffs: &[F]) -> Vec<F> {
+    assert_eq!(coeffs.len(), domain.size());
+    if coeffs.len() == 1 {
+        return coeffs.to_vec();
+    }
+    let (even, odd) = split(coeffs);
+    let doubled = domain.double();
+    let (even, odd) = (line_evaluate(&doubled, &even), line_evaluate(&doubled, &odd));
+    let xs = domain.elements();
+    let half = coeffs.len() / 2;
+    let first = (0..half).map(|j| even[j] + xs[j] * odd[j]);
+    let second = (0..half).map(|j| even[j] - xs[j] * odd[j]);
+    first.chain(second).collect()
+}
+
+// Coefficients of the polynomial with `evaluations` over `domain`
+pub fn circle_interpolate<F: CircleField>(domain: &CircleDomain<F>, evaluations: &[F]) -> Vec<F> {
+    assert_eq!(evaluations.len(), domain.size());
+    let half = evaluations.len() / 2;
+    let (even, odd): (Vec<F>, Vec<F>) = domain.points()[..half].iter().enumerate()
+        .map(|(j, p)| inverse_butterfly(evaluations[j], evaluations[j + half], p.y))
+        .unzip();
+    let line = domain.line_domain();
+    interleave(line_interpolate(&line, &even), line_interpolate(&line, &odd))
+}
+
+// Evaluations over `domain` of the polynomial with `coeffs`, at most domain.size() of them
+pub fn circle_evaluate<F: CircleField>(domain: &CircleDomain<F>, coeffs: &[F]) -> Vec<F> {
+    assert!(coeffs.len() <= domain.size(), "Too many coefficients for the domain");
+    let mut padded = coeffs.to_vec();
+    padded.resize(domain.size(), F::ZERO);
+    let (even, odd) = split(&padded);
+    let line = domain.line_domain();
+    let (even, odd) = (line_evaluate(&line, &even), line_evaluate(&line, &odd));
+    let points = domain.points();
+    let half = domain.size() / 2;
+    let first = (0..half).map(|j| even[j] + points[j].y * odd[j]);
+    let second = (0..half).map(|j| even[j] - points[j].y * odd[j]);
+    first.chain(second).collect()
+}
+
+// Extends evaluations over the standard domain of their size to the standard domain 2^log_blow_up times larger
+pub fn circle_lde<F: CircleField>(evaluations: &[F], log_blow_up: u32) -> Vec<F> {
+    assert!(evaluations.len().is_power_of_two() && evaluations.len() >= 2, "Evaluations must cover a circle domain");
+    let log_size = evaluations.len().trailing_zeros();
+    let coeffs = circle_interpolate(&CircleDomain::standard(log_size), evaluations);
+    circle_evaluate(&CircleDomain::standard(log_size + log_blow_up), &coeffs)
+}
+
+// Value of the polynomial at any point of the circle
+pub fn evaluate_at_point<F: PrimeField>(coeffs: &[F], point: CirclePoint<F>) -> F {
+    let num_bits = coeffs.len().next_power_of_two().trailing_zeros() as usize;
+    // y, x, pi(x), pi(pi(x)), ...
+    let mut factors = vec![point.y];
+    let mut x = point.x;
+    for _ in 1..num_bits {
+        factors.push(x);
+        x = squaring_map(x);
+    }
+    coeffs.iter().enumerate().map(|(j, c)| {
+        (0..num_bits).filter(|bit| j >> bit & 1 == 1).fold(*c, |acc, bit| acc * factors[bit])
+    }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{UniformRand, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::circle::domain::CircleDomain;
+    use crate::fields::mersenne31_field::M31;
+    use super::{circle_evaluate, circle_interpolate, circle_lde, evaluate_at_point};
+
+    #[test]
+    fn test_circle_fft() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for log_size in 1..7 {
+            let domain = CircleDomain::<M31>::standard(log_size);
+            let coeffs: Vec<M31> = (0..domain.size()).map(|_| M31::rand(&mut rng)).collect();
+            let evaluations = circle_evaluate(&domain, &coeffs);
+            for (point, value) in domain.points().iter().zip(evaluations.iter()) {
+                assert_eq!(evaluate_at_point(&coeffs, *point), *value);
+            }
+            assert_eq!(circle_interpolate(&domain, &evaluations), coeffs);
+        }
+    }
+
+    #[test]
+    fn test_circle_lde() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let trace: Vec<M31> = (0..16).map(|_| M31::rand(&mut rng)).collect();
+        let coeffs = circle_interpolate(&CircleDomain::standard(4), &trace);
+        let lde = circle_lde(&trace, 2);
+        assert_eq!(lde.len(), 64);
+        let lde_domain = CircleDomain::<M31>::standard(6);
+        for (point, value) in lde_domain.points().iter().zip(lde.iter()) {
+            assert_eq!(evaluate_at_point(&coeffs, *point), *value);
+        }
+        // only 16 coefficients are non zero on the larger domain
+        assert!(circle_interpolate(&lde_domain, &lde)[16..].iter().all(|c| c.is_zero()));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod point;
+pub mod domain;
+pub mod fft;
+pub mod types;
+pub mod prover;
+pub mod verifier;
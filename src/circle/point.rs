@@ -0,0 +1,118 @@
+use std::ops::Mul;
+
+use ark_ff::{Field, PrimeField};
+
+use crate::fields::mersenne31_field::{M31, QM31};
+
+// Fields whose circle group x^2 + y^2 = 1 has a large two-adic subgroup
+pub trait CircleField: PrimeField {
+    // log2 of the order of circle_generator
+    const LOG_CIRCLE_ORDER: u32;
+
+    // folding and out of domain challenges are drawn from this extension
+    type Extension: Field<BasePrimeField = Self>;
+
+    fn circle_generator() -> CirclePoint<Self>;
+}
+
+impl CircleField for M31 {
+    // the whole circle group, of order p + 1
+    const LOG_CIRCLE_ORDER: u32 = 31;
+
+    type Extension = QM31;
+
+    fn circle_generator() -> CirclePoint<Self> {
+        CirclePoint { x: M31::from(2u64), y: M31::from(1268011823u64) }
+    }
+}
+
+// Point of the circle group, written multiplicatively: (x1, y1) * (x2, y2) = (x1 x2 - y1 y2, x1 y2 + x2 y1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CirclePoint<F: PrimeField> {
+    pub x: F,
+    pub y: F,
+}
+
+impl<F: PrimeField> Mul for CirclePoint<F> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        CirclePoint { x: self.x * other.x - self.y * other.y, y: self.x * other.y + other.x * self.y }
+    }
+}
+
+impl<F: PrimeField> CirclePoint<F> {
+    pub fn identity() -> Self {
+        CirclePoint { x: F::ONE, y: F::ZERO }
+    }
+
+    pub fn is_on_circle(&self) -> bool {
+        self.x.square() + self.y.square() == F::ONE
+    }
+
+    // x of the double is 2x^2 - 1, the squaring map pi
+    pub fn double(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = *self;
+        let mut result = Self::identity();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base.double();
+            exp >>= 1;
+        }
+        result
+    }
+
+    // group inverse
+    pub fn conjugate(&self) -> Self {
+        CirclePoint { x: self.x, y: -self.y }
+    }
+
+    // the point times (-1, 0)
+    pub fn antipode(&self) -> Self {
+        CirclePoint { x: -self.x, y: -self.y }
+    }
+}
+
+// Generator of the subgroup of order 2^log_order
+pub fn subgroup_generator<F: CircleField>(log_order: u32) -> CirclePoint<F> {
+    assert!(log_order <= F::LOG_CIRCLE_ORDER, "No circle subgroup of order 2^{}", log_order);
+    (log_order..F::LOG_CIRCLE_ORDER).fold(F::circle_generator(), |point, _| point.double())
+}
+
+// x coordinate of the double of a point with x coordinate `x`
+pub fn squaring_map<F: PrimeField>(x: F) -> F {
+    x.square().double() - F::ONE
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::mersenne31_field::M31;
+    use super::{subgroup_generator, squaring_map, CircleField, CirclePoint};
+
+    #[test]
+    fn test_circle_group() {
+        let g = M31::circle_generator();
+        assert!(g.is_on_circle());
+        // order exactly 2^31
+        assert_eq!(g.pow(1 << 31), CirclePoint::identity());
+        assert_eq!(g.pow(1 << 30), CirclePoint { x: -M31::from(1u64), y: M31::from(0u64) });
+
+        let a = g.pow(12345);
+        let b = g.pow(678);
+        assert!((a * b).is_on_circle());
+        assert_eq!(a * b, g.pow(12345 + 678));
+        assert_eq!(a * a.conjugate(), CirclePoint::identity());
+        assert_eq!(a.antipode(), a * g.pow(1 << 30));
+        assert_eq!(a.double().x, squaring_map(a.x));
+
+        let h = subgroup_generator::<M31>(5);
+        assert_eq!(h.pow(32), CirclePoint::identity());
+        assert_ne!(h.pow(16), CirclePoint::identity());
+    }
+}
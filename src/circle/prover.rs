@@ -0,0 +1,115 @@
+use ark_ff::Field;
+use tracing::{debug, debug_span, trace};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::prover::generate_query_eval_proofs;
+use crate::fri::utils::new_fri_transcript;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::MerkleTree;
+
+use super::domain::{CircleDomain, LineDomain};
+use super::fft::{circle_lde, fold_pair};
+use super::point::CircleField;
+use super::types::{CircleFriConfig, CircleFriProof, CIRCLE_DOMAIN_SEPARATOR};
+
+// Folds the values at the points j and j + n/2 (conjugates, y and -y) of a circle domain onto x_j
+pub fn fold_circle_pair<F: CircleField>(domain: &CircleDomain<F>, j: usize, a: F, b: F, alpha: F::Extension) -> F::Extension {
+    fold_pair(F::Extension::from_base_prime_field(a), F::Extension::from_base_prime_field(b), domain.point(j).y, alpha)
+}
+
+// Folds the values at x_j and -x_j (j + n/2) of a line domain onto pi(x_j), element j of the doubled domain
+pub fn fold_line_pair<F: CircleField>(domain: &LineDomain<F>, j: usize, a: F::Extension, b: F::Extension, beta: F::Extension) -> F::Extension {
+    fold_pair(a, b, domain.element(j), beta)
+}
+
+// Challenge from the extension, one base field challenge per coordinate
+pub fn get_extension_challenge<F: CircleField>(transcript: &mut impl TranscriptProtocol<F>, label: &'static [u8]) -> F::Extension {
+    let coordinates: Vec<F> = (0..F::Extension::extension_degree()).map(|_| transcript.get_challenge(label)).collect();
+    F::Extension::from_base_prime_field_elems(&coordinates).unwrap()
+}
+
+// Base field coordinates of extension values, as committed and observed
+pub fn extension_coordinates<F: CircleField>(values: &[F::Extension]) -> Vec<F> {
+    values.iter().flat_map(|value| value.to_base_prime_field_elements()).collect()
+}
+
+// Binds the config and size to the transcript before any commitment
+pub fn observe_circle_fri_statement<F: CircleField>(transcript: &mut impl TranscriptProtocol<F>, config: &CircleFriConfig, log_size: u32) {
+    transcript.observe_u64(b"num query", config.num_query as u64);
+    transcript.observe_u64(b"blow up factor", config.blow_up_factor as u64);
+    transcript.observe_u64(b"merkle cap bits", config.merkle_cap_bits as u64);
+    transcript.observe_u64(b"log size", log_size as u64);
+}
+
+// Leaf j holds the values at j and j + n/2, the pair folded together, as coordinates in F
+fn commit_pairs<F: CircleField, H: Hasher_<F>, E: Field<BasePrimeField = F>>(evaluations: &[E], merkle_cap_bits: u32, transcript: &mut impl TranscriptProtocol<F>) -> MerkleTree<F, H> {
+    let half = evaluations.len() / 2;
+    let mut tree = MerkleTree::<F, H>::new(merkle_cap_bits);
+    tree.insert((0..half).map(|j| {
+        [evaluations[j], evaluations[j + half]].iter().flat_map(|e| e.to_base_prime_field_elements()).collect()
+    }).collect()).expect("Tree is memory backed");
+    let root_field: Vec<F> = tree.compute_tree().expect("Tree is memory backed").iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"merkle_root", &root_field);
+    tree
+}
+
+pub fn generate_circle_fri_proof<F: CircleField, H: Hasher_<F>>(evaluations: &[F], config: &CircleFriConfig) -> CircleFriProof<F, H> {
+    let mut transcript = new_fri_transcript(CIRCLE_DOMAIN_SEPARATOR);
+    generate_circle_fri_proof_with_transcript(evaluations, config, &mut transcript)
+}
+
+// Proves that the LDE of `evaluations`, given over the standard circle domain of their size, has as many
+// coefficients as there are evaluations
+pub fn generate_circle_fri_proof_with_transcript<F: CircleField, H: Hasher_<F>>(evaluations: &[F], config: &CircleFriConfig, transcript: &mut impl TranscriptProtocol<F>) -> CircleFriProof<F, H> {
+    assert!(evaluations.len().is_power_of_two(), "Evaluations must cover a circle domain");
+    let log_size = evaluations.len().trailing_zeros();
+    if let Err(e) = config.validate::<F>(log_size) {
+        panic!("Invalid Circle FRI config: {}", e);
+    }
+    let _prove_span = debug_span!("circle_fri_prove", log_size, num_query = config.num_query).entered();
+    observe_circle_fri_statement(transcript, config, log_size);
+
+    let log_blow_up = config.blow_up_factor.trailing_zeros();
+    let lde_span = debug_span!("lde", log_size, log_blow_up).entered();
+    let lde = circle_lde(evaluations, log_blow_up);
+    lde_span.exit();
+    prove(&lde, log_size, config, transcript)
+}
+
+// Commit and query phases for `lde`, evaluations over the standard domain of their size
+pub(super) fn prove<F: CircleField, H: Hasher_<F>>(lde: &[F], log_size: u32, config: &CircleFriConfig, transcript: &mut impl TranscriptProtocol<F>) -> CircleFriProof<F, H> {
+    let domain = CircleDomain::<F>::standard(lde.len().trailing_zeros());
+
+    // First fold, on y, into the extension
+    let mut trees = vec![commit_pairs::<F, H, F>(lde, config.merkle_cap_bits, transcript)];
+    let alpha = get_extension_challenge(transcript, b"alpha");
+    let half = domain.size() / 2;
+    let mut line = domain.line_domain();
+    let mut current: Vec<F::Extension> = (0..half).map(|j| fold_circle_pair(&domain, j, lde[j], lde[j + half], alpha)).collect();
+
+    // Then on x, down to a constant
+    for level in 1..log_size {
+        let _fold_span = debug_span!("fold", level, domain_size = current.len()).entered();
+        trees.push(commit_pairs::<F, H, F::Extension>(&current, config.merkle_cap_bits, transcript));
+        let beta = get_extension_challenge(transcript, b"alpha");
+        let half = current.len() / 2;
+        let xs = line.elements();
+        current = (0..half).map(|j| fold_pair(current[j], current[j + half], xs[j], beta)).collect();
+        line = line.double();
+    }
+    // constant for a low degree lde
+    let final_value = current[0];
+    transcript.observe_elements(b"final value", &extension_coordinates::<F>(&[final_value]));
+    debug!(num_levels = trees.len(), "reached final value");
+
+    let _query_span = debug_span!("query_phase", num_query = config.num_query).entered();
+    let queries = transcript.get_challenge_indices(b"challenge indices", config.num_query as usize);
+    trace!(?queries, "sampled queries");
+    let query_eval_proofs = generate_query_eval_proofs::<F, H>(queries, domain.size(), vec![1; log_size as usize], &trees);
+
+    CircleFriProof {
+        level_roots: trees.iter().map(|tree| tree.root_cap.clone().unwrap()).collect(),
+        final_value,
+        query_eval_proofs,
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::fri::types::QueryEvalProofs;
+use crate::fri::utils::FriConfigError;
+use crate::hashing::hasher::Hasher_;
+
+use super::point::CircleField;
+
+// Domain separator used by generate_circle_fri_proof / verify_circle_fri_proof
+pub const CIRCLE_DOMAIN_SEPARATOR: &[u8] = b"fri-commitment-circle";
+
+// Circle FRI folds one variable per level, down to a constant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircleFriConfig {
+    pub num_query: u32,
+    pub blow_up_factor: u32,
+    pub merkle_cap_bits: u32,
+}
+
+// Reasons a CircleFriConfig cannot be used, on top of those shared with FRI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircleFriConfigError {
+    Fri(FriConfigError),
+    TooFewCoefficients(u64), // folds at least once, so needs at least 2 coefficients
+}
+
+impl From<FriConfigError> for CircleFriConfigError {
+    fn from(e: FriConfigError) -> Self {
+        CircleFriConfigError::Fri(e)
+    }
+}
+
+impl fmt::Display for CircleFriConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircleFriConfigError::Fri(e) => e.fmt(f),
+            CircleFriConfigError::TooFewCoefficients(n) => write!(f, "{} coefficients leave nothing to fold, at least 2 are needed", n),
+        }
+    }
+}
+
+impl std::error::Error for CircleFriConfigError {}
+
+impl CircleFriConfig {
+    // Validates the config for polynomials with 2^log_size coefficients
+    pub fn validate<F: CircleField>(&self, log_size: u32) -> Result<(), CircleFriConfigError> {
+        if self.num_query == 0 {
+            return Err(FriConfigError::NoQueries.into());
+        }
+        if !self.blow_up_factor.is_power_of_two() || self.blow_up_factor < 2 {
+            return Err(FriConfigError::InvalidBlowUpFactor(self.blow_up_factor).into());
+        }
+        if log_size == 0 {
+            return Err(CircleFriConfigError::TooFewCoefficients(1));
+        }
+        let log_blow_up = self.blow_up_factor.trailing_zeros();
+        // the domain is a standard coset, half of a subgroup twice its size
+        let domain_bits = log_size.saturating_add(log_blow_up);
+        let max_bits = (F::LOG_CIRCLE_ORDER - 1).min(31);
+        if domain_bits > max_bits {
+            return Err(FriConfigError::DomainTooLarge { domain_bits, max_bits }.into());
+        }
+        // the last committed level has blow_up leaves
+        if log_blow_up < self.merkle_cap_bits.saturating_add(1) {
+            return Err(FriConfigError::MerkleCapTooLarge {
+                merkle_cap_bits: self.merkle_cap_bits,
+                final_domain_size: self.blow_up_factor as u64,
+            }.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircleFriProof<F: CircleField, H: Hasher_<F>> {
+    // the circle level (leaf j holds points j and its conjugate j + n/2), then the line levels
+    // (leaf j holds x_j and -x_j at j + n/2). Line level values are in F::Extension, stored as their
+    // base field coordinates.
+    pub level_roots: Vec<Vec<H::Hash>>,
+    pub final_value: F::Extension,
+    pub query_eval_proofs: Vec<QueryEvalProofs<F, H>>,
+}
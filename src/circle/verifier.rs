@@ -0,0 +1,165 @@
+use ark_ff::Field;
+use tracing::{debug, debug_span};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::utils::new_fri_transcript;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::verify_multi_proof;
+
+use super::domain::CircleDomain;
+use super::point::CircleField;
+use super::prover::{extension_coordinates, fold_circle_pair, fold_line_pair, get_extension_challenge, observe_circle_fri_statement};
+use super::types::{CircleFriConfig, CircleFriProof, CIRCLE_DOMAIN_SEPARATOR};
+
+pub fn verify_circle_fri_proof<F: CircleField, H: Hasher_<F>>(config: &CircleFriConfig, log_size: u32, proof: CircleFriProof<F, H>) -> bool {
+    let mut transcript = new_fri_transcript(CIRCLE_DOMAIN_SEPARATOR);
+    verify_circle_fri_proof_with_transcript(config, log_size, proof, &mut transcript)
+}
+
+// Verifies a proof that the committed evaluations are those of a polynomial with 2^log_size coefficients
+pub fn verify_circle_fri_proof_with_transcript<F: CircleField, H: Hasher_<F>>(config: &CircleFriConfig, log_size: u32, proof: CircleFriProof<F, H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    if config.validate::<F>(log_size).is_err() {
+        return false;
+    }
+    let num_levels = log_size as usize;
+    if proof.level_roots.len() != num_levels || proof.query_eval_proofs.len() != num_levels {
+        return false;
+    }
+    let _verify_span = debug_span!("circle_fri_verify", log_size, num_query = config.num_query).entered();
+    observe_circle_fri_statement(transcript, config, log_size);
+
+    let mut challenges: Vec<F::Extension> = Vec::with_capacity(num_levels);
+    for root in proof.level_roots.iter() {
+        let root_field: Vec<F> = root.iter().map(|r| H::hash_as_field(*r)).collect();
+        transcript.observe_elements(b"merkle_root", &root_field);
+        challenges.push(get_extension_challenge(transcript, b"alpha"));
+    }
+    transcript.observe_elements(b"final value", &extension_coordinates::<F>(&[proof.final_value]));
+    let queries = transcript.get_challenge_indices(b"challenge indices", config.num_query as usize);
+
+    let domain = CircleDomain::<F>::standard(log_size + config.blow_up_factor.trailing_zeros());
+    for (l, (root, eval_proof)) in proof.level_roots.iter().zip(proof.query_eval_proofs.iter()).enumerate() {
+        if !verify_multi_proof::<F, H>(root, (domain.size() >> l).trailing_zeros() - 1, &eval_proof.merkle_proof) {
+            debug!(level = l, "invalid level opening");
+            return false;
+        }
+    }
+
+    // the circle level holds base field values, the line levels extension coordinates
+    let extension_degree = F::Extension::extension_degree() as usize;
+    for q_start in queries {
+        let _query_span = debug_span!("verify_query", q_start).entered();
+        let q_init = q_start as usize % (domain.size() / 2);
+        let mut line = domain.line_domain();
+        let mut next_level_value = F::Extension::ZERO;
+        for (l, eval_proof) in proof.query_eval_proofs.iter().enumerate() {
+            let half = (domain.size() >> l) / 2;
+            let q = q_init % (2 * half);
+            let leaf_size = if l == 0 { 2 } else { 2 * extension_degree };
+            let leaf = match eval_proof.merkle_proof.leaf(q % half) {
+                Some(leaf) if leaf.len() == leaf_size => leaf,
+                _ => return false,
+            };
+            if l == 0 {
+                next_level_value = fold_circle_pair(&domain, q % half, leaf[0], leaf[1], challenges[0]);
+            } else {
+                let evaluations: Vec<F::Extension> = leaf.chunks(extension_degree)
+                    .map(|coordinates| F::Extension::from_base_prime_field_elems(coordinates).unwrap())
+                    .collect();
+                if next_level_value != evaluations[q / half] {
+                    debug!(q, level = l, "consistency check failed with the previous level");
+                    return false;
+                }
+                next_level_value = fold_line_pair(&line, q % half, evaluations[0], evaluations[1], challenges[l]);
+                line = line.double();
+            }
+        }
+        if next_level_value != proof.final_value {
+            debug!(q_start, "last fold does not match the final value");
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{Field, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::circle::prover::{generate_circle_fri_proof, prove};
+    use crate::circle::types::{CircleFriConfig, CircleFriConfigError, CIRCLE_DOMAIN_SEPARATOR};
+    use crate::fields::mersenne31_field::{M31, QM31};
+    use crate::fri::utils::new_fri_transcript;
+    use crate::hashing::hasher::Sha256_;
+    use super::verify_circle_fri_proof;
+
+    fn config() -> CircleFriConfig {
+        CircleFriConfig { num_query: 10, blow_up_factor: 4, merkle_cap_bits: 1 }
+    }
+
+    fn random_evaluations(rng: &mut StdRng, n: usize) -> Vec<M31> {
+        (0..n).map(|_| M31::rand(rng)).collect()
+    }
+
+    #[test]
+    fn test_circle_fri() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for log_size in 1..8 {
+            let evaluations = random_evaluations(&mut rng, 1 << log_size);
+            let proof = generate_circle_fri_proof::<M31, Sha256_<M31>>(&evaluations, &config());
+            assert_eq!(proof.level_roots.len(), log_size);
+            assert!(verify_circle_fri_proof(&config(), log_size as u32, proof.clone()));
+            // wrong size
+            assert!(!verify_circle_fri_proof(&config(), log_size as u32 + 1, proof));
+        }
+    }
+
+    #[test]
+    fn test_circle_fri_extension() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let evaluations = random_evaluations(&mut rng, 16);
+        let proof = generate_circle_fri_proof::<M31, Sha256_<M31>>(&evaluations, &config());
+        // folds leave the base field after the first challenge
+        assert!(proof.final_value.to_base_prime_field_elements().skip(1).any(|c| c != M31::from(0u64)));
+        assert_eq!(proof.query_eval_proofs[0].merkle_proof.leaves[0].len(), 2);
+        assert!(proof.query_eval_proofs[1..].iter().all(|p| p.merkle_proof.leaves.iter().all(|leaf| leaf.len() == 8)));
+
+        // a line level value changed in one coordinate
+        let mut bad_coordinate = proof.clone();
+        bad_coordinate.query_eval_proofs[2].merkle_proof.leaves[0][5] += M31::from(1u64);
+        assert!(!verify_circle_fri_proof(&config(), 4, bad_coordinate));
+        assert!(verify_circle_fri_proof(&config(), 4, proof));
+
+        assert_eq!(config().validate::<M31>(0), Err(CircleFriConfigError::TooFewCoefficients(1)));
+        assert!(!verify_circle_fri_proof(&config(), 0, generate_circle_fri_proof::<M31, Sha256_<M31>>(&evaluations[..2], &config())));
+    }
+
+    #[test]
+    fn test_circle_fri_tampered() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let evaluations = random_evaluations(&mut rng, 64);
+        let proof = generate_circle_fri_proof::<M31, Sha256_<M31>>(&evaluations, &config());
+
+        let mut bad_final = proof.clone();
+        bad_final.final_value += QM31::from(1u64);
+        assert!(!verify_circle_fri_proof(&config(), 6, bad_final));
+
+        let mut bad_root = proof.clone();
+        bad_root.level_roots.swap(1, 2);
+        assert!(!verify_circle_fri_proof(&config(), 6, bad_root));
+
+        let mut bad_config = config();
+        bad_config.merkle_cap_bits = 2;
+        assert!(!verify_circle_fri_proof(&bad_config, 6, proof));
+    }
+
+    #[test]
+    fn test_circle_fri_high_degree_rejected() {
+        // random values over the whole lde domain are far from any low degree polynomial
+        let mut rng = StdRng::seed_from_u64(2);
+        let lde = random_evaluations(&mut rng, 64 * 4);
+        let proof = prove::<M31, Sha256_<M31>>(&lde, 6, &config(), &mut new_fri_transcript(CIRCLE_DOMAIN_SEPARATOR));
+        assert!(!verify_circle_fri_proof(&config(), 6, proof));
+    }
+}
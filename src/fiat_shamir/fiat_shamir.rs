@@ -3,9 +3,23 @@ use merlin::Transcript;
 
 pub trait TranscriptProtocol<F: PrimeField> {
     fn observe_element(&mut self, label: &'static [u8], elem: &F);
-    fn observe_elements(&mut self, label: &'static [u8], elems: &Vec<F>);
+    fn observe_elements(&mut self, label: &'static [u8], elems: &[F]);
+    fn observe_u64(&mut self, label: &'static [u8], value: u64);
     fn get_challenge(&mut self, label: &'static [u8]) -> F;
     fn get_challenge_indices(&mut self, label: &'static [u8], n: usize) -> Vec<u32>;
+    // Smallest nonce passing check_pow from the current state, leaving the transcript unchanged
+    fn grind(&self, bits: u32) -> u64;
+
+    // Proof of work: absorbs `nonce` and accepts it if the next challenge index has `bits` trailing zeros
+    fn check_pow(&mut self, bits: u32, nonce: u64) -> bool {
+        self.observe_u64(b"pow nonce", nonce);
+        self.get_challenge_indices(b"pow", 1)[0].trailing_zeros() >= bits
+    }
+}
+
+// Tries nonces on copies of `transcript` until one passes check_pow
+pub fn grind_by_cloning<F: PrimeField, T: TranscriptProtocol<F> + Clone>(transcript: &T, bits: u32) -> u64 {
+    (0..u64::MAX).find(|nonce| transcript.clone().check_pow(bits, *nonce)).expect("No nonce found")
 }
 
 impl<F: PrimeField> TranscriptProtocol<F> for Transcript {
@@ -15,7 +29,7 @@ impl<F: PrimeField> TranscriptProtocol<F> for Transcript {
         self.append_message(label, &buffer);
     }
 
-    fn observe_elements(&mut self, label: &'static [u8], elems: &Vec<F>) {
+    fn observe_elements(&mut self, label: &'static [u8], elems: &[F]) {
         let mut buffer = vec![];
         for e in elems {
             e.serialize_uncompressed(&mut buffer).expect("Serialization Failed");
@@ -23,6 +37,10 @@ impl<F: PrimeField> TranscriptProtocol<F> for Transcript {
         self.append_message(label, &buffer);
     }
 
+    fn observe_u64(&mut self, label: &'static [u8], value: u64) {
+        self.append_u64(label, value);
+    }
+
     fn get_challenge(&mut self, label: &'static [u8]) -> F {
         let elem_byte_size = (F::MODULUS_BIT_SIZE/8) as usize;
         let mut buf = vec![0; elem_byte_size];
@@ -39,6 +57,10 @@ impl<F: PrimeField> TranscriptProtocol<F> for Transcript {
         }
         challenge_indices
     }
+
+    fn grind(&self, bits: u32) -> u64 {
+        grind_by_cloning::<F, _>(self, bits)
+    }
 }
 
 #[cfg(test)]
@@ -68,4 +90,20 @@ mod tests {
         assert_eq!(c1_p, c1_v);
         assert_eq!(c2_p, c2_v);
     }
+
+    #[test]
+    fn test_grinding() {
+        let mut transcript = Transcript::new(b"new");
+        TranscriptProtocol::<Fq>::observe_u64(&mut transcript, b"int", 3);
+        let nonce = TranscriptProtocol::<Fq>::grind(&transcript, 8);
+
+        let mut prover = transcript.clone();
+        assert!(TranscriptProtocol::<Fq>::check_pow(&mut prover, 8, nonce));
+        // about one nonce in 2^8 passes, the smallest one is found
+        assert!((0..nonce).all(|n| !TranscriptProtocol::<Fq>::check_pow(&mut transcript.clone(), 8, n)));
+        // the nonce is bound to the following challenges
+        let mut other = transcript.clone();
+        assert!(TranscriptProtocol::<Fq>::check_pow(&mut other, 0, nonce + 1));
+        assert_ne!(TranscriptProtocol::<Fq>::get_challenge(&mut prover, b"c"), TranscriptProtocol::<Fq>::get_challenge(&mut other, b"c"));
+    }
 }
@@ -0,0 +1,108 @@
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256};
+
+use super::fiat_shamir::{grind_by_cloning, TranscriptProtocol};
+
+// Hash chain transcript over Keccak256, cheap to replay in the EVM.
+// Every message updates the state as state = keccak256(state || label || data), field elements and
+// integers are absorbed as 32 byte big endian words (as abi.encodePacked does for uint256).
+// Challenges are squeezed as state = keccak256(state || label).
+#[derive(Clone, Debug)]
+pub struct KeccakTranscript {
+    state: [u8; 32],
+}
+
+impl KeccakTranscript {
+    pub fn new(label: &[u8]) -> Self {
+        KeccakTranscript { state: Keccak256::digest(label).into() }
+    }
+
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+
+    fn absorb(&mut self, label: &[u8], data: &[u8]) {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        hasher.update(label);
+        hasher.update(data);
+        self.state = hasher.finalize().into();
+    }
+}
+
+// 32 byte big endian encoding of a field element
+pub fn field_to_word<F: PrimeField>(elem: &F) -> [u8; 32] {
+    let bytes = elem.into_bigint().to_bytes_be();
+    assert!(bytes.len() <= 32, "Field elements must fit in a word");
+    let mut word = [0u8; 32];
+    word[32-bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+impl<F: PrimeField> TranscriptProtocol<F> for KeccakTranscript {
+    fn observe_element(&mut self, label: &'static [u8], elem: &F) {
+        self.absorb(label, &field_to_word(elem));
+    }
+
+    fn observe_elements(&mut self, label: &'static [u8], elems: &[F]) {
+        let buffer: Vec<u8> = elems.iter().flat_map(field_to_word).collect();
+        self.absorb(label, &buffer);
+    }
+
+    fn observe_u64(&mut self, label: &'static [u8], value: u64) {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        self.absorb(label, &word);
+    }
+
+    fn get_challenge(&mut self, label: &'static [u8]) -> F {
+        self.absorb(label, &[]);
+        F::from_be_bytes_mod_order(&self.state)
+    }
+
+    // One squeeze per index, taking the low 32 bits of the state
+    fn get_challenge_indices(&mut self, label: &'static [u8], n: usize) -> Vec<u32> {
+        (0..n).map(|_| {
+            self.absorb(label, &[]);
+            u32::from_be_bytes([self.state[28], self.state[29], self.state[30], self.state[31]])
+        }).collect()
+    }
+
+    fn grind(&self, bits: u32) -> u64 {
+        grind_by_cloning::<F, _>(self, bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+    use super::{field_to_word, KeccakTranscript};
+    use sha3::{Digest, Keccak256};
+
+    #[test]
+    fn test_keccak_transcript() {
+        let elements = vec![Fq::from(1), Fq::from(2), Fq::from(64)];
+        let run = |label: &[u8]| {
+            let mut transcript = KeccakTranscript::new(label);
+            transcript.observe_elements(b"elems", &elements);
+            TranscriptProtocol::<Fq>::observe_u64(&mut transcript, b"int", 7);
+            let c: Fq = transcript.get_challenge(b"challenge");
+            let indices = TranscriptProtocol::<Fq>::get_challenge_indices(&mut transcript, b"indices", 3);
+            (c, indices, transcript.state())
+        };
+        assert_eq!(run(b"a"), run(b"a"));
+        assert_ne!(run(b"a").0, run(b"b").0);
+
+        // state update matches keccak256(abi.encodePacked(state, "elems", uint256[] elems))
+        let mut transcript = KeccakTranscript::new(b"a");
+        let mut expected = Keccak256::new();
+        expected.update(Keccak256::digest(b"a"));
+        expected.update(b"elems");
+        for e in elements.iter() {
+            expected.update(field_to_word(e));
+        }
+        transcript.observe_elements(b"elems", &elements);
+        assert_eq!(transcript.state(), <[u8; 32]>::from(expected.finalize()));
+    }
+}
@@ -1 +1,6 @@
-pub mod fiat_shamir;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod fiat_shamir;
+pub mod keccak_transcript;
+pub mod random_coins;
+#[cfg(feature = "r1cs")]
+pub mod poseidon_transcript;
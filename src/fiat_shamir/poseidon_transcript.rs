@@ -0,0 +1,56 @@
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+
+use crate::hashing::poseidon::poseidon_config;
+use super::fiat_shamir::{grind_by_cloning, TranscriptProtocol};
+
+// Duplex Poseidon sponge transcript, replayed in-circuit by crate::r1cs::transcript::PoseidonTranscriptVar.
+// Labels are not absorbed, the order of FRI messages is fixed by the protocol.
+#[derive(Clone)]
+pub struct PoseidonTranscript {
+    sponge: PoseidonSponge<Fr>,
+}
+
+// Field elements absorbed for a domain separator
+pub fn domain_separator_elements(domain_separator: &[u8]) -> Vec<Fr> {
+    domain_separator.to_sponge_field_elements_as_vec::<Fr>()
+}
+
+impl PoseidonTranscript {
+    pub fn new(domain_separator: &[u8]) -> Self {
+        let mut sponge = PoseidonSponge::new(poseidon_config());
+        sponge.absorb(&domain_separator_elements(domain_separator));
+        PoseidonTranscript { sponge }
+    }
+}
+
+impl TranscriptProtocol<Fr> for PoseidonTranscript {
+    fn observe_element(&mut self, _label: &'static [u8], elem: &Fr) {
+        self.sponge.absorb(elem);
+    }
+
+    fn observe_elements(&mut self, _label: &'static [u8], elems: &[Fr]) {
+        self.sponge.absorb(&elems);
+    }
+
+    fn observe_u64(&mut self, _label: &'static [u8], value: u64) {
+        self.sponge.absorb(&Fr::from(value));
+    }
+
+    fn get_challenge(&mut self, _label: &'static [u8]) -> Fr {
+        self.sponge.squeeze_native_field_elements(1)[0]
+    }
+
+    // One squeezed element per index, taking its low 32 bits
+    fn get_challenge_indices(&mut self, _label: &'static [u8], n: usize) -> Vec<u32> {
+        self.sponge.squeeze_native_field_elements(n).iter()
+            .map(|e| e.into_bigint().0[0] as u32)
+            .collect()
+    }
+
+    fn grind(&self, bits: u32) -> u64 {
+        grind_by_cloning(self, bits)
+    }
+}
@@ -0,0 +1,41 @@
+use ark_ff::PrimeField;
+use rand::RngCore;
+
+use super::fiat_shamir::TranscriptProtocol;
+
+// Verifier randomness drawn from `rng` instead of hashed from the messages, for running a protocol
+// interactively. Observed messages are ignored and proof of work always passes, grinding only guards
+// Fiat-Shamir provers retrying for favourable queries.
+pub struct RandomCoins<R: RngCore> {
+    rng: R,
+}
+
+impl<R: RngCore> RandomCoins<R> {
+    pub fn new(rng: R) -> Self {
+        RandomCoins { rng }
+    }
+}
+
+impl<F: PrimeField, R: RngCore> TranscriptProtocol<F> for RandomCoins<R> {
+    fn observe_element(&mut self, _label: &'static [u8], _elem: &F) {}
+
+    fn observe_elements(&mut self, _label: &'static [u8], _elems: &[F]) {}
+
+    fn observe_u64(&mut self, _label: &'static [u8], _value: u64) {}
+
+    fn get_challenge(&mut self, _label: &'static [u8]) -> F {
+        F::rand(&mut self.rng)
+    }
+
+    fn get_challenge_indices(&mut self, _label: &'static [u8], n: usize) -> Vec<u32> {
+        (0..n).map(|_| self.rng.next_u32()).collect()
+    }
+
+    fn grind(&self, _bits: u32) -> u64 {
+        0
+    }
+
+    fn check_pow(&mut self, _bits: u32, _nonce: u64) -> bool {
+        true
+    }
+}
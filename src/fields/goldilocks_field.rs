@@ -1,3 +1,6 @@
+// the MontConfig derive expands to impls inside a const block
+#![allow(non_local_definitions)]
+
 use ark_ff::fields::{Fp64, MontBackend, MontConfig};
 
 #[derive(MontConfig)]
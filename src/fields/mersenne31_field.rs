@@ -0,0 +1,78 @@
+// the MontConfig derive expands to impls inside a const block
+#![allow(non_local_definitions)]
+
+use ark_ff::fields::{Fp2, Fp2Config, Fp64, MontBackend, MontConfig, QuadExtConfig, QuadExtField};
+use ark_ff::MontFp;
+
+// Mersenne-31, p = 2^31 - 1. p - 1 has two-adicity 1, FFT domains come from the circle group (see crate::circle)
+#[derive(MontConfig)]
+#[modulus = "2147483647"]
+#[generator = "7"]
+pub struct M31Config;
+pub type M31 = Fp64<MontBackend<M31Config, 1>>;
+
+// CM31 = M31[i]/(i^2 + 1), -1 is a non-residue as p = 3 mod 4
+pub struct CM31Config;
+
+impl Fp2Config for CM31Config {
+    type Fp = M31;
+
+    const NONRESIDUE: M31 = MontFp!("2147483646");
+
+    // i^(p^k - 1) for k = 0, 1
+    const FROBENIUS_COEFF_FP2_C1: &'static [M31] = &[MontFp!("1"), MontFp!("2147483646")];
+}
+
+pub type CM31 = Fp2<CM31Config>;
+
+// QM31 = CM31[u]/(u^2 - (2 + i)), the degree 4 extension Stwo draws its challenges from
+pub struct QM31Config;
+
+impl QuadExtConfig for QM31Config {
+    type BasePrimeField = M31;
+    type BaseField = CM31;
+    type FrobCoeff = CM31;
+
+    const DEGREE_OVER_BASE_PRIME_FIELD: usize = 4;
+
+    const NONRESIDUE: CM31 = CM31::new(MontFp!("2"), MontFp!("1"));
+
+    // u^(p^k - 1) = (2 + i)^((p^k - 1) / 2) for k = 0..3
+    const FROBENIUS_COEFF_C1: &'static [CM31] = &[
+        CM31::new(MontFp!("1"), MontFp!("0")),
+        CM31::new(MontFp!("21189756"), MontFp!("42379512")),
+        CM31::new(MontFp!("2147483646"), MontFp!("0")),
+        CM31::new(MontFp!("2126293891"), MontFp!("2105104135")),
+    ];
+
+    fn mul_base_field_by_frob_coeff(fe: &mut CM31, power: usize) {
+        *fe *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
+
+pub type QM31 = QuadExtField<QM31Config>;
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{Field, PrimeField, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{CM31, M31, QM31};
+
+    #[test]
+    fn test_extensions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let p = M31::MODULUS.0[0];
+        let i = CM31::new(M31::from(0u64), M31::from(1u64));
+        assert_eq!(i.square(), -CM31::ONE);
+
+        let x = QM31::rand(&mut rng);
+        assert_eq!(x * x.inverse().unwrap(), QM31::ONE);
+        for power in 0..4 {
+            assert_eq!(x.frobenius_map(power), (0..power).fold(x, |y, _| y.pow([p])));
+        }
+        // u^2 - (2 + i) is irreducible
+        assert!(CM31::new(M31::from(2u64), M31::from(1u64)).sqrt().is_none());
+        assert_eq!(QM31::from_base_prime_field_elems(&[M31::from(3u64); 4]).unwrap().to_base_prime_field_elements().collect::<Vec<_>>(), vec![M31::from(3u64); 4]);
+    }
+}
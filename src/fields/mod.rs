@@ -1 +1,4 @@
-pub mod goldilocks_field;
\ No newline at end of file
+pub mod goldilocks_field;
+pub mod mersenne31_field;
+#[cfg(feature = "serde")]
+pub mod serde_utils;
\ No newline at end of file
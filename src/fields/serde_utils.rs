@@ -0,0 +1,115 @@
+// serde helpers used by the proof and config types when the `serde` feature is enabled.
+// Field elements are written as canonical decimal strings, hashes as hex of their canonical encoding.
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub fn field_to_string<F: PrimeField>(elem: &F) -> String {
+    elem.into_bigint().to_string()
+}
+
+// Parses a decimal string, rejecting anything that is not the canonical representation of an element
+pub fn field_from_str<F: PrimeField>(s: &str) -> Option<F> {
+    let elem = F::from_str(s).ok()?;
+    (field_to_string(&elem) == s).then_some(elem)
+}
+
+pub fn canonical_to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("Serialization Failed");
+    hex::encode(bytes)
+}
+
+pub fn canonical_from_hex<T: CanonicalDeserialize>(s: &str) -> Option<T> {
+    let bytes = hex::decode(s).ok()?;
+    let mut reader = &bytes[..];
+    let value = T::deserialize_compressed(&mut reader).ok()?;
+    reader.is_empty().then_some(value)
+}
+
+fn parse_all<T, E: de::Error>(strings: Vec<String>, parse: impl Fn(&str) -> Option<T>, expected: &'static str) -> Result<Vec<T>, E> {
+    strings.iter()
+        .map(|s| parse(s).ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &expected)))
+        .collect()
+}
+
+// `Vec<F>` as a list of decimal strings
+pub mod field_vec {
+    use super::*;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(elems: &[F], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(elems.iter().map(field_to_string))
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<F>, D::Error> {
+        parse_all(Vec::deserialize(deserializer)?, field_from_str, "a canonical decimal field element")
+    }
+}
+
+// `Vec<Vec<F>>` as a list of lists of decimal strings
+pub mod field_vec_vec {
+    use super::*;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(rows: &[Vec<F>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(rows.iter().map(|r| r.iter().map(field_to_string).collect::<Vec<String>>()))
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<F>>, D::Error> {
+        let rows: Vec<Vec<String>> = Vec::deserialize(deserializer)?;
+        rows.into_iter().map(|r| parse_all(r, field_from_str, "a canonical decimal field element")).collect()
+    }
+}
+
+// `Vec<T>` of hashes as a list of hex strings
+pub mod hash_vec {
+    use super::*;
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(hashes: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(hashes.iter().map(canonical_to_hex))
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<T>, D::Error> {
+        parse_all(Vec::deserialize(deserializer)?, canonical_from_hex, "a hex encoded hash")
+    }
+}
+
+// `Vec<Vec<T>>` of hashes as a list of lists of hex strings
+pub mod hash_vec_vec {
+    use super::*;
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(rows: &[Vec<T>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(rows.iter().map(|r| r.iter().map(canonical_to_hex).collect::<Vec<String>>()))
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<T>>, D::Error> {
+        let rows: Vec<Vec<String>> = Vec::deserialize(deserializer)?;
+        rows.into_iter().map(|r| parse_all(r, canonical_from_hex, "a hex encoded hash")).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use super::{field_from_str, field_to_string, canonical_from_hex, canonical_to_hex};
+
+    #[test]
+    fn test_field_strings() {
+        assert_eq!(field_to_string(&Fq::from(0)), "0");
+        assert_eq!(field_to_string(&-Fq::from(1)), "18446744069414584320");
+        assert_eq!(field_from_str::<Fq>("18446744069414584320"), Some(-Fq::from(1)));
+        assert_eq!(field_from_str::<Fq>("0"), Some(Fq::from(0)));
+
+        // modulus, leading zeros, signs and non-digits are not canonical
+        for s in ["18446744069414584321", "007", "-1", "+1", "1a", " 1", ""] {
+            assert_eq!(field_from_str::<Fq>(s), None, "{:?} accepted", s);
+        }
+    }
+
+    #[test]
+    fn test_hash_hex() {
+        let h = Fq::from(123456789);
+        assert_eq!(canonical_from_hex::<Fq>(&canonical_to_hex(&h)), Some(h));
+        assert_eq!(canonical_from_hex::<Fq>("zz"), None);
+        assert_eq!(canonical_from_hex::<Fq>(&(canonical_to_hex(&h) + "00")), None);
+    }
+}
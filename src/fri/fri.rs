@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod test {
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use crate::fri::schedule::{optimal_reduction_schedule, HasherCosts, ScheduleObjective};
     use crate::fri::types::FriConfig;
+    use crate::fri::utils::FriConfigError;
     use crate::fri::verifier::verify_fri_proof;
     use crate::{hashing::hasher::Sha256_, fri::prover::generate_fri_proof};
     use crate::fields::goldilocks_field::Fq;
@@ -10,12 +12,16 @@ mod test {
     pub fn test_(){
         //The coefficient of x^i is stored at location i in coeffs.
         let coeff_u64: Vec<i64> = vec![19, 56, 34, 48,43,37, 10, 10];
-        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(x.clone())).collect();
+        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(*x)).collect();
 
         // 19 + 56x + 34x^2 + 48x^3 + 43x^4 + 37x^5 + 10x^6 + 10x^7
         let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
 
-        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 1, level_reductions_bits: todo!() };
+        // a cap of 2 roots needs a final domain of at least 4, with blow up 2 the trees are committed by their roots
+        let costs = HasherCosts::of::<Fq, Sha256_<Fq>>();
+        assert_eq!(optimal_reduction_schedule(7, 2, 0, 1, 4, &costs, ScheduleObjective::ProofSize), None);
+        let level_reductions_bits = optimal_reduction_schedule(7, 2, 0, 0, 4, &costs, ScheduleObjective::ProofSize).unwrap();
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits, pow_bits: 0 };
 
         let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
         
@@ -26,31 +32,44 @@ mod test {
     pub fn test_1(){
         //The coefficient of x^i is stored at location i in coeffs.
         let coeff_u64: Vec<i64> = vec![19, 56, 34, 48,43,37, 10, 10];
-        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(x.clone())).collect();
+        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(*x)).collect();
 
         // 19 + 56x + 34x^2 + 48x^3 + 43x^4 + 37x^5 + 10x^6 + 10x^7
         let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
 
-        let fri_config = FriConfig { num_query: 1, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits: vec![2,1] };
+        let fri_config = FriConfig { num_query: 1, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits: vec![2,1], pow_bits: 0 };
 
         let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
-        
-        println!("FRI PROOF: {:#?}", fri_proof);
+        // one batched opening per level, a single query opens one leaf each
+        assert_eq!(fri_proof.level_roots.len(), 2);
+        assert!(fri_proof.query_eval_proofs.iter().all(|level| level.merkle_proof.leaves.len() == 1));
+        assert_eq!(fri_proof.final_evaluations.len(), 2);
 
         assert!(verify_fri_proof(fri_config, (coeff_u64.len()-1) as u32, fri_proof))
     }
 
     #[test]
-    #[should_panic]
     pub fn test_2(){
+        // merkle cap too large for the final level, no schedule exists
+        let costs = HasherCosts::of::<Fq, Sha256_<Fq>>();
+        assert_eq!(optimal_reduction_schedule(15, 2, 0, 2, 4, &costs, ScheduleObjective::ProofSize), None);
+
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 2, level_reductions_bits: vec![2, 2], pow_bits: 0 };
+        assert_eq!(fri_config.validate::<Fq>(15), Err(FriConfigError::MerkleCapTooLarge { merkle_cap_bits: 2, final_domain_size: 2 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "needs a final domain larger than")]
+    pub fn test_2_prover_panics(){
         //The coefficient of x^i is stored at location i in coeffs.
         let coeff_u64: Vec<i64> = vec![19, 56, 34, 48,43,37, 10, 10, 19, 56, 34, 48,43,37, 10, 10];
-        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(x.clone())).collect();
+        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(*x)).collect();
 
         // 19 + 56x + 34x^2 + 48x^3 + 43x^4 + 37x^5 + 10x^6 + 10x^7
         let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
 
-        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 2, level_reductions_bits: todo!() };
+        // merkle cap too large for the final level
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 2, level_reductions_bits: vec![2, 2], pow_bits: 0 };
 
         let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
         
@@ -61,15 +80,303 @@ mod test {
     pub fn test_3() {
         //The coefficient of x^i is stored at location i in coeffs.
         let coeff_u64: Vec<i64> = vec![19, 56, 34, 48,43,37, 10, 10, 19, 56, 34, 48,43,37, 10, 10, 19, 56, 34, 48,43,37, 10, 10, 19, 56, 34, 48,43,37, 10, 10];
-        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(x.clone())).collect();
+        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(*x)).collect();
 
         // 19 + 56x + 34x^2 + 48x^3 + 43x^4 + 37x^5 + 10x^6 + 10x^7
         let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
 
-        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 3 , merkle_cap_bits: 2, level_reductions_bits: todo!() };
+        let level_reductions_bits = optimal_reduction_schedule(31, 2, 3, 2, 4, &HasherCosts::of::<Fq, Sha256_<Fq>>(), ScheduleObjective::VerifierHashes).unwrap();
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 3 , merkle_cap_bits: 2, level_reductions_bits, pow_bits: 0 };
 
         let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
         
         assert!(verify_fri_proof(fri_config, (coeff_u64.len()-1) as u32, fri_proof))
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_many_queries() {
+        let coeff_u64: Vec<i64> = (0..32).map(|i| (i * 7 + 3) % 61).collect();
+        let coeffs: Vec<Fq> = coeff_u64.iter().map(|x| Fq::from(*x)).collect();
+
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+
+        // 32 queries over a domain of 128 share most of their merkle siblings
+        let fri_config = FriConfig { num_query: 32, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 2, level_reductions_bits: vec![2,2], pow_bits: 0 };
+
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+
+        assert!(verify_fri_proof(fri_config, (coeff_u64.len()-1) as u32, fri_proof))
+    }
+
+    #[test]
+    pub fn test_explicit_degree_bound() {
+        use crate::fri::prover::generate_fri_proof_with_degree;
+        use crate::fri::utils::{new_fri_transcript, DEFAULT_DOMAIN_SEPARATOR};
+
+        // degree 20, proven against the bound 31, e.g. a polynomial whose leading coefficients vanish
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec((0..21).map(|i| Fq::from(i * 3 + 1)).collect());
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![3,1], pow_bits: 0 };
+
+        let fri_proof = generate_fri_proof_with_degree::<Fq, Sha256_<Fq>>(poly, 31, fri_config.clone(), &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        assert!(verify_fri_proof(fri_config.clone(), 31, fri_proof.clone()));
+        assert!(!verify_fri_proof(fri_config, 15, fri_proof));
+    }
+
+    #[test]
+    pub fn test_invalid_config_rejected() {
+        let coeffs: Vec<Fq> = (0..16).map(|i| Fq::from(i + 1)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits: vec![2,2], pow_bits: 0 };
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+
+        // the reductions fold past a constant polynomial of degree 7
+        assert!(!verify_fri_proof(fri_config.clone(), 7, fri_proof.clone()));
+        let surplus = FriConfig { level_reductions_bits: vec![2,2,1], ..fri_config };
+        assert!(!verify_fri_proof(surplus, 15, fri_proof));
+    }
+
+    fn final_layer_proof(last_polynomial_degree: u32) -> (FriConfig, crate::fri::types::FRIProof<Fq, Sha256_<Fq>>) {
+        let coeffs: Vec<Fq> = (0..32).map(|i| Fq::from(i * 5 + 2)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let level_reductions_bits = if last_polynomial_degree == 0 { vec![2,3] } else { vec![2,1] };
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree, merkle_cap_bits: 1, level_reductions_bits, pow_bits: 0 };
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+        (fri_config, fri_proof)
+    }
+
+    #[test]
+    pub fn test_final_layer_constant() {
+        let (fri_config, fri_proof) = final_layer_proof(0);
+        assert!(verify_fri_proof(fri_config.clone(), 31, fri_proof.clone()));
+
+        // only the first evaluation differs, every other pair is equal
+        for i in 0..fri_proof.final_evaluations.len() {
+            let mut bad_proof = fri_proof.clone();
+            bad_proof.final_evaluations[i] += Fq::from(1);
+            assert!(!verify_fri_proof(fri_config.clone(), 31, bad_proof), "tampered evaluation {} accepted", i);
+        }
+    }
+
+    #[test]
+    pub fn test_final_layer_too_high_degree() {
+        use ark_ff::{FftField, Field};
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+        use crate::fri::verifier::final_polynomial_degree_check;
+
+        let (fri_config, fri_proof) = final_layer_proof(3);
+        assert!(verify_fri_proof(fri_config.clone(), 31, fri_proof.clone()));
+
+        // evaluations of a degree 4 polynomial over the final coset
+        let final_offset = Fq::GENERATOR.pow([1u64 << 3]);
+        let domain = GeneralEvaluationDomain::<Fq>::new(16).unwrap().get_coset(final_offset).unwrap();
+        let high_degree: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5)]);
+        let evaluations: Vec<Fq> = domain.elements().map(|x| high_degree.evaluate(&x)).collect();
+        assert!(final_polynomial_degree_check(&evaluations, final_offset, 4));
+        assert!(!final_polynomial_degree_check(&evaluations, final_offset, 3));
+
+        let mut bad_proof = fri_proof.clone();
+        bad_proof.final_evaluations = evaluations;
+        assert!(!verify_fri_proof(fri_config.clone(), 31, bad_proof));
+
+        let mut bad_proof = fri_proof.clone();
+        bad_proof.final_evaluations[5] += Fq::from(1);
+        assert!(!verify_fri_proof(fri_config.clone(), 31, bad_proof));
+
+        // final layer of the wrong length
+        let mut bad_proof = fri_proof;
+        bad_proof.final_evaluations.truncate(8);
+        assert!(!verify_fri_proof(fri_config, 31, bad_proof));
+    }
+
+    #[test]
+    pub fn test_transcript_binding() {
+        use merlin::Transcript;
+        use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+        use crate::fri::prover::generate_fri_proof_with_transcript;
+        use crate::fri::utils::new_fri_transcript;
+        use crate::fri::verifier::verify_fri_proof_with_transcript;
+
+        let coeffs: Vec<Fq> = (0..16).map(|i| Fq::from(i * 9 + 4)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 0 , merkle_cap_bits: 1, level_reductions_bits: vec![2,2], pow_bits: 0 };
+
+        let mut prover_transcript = new_fri_transcript(b"statement A");
+        let fri_proof = generate_fri_proof_with_transcript::<Fq, Sha256_<Fq>>(poly.clone(), fri_config.clone(), &mut prover_transcript);
+
+        let mut verifier_transcript = new_fri_transcript(b"statement A");
+        assert!(verify_fri_proof_with_transcript(fri_config.clone(), 15, fri_proof.clone(), &mut verifier_transcript));
+        // both sides continue from the same state
+        let c_p: Fq = prover_transcript.get_challenge(b"after fri");
+        let c_v: Fq = verifier_transcript.get_challenge(b"after fri");
+        assert_eq!(c_p, c_v);
+
+        // another domain separator derives other challenges
+        assert!(!verify_fri_proof_with_transcript(fri_config.clone(), 15, fri_proof.clone(), &mut new_fri_transcript(b"statement B")));
+        assert!(!verify_fri_proof(fri_config.clone(), 15, fri_proof.clone()));
+
+        // a proof for 8 queries does not pass as a proof for a subset of them
+        let fewer_queries = FriConfig { num_query: 4, ..fri_config.clone() };
+        assert!(!verify_fri_proof_with_transcript(fewer_queries, 15, fri_proof, &mut new_fri_transcript(b"statement A")));
+
+        // embedded in an outer protocol which absorbed its own messages first
+        let mut outer_prover = Transcript::new(b"outer protocol");
+        outer_prover.append_message(b"public input", b"42");
+        let fri_proof = generate_fri_proof_with_transcript::<Fq, Sha256_<Fq>>(poly, fri_config.clone(), &mut outer_prover);
+
+        let mut outer_verifier = Transcript::new(b"outer protocol");
+        outer_verifier.append_message(b"public input", b"42");
+        assert!(verify_fri_proof_with_transcript(fri_config.clone(), 15, fri_proof.clone(), &mut outer_verifier));
+
+        let mut other_input = Transcript::new(b"outer protocol");
+        other_input.append_message(b"public input", b"43");
+        assert!(!verify_fri_proof_with_transcript(fri_config, 15, fri_proof, &mut other_input));
+    }
+
+    #[test]
+    pub fn test_vector_commitment_backends() {
+        use crate::fri::prover::{generate_fri_proof_with_transcript, generate_fri_proof_with_vc};
+        use crate::fri::types::FRIProof;
+        use crate::fri::utils::{new_fri_transcript, DEFAULT_DOMAIN_SEPARATOR};
+        use crate::fri::verifier::verify_fri_proof_with_vc;
+        use crate::vector_commitment::{merkle::{BinaryMerkle, CappedMerkle, KaryMerkle}, VectorCommitment};
+
+        let coeffs: Vec<Fq> = (0..32).map(|i| Fq::from(i * 5 + 2)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 6, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![2,1,1], pow_bits: 0 };
+
+        // Same prover and verifier code for every backend
+        fn prove_and_verify<V: VectorCommitment<Fq>>(vc: V, poly: &DensePolynomial<Fq>, fri_config: &FriConfig) {
+            let proof = generate_fri_proof_with_vc(poly.clone(), fri_config.clone(), &vc, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+            assert!(verify_fri_proof_with_vc(fri_config.clone(), 31, &vc, proof, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR)));
+        }
+        prove_and_verify(BinaryMerkle::<Fq, Sha256_<Fq>>::new(), &poly, &fri_config);
+        prove_and_verify(CappedMerkle::<Fq, Sha256_<Fq>>::new(1), &poly, &fri_config);
+        prove_and_verify(KaryMerkle::<Fq, Sha256_<Fq>>::new(4), &poly, &fri_config);
+        prove_and_verify(KaryMerkle::<Fq, Sha256_<Fq>>::new(3), &poly, &fri_config);
+
+        // The capped backend produces the regular FRIProof
+        let vc = CappedMerkle::<Fq, Sha256_<Fq>>::new(fri_config.merkle_cap_bits);
+        let proof: FRIProof<Fq, Sha256_<Fq>> = generate_fri_proof_with_vc(poly.clone(), fri_config.clone(), &vc, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR)).into();
+        let expected = generate_fri_proof_with_transcript::<Fq, Sha256_<Fq>>(poly.clone(), fri_config.clone(), &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        assert_eq!(proof.to_bytes(), expected.to_bytes());
+        assert!(verify_fri_proof(fri_config.clone(), 31, proof));
+
+        // openings of one backend do not verify against another backend's commitments
+        let kary = KaryMerkle::<Fq, Sha256_<Fq>>::new(4);
+        let mut proof = generate_fri_proof_with_vc(poly.clone(), fri_config.clone(), &kary, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        let other = generate_fri_proof_with_vc(poly, fri_config.clone(), &KaryMerkle::<Fq, Sha256_<Fq>>::new(2), &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        proof.level_commitments[1] = other.level_commitments[1];
+        assert!(!verify_fri_proof_with_vc(fri_config, 31, &kary, proof, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR)));
+    }
+
+    #[test]
+    pub fn test_commit_then_query() {
+        use crate::fri::interactive::FriVerifier;
+        use crate::fri::prover::{commit_phase, generate_fri_proof_with_transcript, query_phase};
+        use crate::fri::types::FRIProof;
+        use crate::fri::utils::{new_fri_transcript, observe_fri_statement, DEFAULT_DOMAIN_SEPARATOR};
+        use crate::vector_commitment::merkle::CappedMerkle;
+
+        let coeffs: Vec<Fq> = (0..32).map(|i| Fq::from(i * 11 + 6)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 6, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![2,2], pow_bits: 0 };
+        let vc = CappedMerkle::<Fq, Sha256_<Fq>>::new(fri_config.merkle_cap_bits);
+
+        // committing then querying gives the one shot proof
+        let mut prover_transcript = new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR);
+        let data = commit_phase(poly.clone(), fri_config.clone(), &vc, &mut prover_transcript);
+        let proof: FRIProof<Fq, Sha256_<Fq>> = query_phase(&data, &vc, &mut prover_transcript).into();
+        let expected = generate_fri_proof_with_transcript::<Fq, Sha256_<Fq>>(poly, fri_config.clone(), &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        assert_eq!(proof.to_bytes(), expected.to_bytes());
+
+        let mut verifier_transcript = new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR);
+        let mut verifier = FriVerifier::new(fri_config.clone(), 31, &vc).unwrap();
+        observe_fri_statement::<Fq>(&mut verifier_transcript, &fri_config, 31);
+        for commitment in data.level_commitments() {
+            verifier.receive_commitment(commitment.clone(), &mut verifier_transcript);
+            verifier.challenge(&mut verifier_transcript);
+        }
+        verifier.receive_final_polynomial(data.final_evaluations().to_vec(), proof.pow_nonce, &mut verifier_transcript).unwrap();
+        let openings: Vec<_> = proof.query_eval_proofs.into_iter().map(|p| p.merkle_proof).collect();
+        assert!(verifier.check_answers(&openings));
+
+        // extra queries answered from the retained trees, without recommitting
+        let extra_queries = vec![3, 77, 100, 127];
+        let extra_openings = data.answer_queries(&vc, &extra_queries);
+        assert!(verifier.check_queries(&extra_queries, &extra_openings));
+        // query 5 goes through first level leaf 5, which is not opened
+        assert!(!verifier.check_queries(&[5, 77, 100, 127], &extra_openings));
+    }
+
+    #[test]
+    pub fn test_coset_fold_matches_interpolation() {
+        use ark_ff::{Field, UniformRand};
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        use rand::{rngs::StdRng, SeedableRng};
+        use crate::fri::verifier::{calcualate_next_level_value, fold_coset_evaluations};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let domain_size = 64;
+        let offset = Fq::from(7u64);
+        let eval_domain = GeneralEvaluationDomain::<Fq>::new(domain_size).unwrap().get_coset(offset).unwrap();
+        for reduction in [2, 4, 8, 16] {
+            let query_addition_factor = domain_size / reduction;
+            let coset_domain = GeneralEvaluationDomain::<Fq>::new(reduction).unwrap();
+            for j in [0, 1, query_addition_factor - 1] {
+                let points: Vec<usize> = (0..reduction).map(|i| j + i * query_addition_factor).collect();
+                let evaluations: Vec<Fq> = (0..reduction).map(|_| Fq::rand(&mut rng)).collect();
+                let challenge = Fq::rand(&mut rng);
+                let shift_inv = eval_domain.element(j).inverse().unwrap();
+                assert_eq!(
+                    fold_coset_evaluations(&evaluations, challenge, shift_inv, &coset_domain),
+                    calcualate_next_level_value(points, evaluations, challenge, eval_domain),
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{span, Subscriber};
+        use tracing_subscriber::{layer::{Context, SubscriberExt}, registry::LookupSpan, Layer, Registry};
+
+        // Records the name of every span opened, with the names of the spans it is opened in
+        type Opened = Vec<(&'static str, Vec<&'static str>)>;
+        struct SpanNames(Arc<Mutex<Opened>>);
+        impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanNames {
+            fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, ctx: Context<'_, S>) {
+                let parents = ctx.lookup_current().map(|current| current.scope().map(|s| s.name()).collect()).unwrap_or_default();
+                self.0.lock().unwrap().push((attrs.metadata().name(), parents));
+            }
+        }
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = Registry::default().with(SpanNames(names.clone()));
+
+        let coeffs: Vec<Fq> = (0..16).map(|i| Fq::from(i + 3)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 3, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits: vec![2,2], pow_bits: 2 };
+
+        tracing::subscriber::with_default(subscriber, || {
+            let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+            assert!(verify_fri_proof(fri_config, 15, fri_proof));
+        });
+
+        let names = names.lock().unwrap();
+        let count = |name: &str| names.iter().filter(|(n, _)| *n == name).count();
+        assert_eq!(count("fri_prove"), 1);
+        assert_eq!(count("lde"), 3);
+        assert_eq!(count("commit_level"), 2);
+        assert_eq!(count("fold"), 2);
+        assert_eq!(count("query_phase"), 1);
+        assert_eq!(count("grind"), 1);
+        assert_eq!(count("open_queries"), 1);
+        // grinding, sampling and the openings all happen in the query phase
+        let in_query_phase = |name: &str| names.iter().filter(|(n, _)| *n == name).all(|(_, parents)| parents.contains(&"query_phase"));
+        assert!(in_query_phase("grind") && in_query_phase("open_queries"));
+        assert_eq!(count("fri_verify"), 1);
+        assert_eq!(count("verify_query"), 3);
+    }
+}
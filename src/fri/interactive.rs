@@ -0,0 +1,537 @@
+use ark_ff::{batch_inversion, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+use rand::RngCore;
+use tracing::{debug, debug_span, trace};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::vector_commitment::VectorCommitment;
+
+use super::prover::{fold_polynomial, leaf_groupings, query_leaf_indices, FriProverData};
+use super::types::FriConfig;
+use super::utils::FriConfigError;
+use super::verifier::{final_polynomial_degree_check, fold_coset_evaluations};
+use super::zk::{ZkConfig, ZkConfigError};
+
+// FRI as an interactive protocol. FriProver answers the verifier's messages, FriVerifier draws its
+// challenges from any TranscriptProtocol: a Fiat-Shamir transcript for the non-interactive proofs
+// (generate_fri_proof and verify_fri_proof run these), or RandomCoins to run it interactively.
+//
+// Message flow, with L = level_reductions_bits.len():
+//   with zk: P -> V  commitments to p and to the masking polynomial, V -> P beta
+//   P -> V  commitment to the first level (with zk, of p + beta * masking)
+//   L times: V -> P challenge, P -> V commitment to the folded level (the last round sends none)
+//   P -> V  final evaluations (and with grinding a proof of work nonce), V -> P queries, P -> V openings
+//   (with zk, also of p and the masking polynomial at the first level's leaves)
+
+fn salted_leaves<F: PrimeField>(evaluations: &[F], grouping: &[usize], reduction: usize, zk: &mut Option<(&ZkConfig, &mut dyn RngCore)>) -> Vec<Vec<F>> {
+    grouping.chunks(reduction).map(|leaf| {
+        let mut leaf_element: Vec<F> = leaf.iter().map(|l| evaluations[*l]).collect();
+        if let Some((zk_config, rng)) = zk {
+            leaf_element.extend((0..zk_config.salt_size).map(|_| F::rand(rng)));
+        }
+        leaf_element
+    }).collect()
+}
+
+pub struct FriProver<'a, F: PrimeField, V: VectorCommitment<F>> {
+    data: FriProverData<F, V>,
+    vc: &'a V,
+    zk: Option<(&'a ZkConfig, &'a mut dyn RngCore)>,
+    leaf_groupings: Vec<Vec<usize>>,
+    current_polynomial: DensePolynomial<F>,
+    coeffs_length: usize, // degree bound of the current level plus one
+    offset: F, // coset offset of the current level
+    num_folds: usize,
+    masking_polynomial: Option<DensePolynomial<F>>, // zk only, until `mask` mixes it in
+}
+
+impl<'a, F: PrimeField, V: VectorCommitment<F>> FriProver<'a, F, V> {
+    // Commits to the first level of `polynomial`, proven against the degree bound `degree`
+    pub fn new(polynomial: DensePolynomial<F>, degree: u32, fri_config: FriConfig, vc: &'a V) -> Self {
+        Self::start(polynomial, degree, fri_config, vc, None)
+    }
+
+    // Zero-knowledge variant (see fri::zk), commits to the polynomial and the masking polynomial only.
+    // The first level is committed by `mask`, once the verifier has sent beta.
+    pub fn new_zk(polynomial: DensePolynomial<F>, degree: u32, fri_config: FriConfig, vc: &'a V, zk_config: &'a ZkConfig, rng: &'a mut dyn RngCore) -> Self {
+        if let Err(e) = zk_config.validate() {
+            panic!("Invalid zk config: {}", e);
+        }
+        assert!(!fri_config.level_reductions_bits.is_empty(), "Zero-knowledge proofs need at least one FRI level");
+        Self::start(polynomial, degree, fri_config, vc, Some((zk_config, rng)))
+    }
+
+    fn start(polynomial: DensePolynomial<F>, degree: u32, fri_config: FriConfig, vc: &'a V, zk: Option<(&'a ZkConfig, &'a mut dyn RngCore)>) -> Self {
+        if let Err(e) = fri_config.validate::<F>(degree) {
+            panic!("Invalid FRI config: {}", e);
+        }
+        let coefficients_length = degree as usize + 1;
+        assert!(polynomial.coeffs.len() <= coefficients_length, "Polynomial exceeds the degree bound");
+        let num_levels = fri_config.level_reductions_bits.len();
+        let original_domain = coefficients_length * fri_config.blow_up_factor as usize;
+        debug!(original_domain, num_levels, "generating proof");
+
+        let mut prover = FriProver {
+            leaf_groupings: leaf_groupings(fri_config.level_reductions_bits.clone(), original_domain, num_levels),
+            data: FriProverData {
+                fri_config,
+                original_domain,
+                level_commitments: Vec::with_capacity(num_levels),
+                level_data: Vec::with_capacity(num_levels),
+                polynomial: None,
+                masking: None,
+                zk_data: Vec::new(),
+                final_evaluations: None,
+            },
+            vc,
+            zk,
+            current_polynomial: polynomial,
+            coeffs_length: coefficients_length,
+            // offset for coset-fri, set to the group generator
+            offset: F::GENERATOR,
+            num_folds: 0,
+            masking_polynomial: None,
+        };
+        if num_levels == 0 {
+            prover.data.final_evaluations = Some(prover.lde(&prover.current_polynomial));
+        } else if let Some((_, rng)) = &mut prover.zk {
+            // A random polynomial of the same degree, mixed into p once beta is known. Both are committed
+            // with the first level's leaf layout, so that their openings line up with the first level's.
+            let masking_coeffs = (0..coefficients_length).map(|_| F::rand(rng)).collect();
+            let masking_polynomial = DensePolynomial::from_coefficients_vec(masking_coeffs);
+            prover.data.polynomial = Some(prover.commit_zk_oracle(&prover.current_polynomial.clone()));
+            prover.data.masking = Some(prover.commit_zk_oracle(&masking_polynomial));
+            prover.masking_polynomial = Some(masking_polynomial);
+        } else {
+            prover.commit_level();
+        }
+        prover
+    }
+
+    // Evaluations of `polynomial`, of at most coeffs_length coefficients, over the current coset
+    fn lde(&self, polynomial: &DensePolynomial<F>) -> Vec<F> {
+        let coeffs_length = self.coeffs_length;
+        let domain_size = coeffs_length * self.data.fri_config.blow_up_factor as usize;
+        let _lde_span = debug_span!("lde", level = self.num_folds, coeffs_length, domain_size).entered();
+        let eval_domain = GeneralEvaluationDomain::<F>::new(domain_size).unwrap().get_coset(self.offset).expect("Error in getting coset");
+        eval_domain.fft(&polynomial.coeffs)
+    }
+
+    // zk only, commits to `polynomial` over the first level's domain and leaves
+    fn commit_zk_oracle(&mut self, polynomial: &DensePolynomial<F>) -> V::Commitment {
+        let evaluations = self.lde(polynomial);
+        let reduction = 1<<self.data.fri_config.level_reductions_bits[0];
+        let (commitment, data) = self.vc.commit(salted_leaves(&evaluations, &self.leaf_groupings[0], reduction, &mut self.zk));
+        self.data.zk_data.push(data);
+        commitment
+    }
+
+    fn commit_level(&mut self) -> V::Commitment {
+        let level = self.num_folds;
+        let evaluations = self.lde(&self.current_polynomial);
+        let reduction = 1<<self.data.fri_config.level_reductions_bits[level];
+
+        let _commit_span = debug_span!("commit_level", level, num_leaves = evaluations.len()/reduction, leaf_size = reduction).entered();
+        let (commitment, data) = self.vc.commit(salted_leaves(&evaluations, &self.leaf_groupings[level], reduction, &mut self.zk));
+        self.data.level_commitments.push(commitment.clone());
+        self.data.level_data.push(data);
+        commitment
+    }
+
+    // Commitment to the first level, None without levels or, with zk, before `mask`
+    pub fn commitment(&self) -> Option<&V::Commitment> {
+        self.data.level_commitments.first()
+    }
+
+    // zk only, commitment to the polynomial itself
+    pub fn polynomial_commitment(&self) -> Option<&V::Commitment> {
+        self.data.polynomial_commitment()
+    }
+
+    pub fn masking_commitment(&self) -> Option<&V::Commitment> {
+        self.data.masking_commitment()
+    }
+
+    // zk only, commits to p + beta * masking as the first level, so that no level shows p
+    pub fn mask(&mut self, beta: F) -> V::Commitment {
+        let masking_polynomial = self.masking_polynomial.take().expect("No masking polynomial to mix in");
+        self.current_polynomial = &self.current_polynomial + &(&masking_polynomial * beta);
+        self.commit_level()
+    }
+
+    // Folds the last committed level with the verifier's challenge and commits to the result.
+    // The last round leaves the final polynomial and returns None.
+    pub fn commit_round(&mut self, challenge: F) -> Option<V::Commitment> {
+        let num_levels = self.data.fri_config.level_reductions_bits.len();
+        assert!(self.num_folds < num_levels, "All rounds are already committed");
+        assert!(self.masking_polynomial.is_none(), "The masking polynomial must be mixed in before the first round");
+        let reduction = 1<<self.data.fri_config.level_reductions_bits[self.num_folds];
+
+        let fold_span = debug_span!("fold", level = self.num_folds, reduction).entered();
+        let polynomial = std::mem::take(&mut self.current_polynomial);
+        self.current_polynomial = fold_polynomial(polynomial, challenge, reduction);
+        self.coeffs_length /= reduction;
+        self.offset = self.offset.pow([reduction as u64]);
+        self.num_folds += 1;
+        fold_span.exit();
+
+        if self.num_folds < num_levels {
+            return Some(self.commit_level());
+        }
+        // final level evaluations
+        // [TODO] Send polynomial coefficients instead of evals
+        debug!(degree = self.current_polynomial.degree(), "reached final polynomial");
+        self.data.final_evaluations = Some(self.lde(&self.current_polynomial));
+        None
+    }
+
+    // Evaluations of the final polynomial over the last domain, available once all rounds are committed
+    pub fn final_polynomial(&self) -> &[F] {
+        self.data.final_evaluations()
+    }
+
+    // Batched openings, one per level, of the leaves the queries go through
+    pub fn answer_queries(&self, queries: &[u32]) -> Vec<V::Opening> {
+        self.data.answer_queries(self.vc, queries)
+    }
+
+    // zk only, openings of the polynomial and the masking polynomial at the first level's leaves
+    pub fn answer_zk_queries(&self, queries: &[u32]) -> (V::Opening, V::Opening) {
+        self.data.answer_zk_queries(self.vc, queries).expect("Not a zero-knowledge prover")
+    }
+
+    // Commitments and prover data of every level, once all rounds are committed
+    pub fn into_data(self) -> FriProverData<F, V> {
+        assert!(self.data.final_evaluations.is_some(), "Rounds left to commit");
+        self.data
+    }
+}
+
+pub struct FriVerifier<'a, F: PrimeField, V: VectorCommitment<F>> {
+    fri_config: FriConfig,
+    degree: u32,
+    vc: &'a V,
+    salt_size: Option<usize>, // zk only
+    commitments: Vec<V::Commitment>,
+    challenges: Vec<F>,
+    zk_commitments: Option<(V::Commitment, V::Commitment, F)>, // zk only, commitments to p and the masking polynomial, and beta
+    final_evaluations: Option<Vec<F>>,
+    queries: Vec<u32>,
+}
+
+// Opened leaf `idx` without its salt, None if it is not opened or has the wrong size
+fn opened_evaluations<F: PrimeField, V: VectorCommitment<F>>(vc: &V, opening: &V::Opening, idx: usize, reduction: usize, salt_size: usize) -> Option<Vec<F>> {
+    match vc.opened_row(opening, idx) {
+        Some(leaf) if leaf.len() == reduction + salt_size => Some(leaf[..reduction].to_vec()),
+        _ => None,
+    }
+}
+
+impl<'a, F: PrimeField + From<i32>, V: VectorCommitment<F>> FriVerifier<'a, F, V> {
+    pub fn new(fri_config: FriConfig, degree: u32, vc: &'a V) -> Result<Self, FriConfigError> {
+        fri_config.validate::<F>(degree)?;
+        Ok(FriVerifier {
+            fri_config,
+            degree,
+            vc,
+            salt_size: None,
+            commitments: Vec::new(),
+            challenges: Vec::new(),
+            zk_commitments: None,
+            final_evaluations: None,
+            queries: Vec::new(),
+        })
+    }
+
+    pub fn new_zk(fri_config: FriConfig, degree: u32, vc: &'a V, zk_config: &ZkConfig) -> Result<Self, ZkConfigError> {
+        zk_config.validate()?;
+        let mut verifier = Self::new(fri_config, degree, vc)?;
+        verifier.salt_size = Some(zk_config.salt_size);
+        Ok(verifier)
+    }
+
+    pub fn receive_commitment(&mut self, commitment: V::Commitment, transcript: &mut impl TranscriptProtocol<F>) {
+        transcript.observe_elements(b"merkle_root", &self.vc.commitment_elements(&commitment));
+        self.commitments.push(commitment);
+    }
+
+    // zk only, before the first commitment. Returns the weight beta of the masking polynomial.
+    pub fn receive_zk_commitments(&mut self, polynomial: V::Commitment, masking: V::Commitment, transcript: &mut impl TranscriptProtocol<F>) -> F {
+        transcript.observe_elements(b"polynomial_root", &self.vc.commitment_elements(&polynomial));
+        transcript.observe_elements(b"masking_root", &self.vc.commitment_elements(&masking));
+        let beta = transcript.get_challenge(b"masking");
+        self.zk_commitments = Some((polynomial, masking, beta));
+        beta
+    }
+
+    // Folding challenge for the last received commitment
+    pub fn challenge(&mut self, transcript: &mut impl TranscriptProtocol<F>) -> F {
+        let verifier_rand: F = transcript.get_challenge(b"alpha");
+        self.challenges.push(verifier_rand);
+        verifier_rand
+    }
+
+    // Returns the queries the prover has to answer, or None if `pow_nonce` fails the config's proof of
+    // work. Without grinding the nonce must be 0.
+    pub fn receive_final_polynomial(&mut self, final_evaluations: Vec<F>, pow_nonce: u64, transcript: &mut impl TranscriptProtocol<F>) -> Option<Vec<u32>> {
+        transcript.observe_elements(b"final evals", &final_evaluations);
+        self.final_evaluations = Some(final_evaluations);
+        let pow_bits = self.fri_config.pow_bits;
+        if (pow_bits == 0 && pow_nonce != 0) || (pow_bits > 0 && !transcript.check_pow(pow_bits, pow_nonce)) {
+            debug!(pow_nonce, pow_bits, "proof of work rejected");
+            return None;
+        }
+        self.queries = transcript.get_challenge_indices(b"challenge indices", self.fri_config.num_query as usize);
+        Some(self.queries.clone())
+    }
+
+    // Checks the final polynomial and the openings of every level
+    pub fn check_answers(&self, openings: &[V::Opening]) -> bool {
+        self.check_queries(&self.queries, openings)
+    }
+
+    // As check_answers, for openings at other `queries` than the ones drawn, e.g. extra queries asked
+    // once the proof is received. A zk verifier needs the openings of p and the masking polynomial too.
+    pub fn check_queries(&self, queries: &[u32], openings: &[V::Opening]) -> bool {
+        self.salt_size.is_none() && self.check_levels(queries, openings)
+    }
+
+    // zk only, check_answers with the openings of p and the masking polynomial at the first level's
+    // leaves, which must add up to the first level as p + beta * masking
+    pub fn check_zk_answers(&self, openings: &[V::Opening], polynomial_opening: &V::Opening, masking_opening: &V::Opening) -> bool {
+        let (Some(salt_size), Some((polynomial, masking, beta))) = (self.salt_size, &self.zk_commitments) else {
+            return false;
+        };
+        if openings.is_empty() {
+            return false;
+        }
+        let fri_config = &self.fri_config;
+        let original_domain_size = (fri_config.blow_up_factor * (self.degree+1)) as usize;
+        let reduction = 1<<fri_config.level_reductions_bits[0];
+        let num_leaves = original_domain_size>>fri_config.level_reductions_bits[0];
+        if !self.vc.verify(polynomial, num_leaves, polynomial_opening) || !self.vc.verify(masking, num_leaves, masking_opening) {
+            return false;
+        }
+        let leaf_indices = &query_leaf_indices(&self.queries, original_domain_size, &fri_config.level_reductions_bits)[0];
+        for idx in leaf_indices.iter() {
+            let leaves = [&openings[0], polynomial_opening, masking_opening].map(|opening| opened_evaluations(self.vc, opening, *idx, reduction, salt_size));
+            let [Some(level), Some(p), Some(m)] = leaves else {
+                return false;
+            };
+            if level.iter().zip(p.iter().zip(m.iter())).any(|(f, (p, m))| *f != *p + *beta * m) {
+                debug!(idx, "first level is not p + beta * masking");
+                return false;
+            }
+        }
+        self.check_levels(&self.queries, openings)
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn check_levels(&self, queries: &[u32], openings: &[V::Opening]) -> bool {
+        let fri_config = &self.fri_config;
+        let levels_to_iterate = fri_config.level_reductions_bits.len();
+        if self.commitments.len() != levels_to_iterate || self.challenges.len() != levels_to_iterate || openings.len() != levels_to_iterate {
+            return false;
+        }
+        let final_evaluations = match &self.final_evaluations {
+            Some(final_evaluations) => final_evaluations,
+            None => return false,
+        };
+        let salt_size = self.salt_size.unwrap_or(0);
+
+        let final_evalutaion_degree = fri_config.last_polynomial_degree;
+        let original_domain_size = fri_config.blow_up_factor * (self.degree+1);
+
+        debug!(original_domain_size, levels_to_iterate, "verifying proof");
+
+        // [TODO] no need to calculate when we move to sending coeffs since no need of interpolation required
+        // Each reduction by 2^bits raises the coset offset to the power 2^bits
+        let mut final_offset = F::GENERATOR;
+        for bits in fri_config.level_reductions_bits.iter() {
+            final_offset = final_offset.pow([1u64 << bits]);
+        }
+
+        if final_evaluations.len() as u32 != fri_config.blow_up_factor*(final_evalutaion_degree+1) {
+            return false;
+        }
+        if !final_polynomial_degree_check(final_evaluations, final_offset, final_evalutaion_degree) {
+            return false;
+        }
+
+        // Verify the batched openings of each level against the commited root cap
+        let mut domain_size_current = original_domain_size as usize;
+        for l in 0..levels_to_iterate {
+            let next_domain_size = domain_size_current>>fri_config.level_reductions_bits[l];
+            if !self.vc.verify(&self.commitments[l], next_domain_size, &openings[l]) {
+                return false;
+            }
+            domain_size_current = next_domain_size;
+        }
+
+        // A query's leaf at a level holds the evaluations over a coset s * <zeta> of the subgroup of order
+        // `reduction`. Precompute the subgroup per level and the inverses of the shifts s, batch inverted
+        // over all queries of the level.
+        let mut coset_domains: Vec<GeneralEvaluationDomain<F>> = Vec::with_capacity(levels_to_iterate);
+        let mut shift_inverses: Vec<Vec<F>> = Vec::with_capacity(levels_to_iterate);
+        let mut domain_size_current = original_domain_size as usize;
+        let mut offset = F::GENERATOR;
+        for l in 0..levels_to_iterate {
+            let reduction = 1<<fri_config.level_reductions_bits[l];
+            let query_addition_factor = domain_size_current/reduction;
+            let generator = GeneralEvaluationDomain::<F>::new(domain_size_current).unwrap().group_gen();
+            let coset_domain = GeneralEvaluationDomain::<F>::new(reduction).unwrap();
+            debug_assert_eq!(coset_domain.group_gen(), generator.pow([query_addition_factor as u64]));
+
+            let mut shifts: Vec<F> = queries.iter().map(|q_start| {
+                let q = (*q_start as usize)%(original_domain_size as usize/2)%domain_size_current;
+                offset * generator.pow([(q%query_addition_factor) as u64])
+            }).collect();
+            batch_inversion(&mut shifts);
+            coset_domains.push(coset_domain);
+            shift_inverses.push(shifts);
+
+            domain_size_current >>= fri_config.level_reductions_bits[l];
+            offset = offset.pow([reduction as u64]);
+        }
+
+        for (query_idx, q_start) in queries.iter().enumerate() {
+            let _query_span = debug_span!("verify_query", query = q_start).entered();
+            let mut domain_size_current = original_domain_size as usize;
+
+            // Bring query to first half of domain
+            let q_init = (*q_start as usize)%(domain_size_current/2);
+
+            // Contains value of element in next folded level for consistency check
+            let mut next_level_value: F = F::one();
+
+            for l in 0..levels_to_iterate {
+                let reduction = 1<<fri_config.level_reductions_bits[l];
+                let q = q_init%domain_size_current;
+
+                trace!(q, level = l, "verifying query at level");
+
+                let query_addition_factor = domain_size_current/reduction;
+
+                // Extract the leaf opened for the query at the level l (already verified against the root)
+                // Contains all evaluations of this level required corresponding to that query for evaluation of next value in the reduced polynomail evaluations
+                // Evaluations will always be sorted in the order of their sorted queries
+                let evaluations = match opened_evaluations(self.vc, &openings[l], q%query_addition_factor, reduction, salt_size) {
+                    Some(evaluations) => evaluations,
+                    None => return false,
+                };
+
+                if l !=0 {
+                    // check prev round to current round consistency
+                    // (q/query_addition_factor) -> maps the query index to the index in corresponding merkle leaf
+                    if next_level_value != evaluations[q/query_addition_factor] {
+                        debug!(q, level = l, "consistency check failed with the previous level");
+                        return false;
+                    }
+                }
+
+                let verifier_rand = self.challenges[l];
+
+                next_level_value = fold_coset_evaluations(&evaluations, verifier_rand, shift_inverses[l][query_idx], &coset_domains[l]);
+
+                domain_size_current >>= fri_config.level_reductions_bits[l];
+            }
+            let q_final = (*q_start as usize)%(domain_size_current);
+            if levels_to_iterate > 0 && final_evaluations[q_final] != next_level_value {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{channel, Receiver, Sender};
+
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::fiat_shamir::random_coins::RandomCoins;
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::types::FriConfig;
+    use crate::hashing::hasher::Sha256_;
+    use crate::vector_commitment::merkle::CappedMerkle;
+    use super::{FriProver, FriVerifier};
+
+    type Vc = CappedMerkle<Fq, Sha256_<Fq>>;
+    type Commitment = Vec<Fq>;
+    type Opening = crate::merkle_tree::merkle::MerkleMultiProof<Fq, Sha256_<Fq>>;
+
+    enum ProverMessage {
+        Commitment(Commitment),
+        FinalPolynomial(Vec<Fq>),
+        Answers(Vec<Opening>),
+    }
+
+    enum VerifierMessage {
+        Challenge(Fq),
+        Queries(Vec<u32>),
+    }
+
+    fn fri_config() -> FriConfig {
+        FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2,1], pow_bits: 0 }
+    }
+
+    // Prover side, folding with challenge + `shift` instead of the verifier's challenge
+    fn run_prover(polynomial: DensePolynomial<Fq>, shift: Fq, to_verifier: Sender<ProverMessage>, from_verifier: Receiver<VerifierMessage>) {
+        let vc = Vc::new(fri_config().merkle_cap_bits);
+        let mut prover = FriProver::new(polynomial, 31, fri_config(), &vc);
+        to_verifier.send(ProverMessage::Commitment(prover.commitment().unwrap().clone())).unwrap();
+        for _ in 0..fri_config().level_reductions_bits.len() {
+            let VerifierMessage::Challenge(challenge) = from_verifier.recv().unwrap() else { panic!("Expected a challenge") };
+            if let Some(commitment) = prover.commit_round(challenge + shift) {
+                to_verifier.send(ProverMessage::Commitment(commitment)).unwrap();
+            }
+        }
+        to_verifier.send(ProverMessage::FinalPolynomial(prover.final_polynomial().to_vec())).unwrap();
+        let VerifierMessage::Queries(queries) = from_verifier.recv().unwrap() else { panic!("Expected queries") };
+        to_verifier.send(ProverMessage::Answers(prover.answer_queries(&queries))).unwrap();
+    }
+
+    // Runs prover and verifier on their own threads, talking over channels, returns the verifier's decision
+    fn run_over_channel(shift: Fq, seed: u64) -> bool {
+        let coeffs: Vec<Fq> = (0..32).map(|i| Fq::from(i * 7 + 3)).collect();
+        let polynomial = DensePolynomial::from_coefficients_vec(coeffs);
+        let (to_verifier, from_prover) = channel();
+        let (to_prover, from_verifier) = channel();
+
+        std::thread::scope(|s| {
+            s.spawn(move || run_prover(polynomial, shift, to_verifier, from_verifier));
+
+            let vc = Vc::new(fri_config().merkle_cap_bits);
+            let mut verifier = FriVerifier::new(fri_config(), 31, &vc).unwrap();
+            let mut coins = RandomCoins::new(StdRng::seed_from_u64(seed));
+            let mut queries = Vec::new();
+            loop {
+                match from_prover.recv().unwrap() {
+                    ProverMessage::Commitment(commitment) => {
+                        verifier.receive_commitment(commitment, &mut coins);
+                        to_prover.send(VerifierMessage::Challenge(verifier.challenge(&mut coins))).unwrap();
+                    },
+                    ProverMessage::FinalPolynomial(final_evaluations) => {
+                        queries = verifier.receive_final_polynomial(final_evaluations, 0, &mut coins).unwrap();
+                        to_prover.send(VerifierMessage::Queries(queries.clone())).unwrap();
+                    },
+                    ProverMessage::Answers(openings) => {
+                        assert_eq!(queries.len(), 8);
+                        return verifier.check_answers(&openings);
+                    },
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_interactive_fri_over_channel() {
+        for seed in 0..3 {
+            assert!(run_over_channel(Fq::from(0u64), seed));
+        }
+        // a prover which does not fold with the verifier's challenges is caught
+        assert!(!run_over_channel(Fq::from(1u64), 0));
+    }
+}
@@ -1,5 +1,10 @@
+#[allow(clippy::module_inception)]
 pub mod fri;
 pub mod types;
 pub mod prover;
 pub mod verifier;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod security;
+pub mod schedule;
+pub mod zk;
+pub mod interactive;
@@ -1,12 +1,17 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::borrow::Borrow;
 
 use ark_ff::PrimeField;
-use ark_poly::{univariate::DensePolynomial, GeneralEvaluationDomain, EvaluationDomain, DenseUVPolynomial, Polynomial};
-use merlin::Transcript;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use rand::RngCore;
+use tracing::{debug_span, trace};
 
-use crate::{hashing::hasher::Hasher_, merkle_tree::merkle, fri::utils::validate_fri_config, fiat_shamir::fiat_shamir::TranscriptProtocol};
+use crate::{hashing::hasher::Hasher_, merkle_tree::merkle, fiat_shamir::fiat_shamir::TranscriptProtocol};
+use crate::vector_commitment::{merkle::CappedMerkle, VectorCommitment};
 
-use super::types::{FRIProof, FriConfig, QueryEvalProofs};
+use super::interactive::FriProver;
+use super::types::{FRIProof, FriConfig, QueryEvalProofs, VcFRIProof};
+use super::utils::{new_fri_transcript, observe_fri_statement, DEFAULT_DOMAIN_SEPARATOR};
+use super::zk::{ZkConfig, ZkFRIProof};
 
 // Groups the domain indexes so that they can be grouped together by their parities
 pub fn leaf_groupings(reduction_bits: Vec<u32>, start_domain: usize, num_levels: usize) -> Vec<Vec<usize>>{
@@ -19,162 +24,226 @@ pub fn leaf_groupings(reduction_bits: Vec<u32>, start_domain: usize, num_levels:
         for domain_idx in 0..query_addition_factor {
             groupings[level].push(domain_idx);
             for _ in 0..reduction-1{
-                let val = groupings[level].last().unwrap().clone();
+                let val = *groupings[level].last().unwrap();
                 groupings[level].push(val+query_addition_factor);
             }
         }
-        domain_size = domain_size>>reduction_bits[level];
+        domain_size >>= reduction_bits[level];
     }
-    println!("groupings {:?}", groupings);
     groupings
 }
 
 pub fn fold_polynomial<F:PrimeField>(polynomial: DensePolynomial<F>, rand: F, reduction: usize) ->DensePolynomial<F> {
-    let mut folded_coeffs: Vec<F> = Vec::new();
-    for i in (0..polynomial.coeffs.len()).step_by(reduction) {
-        let mut sum = F::ZERO;
-        for j in (0..reduction).rev() {
-            sum = sum * rand + polynomial.coeffs[i+j];
-        }
-        folded_coeffs.push(sum);
-    }
+    // a last chunk shorter than `reduction` has zero leading coefficients
+    let folded_coeffs: Vec<F> = polynomial.coeffs.chunks(reduction)
+        .map(|chunk| chunk.iter().rev().fold(F::ZERO, |sum, c| sum * rand + c))
+        .collect();
     DenseUVPolynomial::from_coefficients_vec(folded_coeffs)
 }
 
-// Generate query proofs for a corresponding query at each FRI level
-pub fn generate_query_eval_proofs<F: PrimeField, H: Hasher_<F>>(queries: Vec<u32>, original_domain: usize, reduction_bits: Vec<u32>, merkle_objs: &Vec<merkle::MerkleTree<F, H>>, query_eval_proofs: &mut Vec<HashMap<usize, QueryEvalProofs<F, H>>>) {
+// Leaf indices opened at each level for the given queries
+pub fn query_leaf_indices(queries: &[u32], original_domain: usize, reduction_bits: &[u32]) -> Vec<Vec<usize>> {
+    let mut level_leaf_indices: Vec<Vec<usize>> = vec![Vec::new(); reduction_bits.len()];
     for q_start in queries {
         let mut domain_size_current = original_domain;
         // We translate each query to first half of its domain
-        let q_init = (q_start as usize)%(original_domain/2);
+        let q_init = (*q_start as usize)%(original_domain/2);
         for l in 0..reduction_bits.len() {
             let q = q_init%domain_size_current;
             let next_domain_size = domain_size_current>>reduction_bits[l];
-            if !query_eval_proofs[l].contains_key(&q) {
-                query_eval_proofs[l].insert(
-                    q,
-                    QueryEvalProofs::<F,H>{
-                        merkle_proof: merkle_objs[l].proof(q%next_domain_size),
-                    });
-            }
+            level_leaf_indices[l].push(q%next_domain_size);
             domain_size_current = next_domain_size;
         }
     }
+    level_leaf_indices
+}
+
+// Generate a batched query proof per FRI level covering every query
+pub fn generate_query_eval_proofs<F: PrimeField, H: Hasher_<F>>(queries: Vec<u32>, original_domain: usize, reduction_bits: Vec<u32>, merkle_objs: &[impl Borrow<merkle::MerkleTree<F, H>>]) -> Vec<QueryEvalProofs<F, H>> {
+    query_leaf_indices(&queries, original_domain, &reduction_bits).iter().zip(merkle_objs.iter()).map(|(indices, merkle)| {
+        QueryEvalProofs::<F,H>{
+            merkle_proof: merkle.borrow().multi_proof(indices),
+        }
+    }).collect()
 }
 
 pub fn generate_fri_proof<F: PrimeField, H: Hasher_<F>> (polynomial: DensePolynomial<F>, fri_config: FriConfig)
  -> FRIProof<F, H> {
-    let mut transcript = Transcript::new(b"new transcript");
-
-    let coefficients_length = polynomial.coeffs.len();
-
-    assert!(validate_fri_config(&fri_config, coefficients_length as u32-1));
-
-    let blow_up = fri_config.blow_up_factor;
-
-    // Store merkle roots corresponding to each level
-    let mut merkle_roots: Vec<Vec<H::Hash>> = Vec::new();
-
-    // Store merkle objects corresponding to each level (used query by query to generate eval proof)
-    let mut merkle_objs: Vec<merkle::MerkleTree<F, H>> = Vec::new();
+    let mut transcript = new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR);
+    generate_fri_proof_with_transcript(polynomial, fri_config, &mut transcript)
+}
 
-    // Keeps track of current polynomial on each step of splitting and mixing
-    let mut current_polynomial = polynomial.clone();
+// Generates the proof on a caller supplied transcript (e.g. of an outer protocol, or a KeccakTranscript
+// for on-chain verification), after binding
+// the config and degree to it. Leaves the transcript after the query phase.
+pub fn generate_fri_proof_with_transcript<F: PrimeField, H: Hasher_<F>> (polynomial: DensePolynomial<F>, fri_config: FriConfig, transcript: &mut impl TranscriptProtocol<F>)
+ -> FRIProof<F, H> {
+    let degree = coefficients_degree(&polynomial);
+    generate_fri_proof_with_degree(polynomial, degree, fri_config, transcript)
+}
 
-    // Num of levels for which fri reduction is to be performed would be same as length of level reduction bits provided
-    let num_levels = fri_config.level_reductions_bits.len();
+// Proves `polynomial` against the degree bound `degree`, which may exceed its actual degree
+pub fn generate_fri_proof_with_degree<F: PrimeField, H: Hasher_<F>> (polynomial: DensePolynomial<F>, degree: u32, fri_config: FriConfig, transcript: &mut impl TranscriptProtocol<F>)
+ -> FRIProof<F, H> {
+    let vc = CappedMerkle::new(fri_config.merkle_cap_bits);
+    let data = commit(polynomial, degree, fri_config, &vc, transcript, None);
+    query_phase(&data, &vc, transcript).into()
+}
 
-    // Keeps track of evaluation and merkle proofs for each query on different levels
-    let mut query_eval_proofs: Vec<HashMap<usize, QueryEvalProofs<F,H>>> = Vec::new();
+// Degree bound taken from the number of coefficients when none is given
+fn coefficients_degree<F: PrimeField>(polynomial: &DensePolynomial<F>) -> u32 {
+    polynomial.coeffs.len().saturating_sub(1) as u32
+}
 
-    // store first level original domain ( this will help to get queries to evaluate on for each round )
-    let original_domain = coefficients_length * blow_up as usize;
+// Generates the proof with the levels committed by `vc` instead of a capped Merkle tree
+pub fn generate_fri_proof_with_vc<F: PrimeField, V: VectorCommitment<F>> (polynomial: DensePolynomial<F>, fri_config: FriConfig, vc: &V, transcript: &mut impl TranscriptProtocol<F>)
+ -> VcFRIProof<F, V> {
+    let data = commit_phase(polynomial, fri_config, vc, transcript);
+    query_phase(&data, vc, transcript)
+}
 
-    // last level final evaluation
-    // [TODO] Send polynomial coefficients instead of evals
-    // Saves verifier from a LDE check
-    let mut final_level_evaluations: Vec<F> = Vec::new();
+// Zero-knowledge variant (see fri::zk), salts and the masking polynomial are drawn from `rng`
+pub fn generate_zk_fri_proof_with_transcript<F: PrimeField, H: Hasher_<F>> (polynomial: DensePolynomial<F>, fri_config: FriConfig, zk_config: &ZkConfig, rng: &mut dyn RngCore, transcript: &mut impl TranscriptProtocol<F>)
+ -> ZkFRIProof<F, H> {
+    let vc = CappedMerkle::new(fri_config.merkle_cap_bits);
+    let degree = coefficients_degree(&polynomial);
+    let data = commit(polynomial, degree, fri_config, &vc, transcript, Some((zk_config, rng)));
+    let _query_span = debug_span!("query_phase", num_query = data.fri_config.num_query).entered();
+    let (queries, fri_proof) = answer_sampled_queries(&data, &vc, transcript);
+    let (polynomial_proof, masking_proof) = data.answer_zk_queries(&vc, &queries).unwrap();
+    ZkFRIProof {
+        fri_proof: fri_proof.into(),
+        polynomial_root: data.polynomial_commitment().unwrap().clone(),
+        masking_root: data.masking_commitment().unwrap().clone(),
+        polynomial_proof,
+        masking_proof,
+    }
+}
 
-    // offset for coset-fri, set to the group generator
-    let mut offset = F::GENERATOR;
+// Commitments and prover data of every level, kept after the commit phase so that queries can be
+// answered later without recommitting
+pub struct FriProverData<F: PrimeField, V: VectorCommitment<F>> {
+    pub(super) fri_config: FriConfig,
+    pub(super) original_domain: usize,
+    pub(super) level_commitments: Vec<V::Commitment>,
+    pub(super) level_data: Vec<V::ProverData>,
+    pub(super) polynomial: Option<V::Commitment>, // zk only
+    pub(super) masking: Option<V::Commitment>, // zk only
+    pub(super) zk_data: Vec<V::ProverData>, // zk only, prover data of the polynomial then the masking polynomial
+    pub(super) final_evaluations: Option<Vec<F>>, // set once all levels are committed
+}
 
-    println!("Generating proof :");
-    println!("num levels {:?}", num_levels);
-    println!("original domain size{:?}", original_domain);
+impl<F: PrimeField, V: VectorCommitment<F>> FriProverData<F, V> {
+    pub fn level_commitments(&self) -> &[V::Commitment] {
+        &self.level_commitments
+    }
 
-    // Iterate over each level and pre-calculate evaluations and merkles
-    println!("---- Iterating through FRI levels : ----");
+    pub fn polynomial_commitment(&self) -> Option<&V::Commitment> {
+        self.polynomial.as_ref()
+    }
 
-    let leaf_groupings = leaf_groupings(fri_config.level_reductions_bits.clone(), original_domain, num_levels);
+    pub fn masking_commitment(&self) -> Option<&V::Commitment> {
+        self.masking.as_ref()
+    }
 
-    for i in 0..num_levels+1 {
-        println!("*** Level {:?} ***", i);
-        let eval_proof = HashMap::new();
-        query_eval_proofs.push(eval_proof);
+    pub fn final_evaluations(&self) -> &[F] {
+        self.final_evaluations.as_ref().expect("Rounds left to commit")
+    }
 
-        let coeffs_length = current_polynomial.coeffs.len();
-        println!("coefficient length : {:?}", coeffs_length);
+    // Batched openings, one per level, of the leaves the queries go through
+    pub fn answer_queries(&self, vc: &V, queries: &[u32]) -> Vec<V::Opening> {
+        assert!(self.final_evaluations.is_some(), "Rounds left to commit");
+        let _open_span = debug_span!("open_queries", num_levels = self.level_data.len(), num_query = queries.len()).entered();
+        query_leaf_indices(queries, self.original_domain, &self.fri_config.level_reductions_bits).iter()
+            .zip(self.level_data.iter())
+            .map(|(indices, data)| vc.open(data, indices))
+            .collect()
+    }
 
-        // 1. Get evaluation domain (roots of unity multiplicative subroup size)
-        let domain_size = coeffs_length * blow_up as usize;
-        let mut eval_domain: GeneralEvaluationDomain<F> = GeneralEvaluationDomain::new(domain_size).unwrap();
-        eval_domain = eval_domain.get_coset(offset).expect("Error in getting coset");
-        println!("domain size : {:?}", domain_size);
-        // 2. Generate evaluations over the eval_domain
-        let evaluations: Vec<F> = eval_domain.elements().map(|d| return current_polynomial.evaluate(&d)).collect();
-        println!("level: {}, evaluations: {:?}", i, evaluations);
+    // zk only, openings of the polynomial and the masking polynomial at the first level's leaves for `queries`
+    pub fn answer_zk_queries(&self, vc: &V, queries: &[u32]) -> Option<(V::Opening, V::Opening)> {
+        let [polynomial, masking] = self.zk_data.as_slice() else {
+            return None;
+        };
+        let indices = &query_leaf_indices(queries, self.original_domain, &self.fri_config.level_reductions_bits)[0];
+        Some((vc.open(polynomial, indices), vc.open(masking, indices)))
+    }
 
-        if i==num_levels {
-            // final level evaluations
-            println!("Reached till final polynomial degree {:?}", current_polynomial.degree());
-            final_level_evaluations = evaluations;
-            break;
+    // Proof answering `queries`, without proof of work
+    pub fn proof(&self, vc: &V, queries: &[u32]) -> VcFRIProof<F, V> {
+        VcFRIProof {
+            final_evaluations: self.final_evaluations().to_vec(),
+            level_commitments: self.level_commitments.clone(),
+            level_openings: self.answer_queries(vc, queries),
+            pow_nonce: 0,
         }
+    }
+}
 
-        let reduction = 1<<fri_config.level_reductions_bits[i];
-
-        let mut merkle = merkle::MerkleTree::<F,H>::new(fri_config.merkle_cap_bits);
-
-        let mut leaves: Vec<Vec<F>> = Vec::new();
-
-        leaf_groupings[i].chunks(reduction).for_each(|leaf| {
-            let leaf_element: Vec<F> = leaf.iter().map(|l| evaluations[l.clone()]).collect();
-            leaves.push(leaf_element);
-        });
+// Commit phase: commits every level, binding the commitments and final evaluations to the transcript
+pub fn commit_phase<F: PrimeField, V: VectorCommitment<F>> (polynomial: DensePolynomial<F>, fri_config: FriConfig, vc: &V, transcript: &mut impl TranscriptProtocol<F>)
+ -> FriProverData<F, V> {
+    let degree = coefficients_degree(&polynomial);
+    commit(polynomial, degree, fri_config, vc, transcript, None)
+}
 
-        merkle.insert(leaves);
+// Query phase: grinds if the config asks for it, then draws the queries from the transcript and answers them
+pub fn query_phase<F: PrimeField, V: VectorCommitment<F>> (data: &FriProverData<F, V>, vc: &V, transcript: &mut impl TranscriptProtocol<F>)
+ -> VcFRIProof<F, V> {
+    let _query_span = debug_span!("query_phase", num_query = data.fri_config.num_query).entered();
+    answer_sampled_queries(data, vc, transcript).1
+}
 
-        merkle_roots.push(merkle.compute_tree());
-        
-        let merkle_root_cap_field:Vec<F> = merkle.root_cap.clone().unwrap().iter().map(|r| H::hash_as_field(r.clone())).collect();
-        transcript.observe_elements(b"merkle_root", &merkle_root_cap_field);
-        merkle_objs.push(merkle);
+// Samples the queries and answers them, returns the queries along with the proof
+fn answer_sampled_queries<F: PrimeField, V: VectorCommitment<F>> (data: &FriProverData<F, V>, vc: &V, transcript: &mut impl TranscriptProtocol<F>)
+ -> (Vec<u32>, VcFRIProof<F, V>) {
+    let (pow_nonce, queries) = sample_queries(&data.fri_config, transcript);
+    let proof = VcFRIProof { pow_nonce, ..data.proof(vc, &queries) };
+    (queries, proof)
+}
 
-        let verifier_rand: F = transcript.get_challenge(b"alpha");
+fn sample_queries<F: PrimeField>(fri_config: &FriConfig, transcript: &mut impl TranscriptProtocol<F>) -> (u64, Vec<u32>) {
+    let mut pow_nonce = 0;
+    if fri_config.pow_bits > 0 {
+        let _grind_span = debug_span!("grind", pow_bits = fri_config.pow_bits).entered();
+        pow_nonce = transcript.grind(fri_config.pow_bits);
+        assert!(transcript.check_pow(fri_config.pow_bits, pow_nonce), "Grinding nonce rejected");
+    }
+    let queries = transcript.get_challenge_indices(b"challenge indices", fri_config.num_query as usize);
+    trace!(pow_nonce, ?queries, "sampled queries");
+    (pow_nonce, queries)
+}
 
-        current_polynomial = fold_polynomial(current_polynomial, verifier_rand, reduction);
+fn commit<F: PrimeField, V: VectorCommitment<F>> (polynomial: DensePolynomial<F>, degree: u32, fri_config: FriConfig, vc: &V, transcript: &mut impl TranscriptProtocol<F>, zk: Option<(&ZkConfig, &mut dyn RngCore)>)
+ -> FriProverData<F, V> {
+    let num_levels = fri_config.level_reductions_bits.len();
 
-        offset = offset.pow([reduction as u64]);
+    let _commit_span = debug_span!("fri_prove", degree, num_levels, num_query = fri_config.num_query).entered();
+
+    // The interactive prover, with the verifier's messages derived from the transcript
+    observe_fri_statement::<F>(transcript, &fri_config, degree);
+    let mut prover = match zk {
+        Some((zk_config, rng)) => {
+            transcript.observe_u64(b"zk salt size", zk_config.salt_size as u64);
+            let mut prover = FriProver::new_zk(polynomial, degree, fri_config, vc, zk_config, rng);
+            transcript.observe_elements(b"polynomial_root", &vc.commitment_elements(prover.polynomial_commitment().unwrap()));
+            transcript.observe_elements(b"masking_root", &vc.commitment_elements(prover.masking_commitment().unwrap()));
+            let beta: F = transcript.get_challenge(b"masking");
+            prover.mask(beta);
+            prover
+        },
+        None => FriProver::new(polynomial, degree, fri_config, vc),
+    };
+
+    if let Some(commitment) = prover.commitment() {
+        transcript.observe_elements(b"merkle_root", &vc.commitment_elements(commitment));
     }
-
-    transcript.observe_elements(b"final evals", &final_level_evaluations);
-
-    // Iterate over each query
-    println!("--- Iterating through FRI queries from verifier ---");
-    let queries = <Transcript as TranscriptProtocol<F>>::get_challenge_indices(
-        &mut transcript,
-        b"challenge indices",
-        fri_config.num_query as usize
-    );
-    println!("Queries : {:?}", queries);
-    
-    // Generate query proofs
-    generate_query_eval_proofs::<F,H>(queries, original_domain, fri_config.level_reductions_bits, &merkle_objs, &mut query_eval_proofs);
-
-    FRIProof { 
-        final_evaluations: final_level_evaluations, 
-        query_eval_proofs,
-        level_roots: merkle_roots,
-        _h: PhantomData,
+    for _ in 0..num_levels {
+        let verifier_rand: F = transcript.get_challenge(b"alpha");
+        if let Some(commitment) = prover.commit_round(verifier_rand) {
+            transcript.observe_elements(b"merkle_root", &vc.commitment_elements(&commitment));
+        }
     }
- }
+    transcript.observe_elements(b"final evals", prover.final_polynomial());
+    prover.into_data()
+}
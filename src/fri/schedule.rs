@@ -0,0 +1,202 @@
+use ark_ff::PrimeField;
+
+use crate::hashing::hasher::Hasher_;
+
+use super::types::FriConfig;
+use super::utils::log2_exact;
+
+// Largest arity (in bits) considered for a single reduction
+pub const MAX_REDUCTION_BITS: u32 = 4;
+
+// Sizes and relative costs of the hasher used for the Merkle commitments
+#[derive(Clone, Copy, Debug)]
+pub struct HasherCosts {
+    pub digest_bytes: usize,
+    pub field_bytes: usize,
+    pub leaf_hash_cost: f64, // per field element in the leaf
+    pub compression_cost: f64, // per two to one hash
+}
+
+impl HasherCosts {
+    // Byte sizes taken from the field and hasher, unit hashing costs
+    pub fn of<F: PrimeField, H: Hasher_<F>>() -> Self {
+        Self {
+            digest_bytes: std::mem::size_of::<H::Hash>(),
+            field_bytes: F::ZERO.compressed_size(),
+            leaf_hash_cost: 1.0,
+            compression_cost: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleObjective {
+    ProofSize, // bytes of the serialised query proofs and level roots
+    VerifierHashes, // weighted leaf hashes and compressions done by the verifier
+}
+
+// Expected number of distinct positions hit by `num_query` uniform queries among `nodes`
+fn expected_distinct(nodes: f64, num_query: u32) -> f64 {
+    nodes * (1.0 - (1.0 - 1.0 / nodes).powi(num_query as i32))
+}
+
+// Expected cost of committing and opening one level with a domain of 2^log_domain and arity 2^bits
+fn level_cost(log_domain: u32, bits: u32, merkle_cap_bits: u32, num_query: u32, costs: &HasherCosts, objective: ScheduleObjective) -> f64 {
+    let log_leaves = log_domain - bits;
+    let opened = expected_distinct((1u64 << log_leaves) as f64, num_query);
+    let leaf_width = (1u64 << bits) as f64;
+    let path_levels = log_leaves - merkle_cap_bits;
+    match objective {
+        ScheduleObjective::ProofSize => {
+            // Multi-proofs only carry siblings whose subtree holds no opened leaf
+            let siblings: f64 = (0..path_levels).map(|k| {
+                let nodes = (1u64 << (log_leaves - k)) as f64;
+                2.0 * expected_distinct(nodes / 2.0, num_query) - expected_distinct(nodes, num_query)
+            }).sum();
+            let leaf_bytes = leaf_width * costs.field_bytes as f64 + std::mem::size_of::<u64>() as f64;
+            let cap_bytes = ((1u64 << merkle_cap_bits) * costs.digest_bytes as u64) as f64;
+            opened * leaf_bytes + siblings * costs.digest_bytes as f64 + cap_bytes
+        }
+        ScheduleObjective::VerifierHashes => {
+            let compressions: f64 = (1..=path_levels).map(|k| expected_distinct((1u64 << (log_leaves - k)) as f64, num_query)).sum();
+            opened * leaf_width * costs.leaf_hash_cost + compressions * costs.compression_cost
+        }
+    }
+}
+
+// Expected cost of the reduction schedule of `fri_config`, None if it is not valid for `degree`
+pub fn schedule_cost(fri_config: &FriConfig, degree: u32, costs: &HasherCosts, objective: ScheduleObjective) -> Option<f64> {
+    let log_coeffs = log2_exact(degree.checked_add(1)?)?;
+    let log_final_coeffs = log2_exact(fri_config.last_polynomial_degree.checked_add(1)?)?;
+    let log_blow_up = log2_exact(fri_config.blow_up_factor)?;
+    if log_final_coeffs > log_coeffs || fri_config.level_reductions_bits.iter().sum::<u32>() != log_coeffs - log_final_coeffs {
+        return None;
+    }
+
+    let mut log_domain = log_coeffs + log_blow_up;
+    let mut cost = 0.0;
+    for bits in &fri_config.level_reductions_bits {
+        // every level's tree must keep at least one level below the cap
+        if *bits == 0 || log_domain < bits + fri_config.merkle_cap_bits + 1 {
+            return None;
+        }
+        cost += level_cost(log_domain, *bits, fri_config.merkle_cap_bits, fri_config.num_query, costs, objective);
+        log_domain -= bits;
+    }
+    Some(cost)
+}
+
+// Searches all schedules with reductions of at most MAX_REDUCTION_BITS and returns the cheapest one.
+// Returns None if no schedule folds `degree` down to `last_polynomial_degree` with the given cap.
+pub fn optimal_reduction_schedule(
+    degree: u32,
+    blow_up_factor: u32,
+    last_polynomial_degree: u32,
+    merkle_cap_bits: u32,
+    num_query: u32,
+    costs: &HasherCosts,
+    objective: ScheduleObjective,
+) -> Option<Vec<u32>> {
+    let log_coeffs = log2_exact(degree.checked_add(1)?)?;
+    let log_final_coeffs = log2_exact(last_polynomial_degree.checked_add(1)?)?;
+    let log_blow_up = log2_exact(blow_up_factor)?;
+    if log_final_coeffs > log_coeffs {
+        return None;
+    }
+    let total_bits = (log_coeffs - log_final_coeffs) as usize;
+    let log_final_domain = log_final_coeffs + log_blow_up;
+
+    // Level costs only depend on the domain size and arity, so best[t] is the cheapest
+    // way to reduce the last t bits, starting from a domain of 2^(log_final_domain + t)
+    let mut best: Vec<Option<(f64, u32)>> = vec![None; total_bits + 1];
+    best[0] = Some((0.0, 0));
+    for t in 1..=total_bits {
+        let log_domain = log_final_domain + t as u32;
+        for bits in 1..=MAX_REDUCTION_BITS.min(t as u32) {
+            let Some((rest, _)) = best[t - bits as usize] else { continue };
+            if log_domain < bits + merkle_cap_bits + 1 {
+                continue;
+            }
+            let cost = rest + level_cost(log_domain, bits, merkle_cap_bits, num_query, costs, objective);
+            if best[t].is_none_or(|(c, _)| cost < c) {
+                best[t] = Some((cost, bits));
+            }
+        }
+    }
+
+    let mut schedule = Vec::new();
+    let mut t = total_bits;
+    while t > 0 {
+        let (_, bits) = best[t]?;
+        schedule.push(bits);
+        t -= bits as usize;
+    }
+    Some(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::{prover::generate_fri_proof, types::FriConfig, verifier::verify_fri_proof};
+    use crate::hashing::hasher::Sha256_;
+    use super::*;
+
+    // All schedules reducing `total` bits with parts in 1..=MAX_REDUCTION_BITS
+    fn all_schedules(total: u32) -> Vec<Vec<u32>> {
+        if total == 0 {
+            return vec![vec![]];
+        }
+        (1..=MAX_REDUCTION_BITS.min(total)).flat_map(|b| {
+            all_schedules(total - b).into_iter().map(move |mut s| { s.insert(0, b); s })
+        }).collect()
+    }
+
+    #[test]
+    fn test_optimal_schedule_is_minimal() {
+        let costs = HasherCosts::of::<Fq, Sha256_<Fq>>();
+        for objective in [ScheduleObjective::ProofSize, ScheduleObjective::VerifierHashes] {
+            for (degree, blow_up, last, cap, queries) in [(1023, 8, 0, 2, 28), ((1 << 14) - 1, 2, 3, 1, 80), (255, 16, 7, 4, 10)] {
+                let schedule = optimal_reduction_schedule(degree, blow_up, last, cap, queries, &costs, objective).unwrap();
+                let config = |level_reductions_bits| FriConfig { num_query: queries, blow_up_factor: blow_up, last_polynomial_degree: last, merkle_cap_bits: cap, level_reductions_bits, pow_bits: 0 };
+                let cost = schedule_cost(&config(schedule.clone()), degree, &costs, objective).unwrap();
+
+                let total = ((degree + 1) / (last + 1)).trailing_zeros();
+                let brute_force = all_schedules(total).into_iter()
+                    .filter_map(|s| schedule_cost(&config(s), degree, &costs, objective))
+                    .fold(f64::INFINITY, f64::min);
+                assert!((cost - brute_force).abs() < 1e-6, "{:?} costs {} instead of {}", schedule, cost, brute_force);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_schedule_parameters() {
+        let costs = HasherCosts::of::<Fq, Sha256_<Fq>>();
+        let objective = ScheduleObjective::ProofSize;
+        // cap as large as the final domain
+        assert_eq!(optimal_reduction_schedule(15, 2, 0, 2, 4, &costs, objective), None);
+        // degree + 1 not a power of two
+        assert_eq!(optimal_reduction_schedule(10, 2, 0, 0, 4, &costs, objective), None);
+        // final degree above the degree
+        assert_eq!(optimal_reduction_schedule(7, 2, 15, 0, 4, &costs, objective), None);
+        // schedule not ending at the final degree
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 2, last_polynomial_degree: 0 , merkle_cap_bits: 0, level_reductions_bits: vec![1,1], pow_bits: 0 };
+        assert_eq!(schedule_cost(&fri_config, 15, &costs, objective), None);
+        // nothing to fold
+        assert_eq!(optimal_reduction_schedule(7, 4, 7, 1, 4, &costs, objective), Some(vec![]));
+    }
+
+    #[test]
+    fn test_optimal_schedule_verifies() {
+        let coeffs: Vec<Fq> = (0..256).map(|i| Fq::from(i * 13 + 7)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+
+        let costs = HasherCosts::of::<Fq, Sha256_<Fq>>();
+        let level_reductions_bits = optimal_reduction_schedule(255, 4, 1, 2, 20, &costs, ScheduleObjective::ProofSize).unwrap();
+        let fri_config = FriConfig { num_query: 20, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 2, level_reductions_bits, pow_bits: 0 };
+
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+        assert!(verify_fri_proof(fri_config, 255, fri_proof));
+    }
+}
@@ -0,0 +1,231 @@
+use ark_ff::PrimeField;
+
+use super::{types::FriConfig, utils::{FriConfigError, MAX_POW_BITS}};
+
+// Soundness analysis used to size the query phase
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundnessRegime {
+    // ethSTARK conjecture: each query contributes log2(blow_up) bits
+    Conjectured,
+    // Johnson bound analysis of [BCIKS20], each query contributes roughly log2(blow_up)/2 bits
+    Proven,
+}
+
+// Achieved security level of a config, in bits, and the queries its shape needs under each regime to
+// reach the conjectured level
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SecurityBits {
+    pub conjectured: f64,
+    pub proven: f64,
+    pub num_query: QueryCounts,
+}
+
+// Number of queries reaching a security level under each regime, None if the field is too small for it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueryCounts {
+    pub conjectured: Option<u32>,
+    pub proven: Option<u32>,
+}
+
+// Largest proximity parameter m tried when optimising the proven bound
+const MAX_JOHNSON_M: u32 = 64;
+
+// Conservative field size in bits (|F| >= 2^(MODULUS_BIT_SIZE-1))
+fn field_bits<F: PrimeField>() -> f64 {
+    (F::MODULUS_BIT_SIZE - 1) as f64
+}
+
+// -log2(a + b) for a = 2^-x, b = 2^-y
+fn combine_errors(x: f64, y: f64) -> f64 {
+    -(2f64.powf(-x) + 2f64.powf(-y)).log2()
+}
+
+// Security of the protocol for the given domain size, rate, arities and grinding. Grinding adds pow_bits
+// to the query phase, the prover needs 2^pow_bits hashes per attempt at favourable queries.
+fn security<F: PrimeField>(
+    regime: SoundnessRegime,
+    num_query: u32,
+    blow_up_factor: u32,
+    domain_size: u64,
+    level_reductions_bits: &[u32],
+    pow_bits: u32,
+) -> f64 {
+    let rate_bits = (blow_up_factor as f64).log2();
+    let log_domain = (domain_size as f64).log2();
+    match regime {
+        SoundnessRegime::Conjectured => {
+            // commit phase error |D|/|F|, query phase error rho^s
+            let commit_bits = field_bits::<F>() - log_domain;
+            let query_bits = num_query as f64 * rate_bits + pow_bits as f64;
+            combine_errors(commit_bits, query_bits)
+        }
+        SoundnessRegime::Proven => {
+            let rho = 1.0 / blow_up_factor as f64;
+            let sum_arities: f64 = level_reductions_bits.iter().map(|b| (1u64 << b) as f64).sum();
+            (3..=MAX_JOHNSON_M).map(|m| {
+                let m = m as f64;
+                // commit phase error:
+                // (m+1/2)^7 / (3 rho^(3/2)) * |D|^2/|F| + (2m+1)(|D|+1)/sqrt(rho) * sum(arities)/|F|
+                let e1 = (m + 0.5).powi(7) / (3.0 * rho.powf(1.5)) * 2f64.powf(2.0 * log_domain - field_bits::<F>());
+                let e2 = (2.0 * m + 1.0) * (domain_size as f64 + 1.0) / rho.sqrt() * sum_arities * 2f64.powf(-field_bits::<F>());
+                let commit_bits = -(e1 + e2).log2();
+                // query phase error (sqrt(rho) (1 + 1/2m))^s
+                let alpha = rho.sqrt() * (1.0 + 1.0 / (2.0 * m));
+                let query_bits = -(num_query as f64) * alpha.log2() + pow_bits as f64;
+                combine_errors(commit_bits, query_bits)
+            }).fold(f64::NEG_INFINITY, f64::max)
+        }
+    }
+}
+
+// Fewest queries reaching `target_bits` under each regime
+fn query_counts<F: PrimeField>(target_bits: f64, blow_up_factor: u32, domain_size: u64, level_reductions_bits: &[u32], pow_bits: u32) -> QueryCounts {
+    let count = |regime| {
+        let bits = |s| security::<F>(regime, s, blow_up_factor, domain_size, level_reductions_bits, pow_bits);
+        // Even infinitely many queries cannot beat the commit phase error, which only depends on the field
+        if bits(u32::MAX) < target_bits {
+            return None;
+        }
+        (1..).find(|s| bits(*s) >= target_bits)
+    };
+    QueryCounts {
+        conjectured: count(SoundnessRegime::Conjectured),
+        proven: count(SoundnessRegime::Proven),
+    }
+}
+
+impl FriConfig {
+    // Builds a config reaching `target_bits` of security under `regime` for polynomials of degree `degree`,
+    // with `pow_bits` of grinding. Folds by 2 at every level down to a constant final polynomial.
+    pub fn for_security<F: PrimeField>(target_bits: u32, degree: u32, blow_up_factor: u32, pow_bits: u32, regime: SoundnessRegime) -> Result<FriConfig, FriConfigError> {
+        if blow_up_factor < 2 || !blow_up_factor.is_power_of_two() {
+            return Err(FriConfigError::InvalidBlowUpFactor(blow_up_factor));
+        }
+        if pow_bits > MAX_POW_BITS {
+            return Err(FriConfigError::PowBitsTooLarge(pow_bits));
+        }
+
+        let coeffs_length = (degree as u64 + 1).next_power_of_two();
+        let domain_size = coeffs_length * blow_up_factor as u64;
+        let level_reductions_bits = vec![1; coeffs_length.trailing_zeros() as usize];
+
+        let counts = query_counts::<F>(target_bits as f64, blow_up_factor, domain_size, &level_reductions_bits, pow_bits);
+        let num_query = match regime {
+            SoundnessRegime::Conjectured => counts.conjectured,
+            SoundnessRegime::Proven => counts.proven,
+        };
+        let num_query = num_query.ok_or_else(|| {
+            let max_bits = security::<F>(regime, u32::MAX, blow_up_factor, domain_size, &level_reductions_bits, pow_bits);
+            FriConfigError::ChallengeFieldTooSmall { target_bits, max_bits: max_bits.floor() as u32 }
+        })?;
+
+        Ok(FriConfig {
+            num_query,
+            blow_up_factor,
+            last_polynomial_degree: 0,
+            merkle_cap_bits: 0,
+            level_reductions_bits,
+            pow_bits,
+        })
+    }
+
+    // Security reached by this config for polynomials of degree `degree` over the field F
+    pub fn security_bits<F: PrimeField>(&self, degree: u32) -> SecurityBits {
+        let domain_size = (degree as u64 + 1).next_power_of_two() * self.blow_up_factor as u64;
+        let bits = |regime| security::<F>(regime, self.num_query, self.blow_up_factor, domain_size, &self.level_reductions_bits, self.pow_bits);
+        let conjectured = bits(SoundnessRegime::Conjectured);
+        SecurityBits {
+            conjectured,
+            proven: bits(SoundnessRegime::Proven),
+            num_query: query_counts::<F>(conjectured, self.blow_up_factor, domain_size, &self.level_reductions_bits, self.pow_bits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::{prover::generate_fri_proof, types::{FRIProof, FriConfig}, verifier::verify_fri_proof};
+    use crate::hashing::hasher::Sha256_;
+    use crate::fri::utils::FriConfigError;
+    use super::SoundnessRegime;
+
+    #[test]
+    fn test_conjectured_num_query() {
+        // 3 bits per query, commit phase error 2^-(63-13)
+        let config = FriConfig::for_security::<Fq>(40, 1023, 8, 0, SoundnessRegime::Conjectured).unwrap();
+        assert_eq!(config.num_query, 14);
+        assert_eq!(config.level_reductions_bits, vec![1; 10]);
+
+        let security = config.security_bits::<Fq>(1023);
+        assert!(security.conjectured >= 40.0);
+        assert!(security.proven < security.conjectured);
+        // the config's own query count reaches its level, the proven bound never does over Goldilocks
+        assert_eq!(security.num_query.conjectured, Some(14));
+        assert_eq!(security.num_query.proven, None);
+
+        // one query less does not reach the target
+        let weaker = FriConfig { num_query: 13, ..config };
+        assert!(weaker.security_bits::<Fq>(1023).conjectured < 40.0);
+
+        // grinding replaces queries
+        let ground = FriConfig::for_security::<Fq>(40, 1023, 8, 12, SoundnessRegime::Conjectured).unwrap();
+        assert_eq!((ground.num_query, ground.pow_bits), (10, 12));
+        assert!(ground.security_bits::<Fq>(1023).conjectured >= 40.0);
+        assert!(FriConfig { pow_bits: 0, ..ground }.security_bits::<Fq>(1023).conjectured < 40.0);
+    }
+
+    #[test]
+    fn test_proven_num_query() {
+        let conjectured = FriConfig::for_security::<Fq>(20, 255, 16, 0, SoundnessRegime::Conjectured).unwrap();
+        let proven = FriConfig::for_security::<Fq>(20, 255, 16, 0, SoundnessRegime::Proven).unwrap();
+        assert!(proven.num_query > conjectured.num_query);
+        assert!(proven.security_bits::<Fq>(255).proven >= 20.0);
+        // 24 conjectured bits are out of the proven bound's reach here, 16 bits take 9 queries instead of 4
+        assert_eq!(conjectured.security_bits::<Fq>(255).num_query.proven, None);
+        let weaker = FriConfig { num_query: 4, ..conjectured };
+        let security = weaker.security_bits::<Fq>(255);
+        assert_eq!(security.num_query.conjectured, Some(4));
+        let num_query = security.num_query.proven.unwrap();
+        assert_eq!(num_query, 9);
+        assert!(FriConfig { num_query, ..weaker.clone() }.security_bits::<Fq>(255).proven >= security.conjectured);
+        assert!(FriConfig { num_query: num_query - 1, ..weaker }.security_bits::<Fq>(255).proven < security.conjectured);
+
+        let ground = FriConfig::for_security::<Fq>(20, 255, 16, 8, SoundnessRegime::Proven).unwrap();
+        assert!(ground.num_query < proven.num_query);
+        assert!(ground.security_bits::<Fq>(255).proven >= 20.0);
+    }
+
+    #[test]
+    fn test_field_too_small() {
+        // 64 bit field cannot give 100 bits without an extension
+        let result = FriConfig::for_security::<Fq>(100, (1 << 16) - 1, 8, 0, SoundnessRegime::Conjectured);
+        assert_eq!(result, Err(FriConfigError::ChallengeFieldTooSmall { target_bits: 100, max_bits: 44 }));
+        // grinding does not help with the commit phase error
+        let result = FriConfig::for_security::<Fq>(100, (1 << 16) - 1, 8, 20, SoundnessRegime::Conjectured);
+        assert_eq!(result, Err(FriConfigError::ChallengeFieldTooSmall { target_bits: 100, max_bits: 44 }));
+
+        let result = FriConfig::for_security::<Fq>(20, 15, 3, 0, SoundnessRegime::Conjectured);
+        assert_eq!(result, Err(FriConfigError::InvalidBlowUpFactor(3)));
+        let result = FriConfig::for_security::<Fq>(20, 15, 4, 33, SoundnessRegime::Conjectured);
+        assert_eq!(result, Err(FriConfigError::PowBitsTooLarge(33)));
+    }
+
+    #[test]
+    fn test_generated_config_verifies() {
+        let coeffs: Vec<Fq> = (0..64).map(|i| Fq::from(i * i + 5)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+
+        let fri_config = FriConfig::for_security::<Fq>(30, 63, 4, 0, SoundnessRegime::Conjectured).unwrap();
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly.clone(), fri_config.clone());
+        assert!(verify_fri_proof(fri_config, 63, fri_proof));
+
+        let fri_config = FriConfig::for_security::<Fq>(30, 63, 4, 10, SoundnessRegime::Conjectured).unwrap();
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+        assert!(verify_fri_proof(fri_config.clone(), 63, fri_proof.clone()));
+        // the nonce is checked
+        let verifies = |pow_nonce| verify_fri_proof(fri_config.clone(), 63, FRIProof { pow_nonce, ..fri_proof.clone() });
+        assert!((0..fri_proof.pow_nonce).all(|nonce| !verifies(nonce)));
+    }
+}
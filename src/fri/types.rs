@@ -1,27 +1,265 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::marker::PhantomData;
 
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate, Write};
 
 use crate::{hashing::hasher::Hasher_, merkle_tree::merkle};
+use crate::vector_commitment::{merkle::CappedMerkle, VectorCommitment};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+// Version of the binary proof encoding, written as the first byte of a serialised FRIProof
+pub const FRI_PROOF_VERSION: u8 = 2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FriConfig {
     pub num_query: u32,
     pub blow_up_factor: u32,
     pub last_polynomial_degree: u32,
     pub merkle_cap_bits: u32, // merkle proofs roots level
     pub level_reductions_bits: Vec<u32>, // array of bits
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pow_bits: u32, // grinding before the queries are drawn
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct QueryEvalProofs<F: PrimeField,H: Hasher_<F>> {
-    pub merkle_proof: merkle::MerkleProof<F, H>,
+    pub merkle_proof: merkle::MerkleMultiProof<F, H>, // opens every leaf queried at this level
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct FRIProof<F: PrimeField, H:Hasher_<F>> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::field_vec"))]
     pub final_evaluations: Vec<F>,
-    pub query_eval_proofs: Vec<HashMap<usize, QueryEvalProofs<F,H>>>, // len -> number of rounds
+    pub query_eval_proofs: Vec<QueryEvalProofs<F,H>>, // len -> number of rounds
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::hash_vec_vec"))]
     pub level_roots: Vec<Vec<H::Hash>>,
+    pub pow_nonce: u64, // proof of work nonce, 0 without grinding
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub _h: PhantomData<H>
 }
+
+// FRI proof over any vector commitment backend, one commitment and one opening per level.
+// FRIProof is the CappedMerkle instance and converts to and from it.
+pub struct VcFRIProof<F: PrimeField, V: VectorCommitment<F>> {
+    pub final_evaluations: Vec<F>,
+    pub level_commitments: Vec<V::Commitment>,
+    pub level_openings: Vec<V::Opening>, // opens every leaf queried at this level
+    pub pow_nonce: u64,
+}
+
+impl<F: PrimeField, H: Hasher_<F>> From<VcFRIProof<F, CappedMerkle<F, H>>> for FRIProof<F, H> {
+    fn from(proof: VcFRIProof<F, CappedMerkle<F, H>>) -> Self {
+        FRIProof {
+            final_evaluations: proof.final_evaluations,
+            query_eval_proofs: proof.level_openings.into_iter().map(|merkle_proof| QueryEvalProofs { merkle_proof }).collect(),
+            level_roots: proof.level_commitments,
+            pow_nonce: proof.pow_nonce,
+            _h: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> From<FRIProof<F, H>> for VcFRIProof<F, CappedMerkle<F, H>> {
+    fn from(proof: FRIProof<F, H>) -> Self {
+        VcFRIProof {
+            final_evaluations: proof.final_evaluations,
+            level_commitments: proof.level_roots,
+            level_openings: proof.query_eval_proofs.into_iter().map(|p| p.merkle_proof).collect(),
+            pow_nonce: proof.pow_nonce,
+        }
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for QueryEvalProofs<F, H> {
+    fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> Result<(), SerializationError> {
+        self.merkle_proof.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.merkle_proof.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> Valid for QueryEvalProofs<F, H> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.merkle_proof.check()
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalDeserialize for QueryEvalProofs<F, H> {
+    fn deserialize_with_mode<R: Read>(reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        Ok(QueryEvalProofs {
+            merkle_proof: merkle::MerkleMultiProof::deserialize_with_mode(reader, compress, validate)?,
+        })
+    }
+}
+
+// Layout: version byte, final evaluations, level roots, query proofs (one per level, in level order),
+// proof of work nonce
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for FRIProof<F, H> {
+    fn serialize_with_mode<W: Write>(&self, mut writer: W, compress: Compress) -> Result<(), SerializationError> {
+        FRI_PROOF_VERSION.serialize_with_mode(&mut writer, compress)?;
+        self.final_evaluations.serialize_with_mode(&mut writer, compress)?;
+        self.level_roots.serialize_with_mode(&mut writer, compress)?;
+        self.query_eval_proofs.serialize_with_mode(&mut writer, compress)?;
+        self.pow_nonce.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        FRI_PROOF_VERSION.serialized_size(compress)
+            + self.final_evaluations.serialized_size(compress)
+            + self.level_roots.serialized_size(compress)
+            + self.query_eval_proofs.serialized_size(compress)
+            + self.pow_nonce.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> Valid for FRIProof<F, H> {
+    fn check(&self) -> Result<(), SerializationError> {
+        // every committed level is opened, and every root cap has a power of two length
+        if self.level_roots.len() != self.query_eval_proofs.len()
+            || self.level_roots.iter().any(|cap| !cap.len().is_power_of_two()) {
+            return Err(SerializationError::InvalidData);
+        }
+        QueryEvalProofs::batch_check(self.query_eval_proofs.iter())
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalDeserialize for FRIProof<F, H> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let version = u8::deserialize_with_mode(&mut reader, compress, validate)?;
+        if version != FRI_PROOF_VERSION {
+            return Err(SerializationError::InvalidData);
+        }
+        let proof = FRIProof {
+            final_evaluations: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            level_roots: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            query_eval_proofs: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            pow_nonce: u64::deserialize_with_mode(&mut reader, compress, validate)?,
+            _h: PhantomData,
+        };
+        if let Validate::Yes = validate {
+            proof.check()?;
+        }
+        Ok(proof)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> FRIProof<F, H> {
+    // Encodes the proof in the canonical (compressed) binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.compressed_size());
+        self.serialize_compressed(&mut bytes).expect("Serialization Failed");
+        bytes
+    }
+
+    // Decodes a proof, rejecting unknown versions, malformed content and trailing bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let mut reader = bytes;
+        let proof = Self::deserialize_compressed(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_serialize::SerializationError;
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::{prover::generate_fri_proof, verifier::verify_fri_proof};
+    use crate::hashing::hasher::Sha256_;
+    use super::{FRIProof, FriConfig, FRI_PROOF_VERSION};
+
+    fn sample_proof() -> (FriConfig, FRIProof<Fq, Sha256_<Fq>>) {
+        let coeffs: Vec<Fq> = (0..16).map(|i| Fq::from(i * 3 + 1)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 };
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(poly, fri_config.clone());
+        (fri_config, fri_proof)
+    }
+
+    #[test]
+    fn test_proof_round_trip() {
+        let (fri_config, fri_proof) = sample_proof();
+        let bytes = fri_proof.to_bytes();
+        assert_eq!(bytes[0], FRI_PROOF_VERSION);
+
+        let decoded = FRIProof::<Fq, Sha256_<Fq>>::from_bytes(&bytes).unwrap();
+        // encoding is deterministic
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(sample_proof().1.to_bytes(), bytes);
+
+        assert!(verify_fri_proof(fri_config, 15, decoded));
+    }
+
+    #[test]
+    fn test_proof_malformed_input() {
+        let (_, fri_proof) = sample_proof();
+        let bytes = fri_proof.to_bytes();
+        let decode = |b: &[u8]| FRIProof::<Fq, Sha256_<Fq>>::from_bytes(b);
+
+        assert!(decode(&[]).is_err());
+
+        // unknown version
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = FRI_PROOF_VERSION + 1;
+        assert!(matches!(decode(&wrong_version), Err(SerializationError::InvalidData)));
+
+        // every strict prefix is rejected
+        for len in 0..bytes.len() {
+            assert!(decode(&bytes[..len]).is_err(), "prefix of length {} decoded", len);
+        }
+
+        // trailing bytes
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(decode(&trailing), Err(SerializationError::InvalidData)));
+
+        // number of level roots does not match number of levels opened
+        let mut missing_level = fri_proof.clone();
+        missing_level.level_roots.pop();
+        assert!(matches!(decode(&missing_level.to_bytes()), Err(SerializationError::InvalidData)));
+
+        // field element outside the modulus
+        let mut non_canonical = bytes.clone();
+        // first final evaluation, after the version byte and the u64 length prefix
+        non_canonical[9..17].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(decode(&non_canonical).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_proof_json() {
+        let (fri_config, fri_proof) = sample_proof();
+
+        let config_json = serde_json::to_string(&fri_config).unwrap();
+        let decoded_config: FriConfig = serde_json::from_str(&config_json).unwrap();
+        assert_eq!(decoded_config.level_reductions_bits, fri_config.level_reductions_bits);
+
+        let json = serde_json::to_value(&fri_proof).unwrap();
+        // field elements are decimal strings
+        assert_eq!(json["final_evaluations"][0], crate::fields::serde_utils::field_to_string(&fri_proof.final_evaluations[0]));
+
+        let decoded: FRIProof<Fq, Sha256_<Fq>> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(decoded.to_bytes(), fri_proof.to_bytes());
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+        assert!(verify_fri_proof(decoded_config, 15, decoded));
+
+        // non-canonical field element
+        let mut bad = json.clone();
+        bad["final_evaluations"][0] = "18446744069414584321".into();
+        assert!(serde_json::from_value::<FRIProof<Fq, Sha256_<Fq>>>(bad).is_err());
+
+        // malformed hash
+        let mut bad = json;
+        bad["level_roots"][0][0] = "not hex".into();
+        assert!(serde_json::from_value::<FRIProof<Fq, Sha256_<Fq>>>(bad).is_err());
+    }
+}
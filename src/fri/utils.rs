@@ -1,21 +1,191 @@
+use std::fmt;
+
+use ark_ff::{FftField, PrimeField};
+use merlin::Transcript;
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+
 use super::types::FriConfig;
 
-/// Validates whether FRI config is valid
-pub fn validate_fri_config (fri_config: &FriConfig, poly_degree: u32) -> bool {
-    // Smallest possible array in FRI construction: blow_up * (last_poly_degree+1)
-    // for merkle proof gen the smallest array utilised would be :
-    // (blow_up * (last_poly_degree+1) * 2**(level_reductions_bits[level_reductions_bits.len()-1]))/2**(level_reductions_bits[level_reductions_bits.len()-1]
-    assert!(fri_config.blow_up_factor * (fri_config.last_polynomial_degree+1) >= 1<<(fri_config.merkle_cap_bits+1), "Invalid FRI params (merkle cap)");
-
-    // Verifies the reductions dont leave the array greater than last_polynomial_degree
-    let redns = fri_config.level_reductions_bits.clone();
-    let mut curr_deg = poly_degree;
-    for i in 0..redns.len(){
-        if curr_deg <= fri_config.last_polynomial_degree {
-            return true;
-        }
-        curr_deg = curr_deg>>redns[i];
+// Domain separator used by generate_fri_proof / verify_fri_proof
+pub const DEFAULT_DOMAIN_SEPARATOR: &[u8] = b"fri-commitment";
+
+// Reasons a FriConfig cannot be used to prove or verify a polynomial of a given degree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FriConfigError {
+    NoQueries,
+    InvalidBlowUpFactor(u32), // must be a power of two greater than 1
+    DegreeNotPowerOfTwo(u32), // degree + 1 must be a power of two
+    LastDegreeNotPowerOfTwo(u32), // last_polynomial_degree + 1 must be a power of two
+    LastDegreeTooLarge { degree: u32, last_polynomial_degree: u32 },
+    ZeroReduction { level: usize },
+    InsufficientReductions { required_bits: u32, provided_bits: u32 },
+    ExcessReductions { required_bits: u32, provided_bits: u32 },
+    MerkleCapTooLarge { merkle_cap_bits: u32, final_domain_size: u64 },
+    DomainTooLarge { domain_bits: u32, max_bits: u32 },
+    ChallengeFieldTooSmall { target_bits: u32, max_bits: u32 },
+    PowBitsTooLarge(u32), // proof of work is checked on a 32 bit challenge index
+}
+
+impl fmt::Display for FriConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FriConfigError::NoQueries => write!(f, "at least one query is required"),
+            FriConfigError::InvalidBlowUpFactor(b) => write!(f, "blow up factor {} is not a power of two greater than 1", b),
+            FriConfigError::DegreeNotPowerOfTwo(d) => write!(f, "degree {} is not one less than a power of two", d),
+            FriConfigError::LastDegreeNotPowerOfTwo(d) => write!(f, "last polynomial degree {} is not one less than a power of two", d),
+            FriConfigError::LastDegreeTooLarge { degree, last_polynomial_degree } =>
+                write!(f, "last polynomial degree {} exceeds degree {}", last_polynomial_degree, degree),
+            FriConfigError::ZeroReduction { level } => write!(f, "level {} has a reduction of 0 bits", level),
+            FriConfigError::InsufficientReductions { required_bits, provided_bits } =>
+                write!(f, "reductions fold {} bits, {} are needed to reach the last polynomial degree", provided_bits, required_bits),
+            FriConfigError::ExcessReductions { required_bits, provided_bits } =>
+                write!(f, "reductions fold {} bits, past the last polynomial degree reached after {}", provided_bits, required_bits),
+            FriConfigError::MerkleCapTooLarge { merkle_cap_bits, final_domain_size } =>
+                write!(f, "merkle cap of {} bits needs a final domain larger than {}", merkle_cap_bits, final_domain_size),
+            FriConfigError::DomainTooLarge { domain_bits, max_bits } =>
+                write!(f, "evaluation domain of 2^{} exceeds the supported 2^{}", domain_bits, max_bits),
+            FriConfigError::ChallengeFieldTooSmall { target_bits, max_bits } =>
+                write!(f, "challenge field too small for {} bits of security (at most {})", target_bits, max_bits),
+            FriConfigError::PowBitsTooLarge(bits) => write!(f, "{} bits of proof of work exceed the supported {}", bits, MAX_POW_BITS),
+        }
     }
+}
+
+impl std::error::Error for FriConfigError {}
+
+// Queries and domain indices are u32
+pub(crate) const MAX_DOMAIN_BITS: u32 = 31;
+
+// Grinding is checked on the trailing zeros of a u32 challenge index
+pub(crate) const MAX_POW_BITS: u32 = 32;
 
-    curr_deg <= fri_config.last_polynomial_degree
-}
\ No newline at end of file
+// log2 of `x`, None if it is not a power of two
+pub(crate) fn log2_exact(x: u32) -> Option<u32> {
+    x.is_power_of_two().then(|| x.trailing_zeros())
+}
+
+impl FriConfig {
+    /// Validates whether FRI config can be used for a polynomial of degree `degree` over F
+    pub fn validate<F: PrimeField>(&self, degree: u32) -> Result<(), FriConfigError> {
+        if self.num_query == 0 {
+            return Err(FriConfigError::NoQueries);
+        }
+        if self.pow_bits > MAX_POW_BITS {
+            return Err(FriConfigError::PowBitsTooLarge(self.pow_bits));
+        }
+        let log_blow_up = match log2_exact(self.blow_up_factor) {
+            Some(bits) if bits > 0 => bits,
+            _ => return Err(FriConfigError::InvalidBlowUpFactor(self.blow_up_factor)),
+        };
+        let log_coeffs = degree.checked_add(1).and_then(log2_exact)
+            .ok_or(FriConfigError::DegreeNotPowerOfTwo(degree))?;
+        let log_final_coeffs = self.last_polynomial_degree.checked_add(1).and_then(log2_exact)
+            .ok_or(FriConfigError::LastDegreeNotPowerOfTwo(self.last_polynomial_degree))?;
+        if log_final_coeffs > log_coeffs {
+            return Err(FriConfigError::LastDegreeTooLarge { degree, last_polynomial_degree: self.last_polynomial_degree });
+        }
+
+        // Domain is a multiplicative subgroup (coset) of size blow_up * (degree+1)
+        let domain_bits = log_coeffs + log_blow_up;
+        let max_bits = <F as FftField>::TWO_ADICITY.min(MAX_DOMAIN_BITS);
+        if domain_bits > max_bits {
+            return Err(FriConfigError::DomainTooLarge { domain_bits, max_bits });
+        }
+
+        // Reductions must land exactly on the last polynomial degree, which fixes the final evaluations length
+        if let Some(level) = self.level_reductions_bits.iter().position(|bits| *bits == 0) {
+            return Err(FriConfigError::ZeroReduction { level });
+        }
+        let required_bits = log_coeffs - log_final_coeffs;
+        let provided_bits = self.level_reductions_bits.iter().fold(0u32, |acc, bits| acc.saturating_add(*bits));
+        if provided_bits < required_bits {
+            return Err(FriConfigError::InsufficientReductions { required_bits, provided_bits });
+        }
+        if provided_bits > required_bits {
+            return Err(FriConfigError::ExcessReductions { required_bits, provided_bits });
+        }
+
+        // Smallest merkle tree is the last level one, with blow_up * (last_poly_degree+1) leaves.
+        // It needs at least one level below the cap.
+        let final_domain_bits = log_final_coeffs + log_blow_up;
+        if final_domain_bits < self.merkle_cap_bits.saturating_add(1) {
+            return Err(FriConfigError::MerkleCapTooLarge {
+                merkle_cap_bits: self.merkle_cap_bits,
+                final_domain_size: 1u64 << final_domain_bits,
+            });
+        }
+        Ok(())
+    }
+}
+
+// Starts a FRI transcript separated from other protocols and instances by `domain_separator`
+pub fn new_fri_transcript(domain_separator: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"FRI");
+    transcript.append_message(b"domain separator", domain_separator);
+    transcript
+}
+
+// Binds the statement (config, degree bound and coset offset) to the transcript before any commitment
+pub fn observe_fri_statement<F: PrimeField>(transcript: &mut impl TranscriptProtocol<F>, fri_config: &FriConfig, degree: u32) {
+    transcript.observe_u64(b"num query", fri_config.num_query as u64);
+    transcript.observe_u64(b"blow up factor", fri_config.blow_up_factor as u64);
+    transcript.observe_u64(b"last polynomial degree", fri_config.last_polynomial_degree as u64);
+    transcript.observe_u64(b"merkle cap bits", fri_config.merkle_cap_bits as u64);
+    transcript.observe_u64(b"num levels", fri_config.level_reductions_bits.len() as u64);
+    for bits in fri_config.level_reductions_bits.iter() {
+        transcript.observe_u64(b"level reduction bits", *bits as u64);
+    }
+    transcript.observe_u64(b"pow bits", fri_config.pow_bits as u64);
+    transcript.observe_u64(b"degree", degree as u64);
+    transcript.observe_element(b"coset offset", &F::GENERATOR);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::types::FriConfig;
+    use super::FriConfigError;
+
+    fn config(num_query: u32, blow_up_factor: u32, last_polynomial_degree: u32, merkle_cap_bits: u32, level_reductions_bits: Vec<u32>) -> FriConfig {
+        FriConfig { num_query, blow_up_factor, last_polynomial_degree, merkle_cap_bits, level_reductions_bits, pow_bits: 0 }
+    }
+
+    #[test]
+    fn test_valid_configs() {
+        assert_eq!(config(4, 2, 0, 0, vec![1,2]).validate::<Fq>(7), Ok(()));
+        assert_eq!(config(4, 4, 3, 2, vec![2,1]).validate::<Fq>(31), Ok(()));
+        assert_eq!(config(1, 2, 7, 0, vec![]).validate::<Fq>(7), Ok(()));
+        assert_eq!(FriConfig { pow_bits: 32, ..config(4, 2, 0, 0, vec![3]) }.validate::<Fq>(7), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        let cases: Vec<(FriConfig, u32, FriConfigError)> = vec![
+            (config(0, 2, 0, 0, vec![3]), 7, FriConfigError::NoQueries),
+            (config(4, 1, 0, 0, vec![3]), 7, FriConfigError::InvalidBlowUpFactor(1)),
+            (config(4, 0, 0, 0, vec![3]), 7, FriConfigError::InvalidBlowUpFactor(0)),
+            (config(4, 6, 0, 0, vec![3]), 7, FriConfigError::InvalidBlowUpFactor(6)),
+            (config(4, 2, 0, 0, vec![3]), 8, FriConfigError::DegreeNotPowerOfTwo(8)),
+            (config(4, 2, 0, 0, vec![3]), u32::MAX, FriConfigError::DegreeNotPowerOfTwo(u32::MAX)),
+            (config(4, 2, 2, 0, vec![1]), 7, FriConfigError::LastDegreeNotPowerOfTwo(2)),
+            (config(4, 2, 15, 0, vec![]), 7, FriConfigError::LastDegreeTooLarge { degree: 7, last_polynomial_degree: 15 }),
+            (config(4, 2, 0, 0, vec![1,0,2]), 7, FriConfigError::ZeroReduction { level: 1 }),
+            (config(4, 2, 0, 0, vec![1,1]), 7, FriConfigError::InsufficientReductions { required_bits: 3, provided_bits: 2 }),
+            // folds past a constant polynomial
+            (config(4, 2, 0, 0, vec![2,2]), 7, FriConfigError::ExcessReductions { required_bits: 3, provided_bits: 4 }),
+            (config(4, 2, 1, 0, vec![1,1,1]), 7, FriConfigError::ExcessReductions { required_bits: 2, provided_bits: 3 }),
+            (config(4, 2, 0, 0, vec![u32::MAX, 1]), 7, FriConfigError::ExcessReductions { required_bits: 3, provided_bits: u32::MAX }),
+            (config(4, 2, 0, 1, vec![3]), 7, FriConfigError::MerkleCapTooLarge { merkle_cap_bits: 1, final_domain_size: 2 }),
+            (config(4, 4, 1, 3, vec![2]), 7, FriConfigError::MerkleCapTooLarge { merkle_cap_bits: 3, final_domain_size: 8 }),
+            (config(4, 4, 1, u32::MAX, vec![2]), 7, FriConfigError::MerkleCapTooLarge { merkle_cap_bits: u32::MAX, final_domain_size: 8 }),
+            // Goldilocks has two-adicity 32, indices are limited to 31 bits
+            (config(4, 2, 0, 0, vec![1; 31]), (1 << 31) - 1, FriConfigError::DomainTooLarge { domain_bits: 32, max_bits: 31 }),
+            (FriConfig { pow_bits: 33, ..config(4, 2, 0, 0, vec![3]) }, 7, FriConfigError::PowBitsTooLarge(33)),
+        ];
+        for (fri_config, degree, expected) in cases {
+            assert_eq!(fri_config.validate::<Fq>(degree), Err(expected.clone()), "expected {:?}", expected);
+            assert!(!expected.to_string().is_empty());
+        }
+    }
+}
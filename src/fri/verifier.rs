@@ -1,10 +1,14 @@
 use ark_ff::PrimeField;
 use ark_poly::{GeneralEvaluationDomain, EvaluationDomain};
-use merlin::Transcript;
+use tracing::debug_span;
 
-use crate::{hashing::hasher::Hasher_, merkle_tree::merkle::merkle_path_verify, fiat_shamir::fiat_shamir::TranscriptProtocol};
+use crate::{hashing::hasher::Hasher_, fiat_shamir::fiat_shamir::TranscriptProtocol};
+use crate::vector_commitment::{merkle::CappedMerkle, VectorCommitment};
 
-use super::types::{FRIProof, FriConfig};
+use super::interactive::FriVerifier;
+use super::types::{FRIProof, FriConfig, VcFRIProof};
+use super::utils::{new_fri_transcript, observe_fri_statement, DEFAULT_DOMAIN_SEPARATOR};
+use super::zk::{ZkConfig, ZkConfigError, ZkFRIProof};
 
 // Interpolates the previous level (xi,yi) and uses verifier challenge to compute value at next level
 pub fn calcualate_next_level_value<F: PrimeField + std::convert::From<i32>>(
@@ -35,124 +39,103 @@ pub fn calcualate_next_level_value<F: PrimeField + std::convert::From<i32>>(
     next_level_val
 }
 
-pub fn verify_fri_proof<F: PrimeField + std::convert::From<i32>, H: Hasher_<F>> (fri_config: FriConfig, degree: u32, fri_proof: FRIProof<F,H>) -> bool {
-    println!("--- Verifying FRI LDE check for degree {:?} ---", degree);
-
-    let final_evaluations = fri_proof.final_evaluations;
-
-    let final_evalutaion_degree = fri_config.last_polynomial_degree;
-
-    let eval_proofs = fri_proof.query_eval_proofs;
-    let level_roots = fri_proof.level_roots;
-
-    let mut transcript = Transcript::new(b"new transcript");
+// Evaluates at `challenge` the interpolant of `evaluations` over the coset s*<zeta>, where zeta generates
+// `coset_domain` of the same size. With d = iFFT(evaluations) the interpolant is sum d_t (x/s)^t, so this
+// only needs s^-1, which callers can batch invert.
+pub fn fold_coset_evaluations<F: PrimeField>(evaluations: &[F], challenge: F, shift_inv: F, coset_domain: &GeneralEvaluationDomain<F>) -> F {
+    let coeffs = coset_domain.ifft(evaluations);
+    let x = challenge * shift_inv;
+    coeffs.iter().rev().fold(F::ZERO, |acc, c| acc * x + c)
+}
 
-    // Extract random verifier challenges from fiat-shamir
-    let mut verifier_randoms = vec![];
-    for root in level_roots.iter() {
-        let merkle_root_cap_field:Vec<F> = root.iter().map(|r| H::hash_as_field(r.clone())).collect();
-        transcript.observe_elements(b"merkle_root", &merkle_root_cap_field);
-        let verifier_rand: F = transcript.get_challenge(b"alpha");
-        verifier_randoms.push(verifier_rand);
-    }
+// Checks that the final evaluations over the coset offset*<w> are those of a polynomial of degree <= max_degree,
+// i.e. that all coefficients of their interpolant above max_degree are zero
+pub fn final_polynomial_degree_check<F: PrimeField>(final_evaluations: &[F], offset: F, max_degree: u32) -> bool {
+    let mut eval_domain: GeneralEvaluationDomain<F> = match GeneralEvaluationDomain::new(final_evaluations.len()) {
+        Some(domain) if domain.size() == final_evaluations.len() => domain,
+        _ => return false,
+    };
+    eval_domain = eval_domain.get_coset(offset).expect("Error in getting coset");
+    let coeffs = eval_domain.ifft(final_evaluations);
+    coeffs.iter().skip(max_degree as usize + 1).all(|c| c.is_zero())
+}
 
-    // Extract queries from fiat-shamir 
-    transcript.observe_elements(b"final evals", &final_evaluations);
-    let queries = <Transcript as TranscriptProtocol<F>>::get_challenge_indices(
-        &mut transcript,
-        b"challenge indices",
-        fri_config.num_query as usize
-    );
+pub fn verify_fri_proof<F: PrimeField + std::convert::From<i32>, H: Hasher_<F>> (fri_config: FriConfig, degree: u32, fri_proof: FRIProof<F,H>) -> bool {
+    let mut transcript = new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR);
+    verify_fri_proof_with_transcript(fri_config, degree, fri_proof, &mut transcript)
+}
 
+// Verifies a proof produced by generate_fri_proof_with_transcript, `transcript` must be in the same
+// state as the prover's one was. Leaves the transcript after the query phase.
+pub fn verify_fri_proof_with_transcript<F: PrimeField + std::convert::From<i32>, H: Hasher_<F>> (fri_config: FriConfig, degree: u32, fri_proof: FRIProof<F,H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    let vc = CappedMerkle::new(fri_config.merkle_cap_bits);
+    verify(fri_config, degree, &vc, fri_proof.into(), transcript, None)
+}
 
-    let original_domain_size = fri_config.blow_up_factor * (degree+1);
-    // let levels_to_iterate = (((degree+1)/(final_evalutaion_degree+1)) as f32).log2() as usize;
-    let levels_to_iterate = fri_config.level_reductions_bits.len();
+// Verifies a proof produced by generate_fri_proof_with_vc with the same backend
+pub fn verify_fri_proof_with_vc<F: PrimeField + std::convert::From<i32>, V: VectorCommitment<F>> (fri_config: FriConfig, degree: u32, vc: &V, fri_proof: VcFRIProof<F, V>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    verify(fri_config, degree, vc, fri_proof, transcript, None)
+}
 
-    println!("original domain size {:?}", original_domain_size);
-    println!("levels to iterate {:?}", levels_to_iterate);
+// Verifies a proof produced by generate_zk_fri_proof_with_transcript
+pub fn verify_zk_fri_proof_with_transcript<F: PrimeField + std::convert::From<i32>, H: Hasher_<F>> (fri_config: FriConfig, degree: u32, zk_config: &ZkConfig, zk_proof: ZkFRIProof<F,H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    let vc = CappedMerkle::new(fri_config.merkle_cap_bits);
+    let zk = ZkOpenings {
+        polynomial_root: zk_proof.polynomial_root,
+        masking_root: zk_proof.masking_root,
+        polynomial_proof: zk_proof.polynomial_proof,
+        masking_proof: zk_proof.masking_proof,
+    };
+    verify(fri_config, degree, &vc, zk_proof.fri_proof.into(), transcript, Some((zk_config, zk)))
+}
 
-    // [TODO] no need to calculate when we move to sending coeffs since no need of interpolation required
-    let mut final_offset = F::GENERATOR;
-    for exp in fri_config.level_reductions_bits.clone() {
-        final_offset = final_offset.pow([exp as u64]);
-    }
+// Commitments to p and the masking polynomial, and their openings at the first level's leaves
+struct ZkOpenings<F: PrimeField, V: VectorCommitment<F>> {
+    polynomial_root: V::Commitment,
+    masking_root: V::Commitment,
+    polynomial_proof: V::Opening,
+    masking_proof: V::Opening,
+}
 
-    assert_eq!(final_evaluations.len() as u32, fri_config.blow_up_factor*(fri_config.last_polynomial_degree+1));
-    
-    let mut final_evaluation_degree_correct = true;
-    if final_evalutaion_degree == 0 {
-        for i in 1..final_evaluations.len(){
-            final_evaluation_degree_correct = final_evaluations[i] == final_evaluations[i-1];
-        }
-    } else {
-        let mut eval_domain: GeneralEvaluationDomain<F> = GeneralEvaluationDomain::new(final_evaluations.len()).unwrap();
-        eval_domain = eval_domain.get_coset(final_offset).expect("Error in getting coset");
-        let coeffs = eval_domain.ifft(&final_evaluations);
-        let final_eval_poly_degree = coeffs.len();
-        final_evaluation_degree_correct = final_eval_poly_degree as u32 <= (fri_config.last_polynomial_degree+1) * fri_config.blow_up_factor;
-        println!("{}", final_eval_poly_degree);
+fn verify<F: PrimeField + std::convert::From<i32>, V: VectorCommitment<F>> (fri_config: FriConfig, degree: u32, vc: &V, fri_proof: VcFRIProof<F, V>, transcript: &mut impl TranscriptProtocol<F>, zk: Option<(&ZkConfig, ZkOpenings<F, V>)>) -> bool {
+    let _verify_span = debug_span!("fri_verify", degree, num_levels = fri_config.level_reductions_bits.len(), num_query = fri_config.num_query).entered();
+
+    let verifier = match zk {
+        Some((zk_config, _)) => FriVerifier::new_zk(fri_config.clone(), degree, vc, zk_config),
+        None => FriVerifier::new(fri_config.clone(), degree, vc).map_err(ZkConfigError::from),
+    };
+    let mut verifier = match verifier {
+        Ok(verifier) => verifier,
+        Err(_) => return false,
+    };
+
+    let num_levels = fri_config.level_reductions_bits.len();
+    if fri_proof.level_commitments.len() != num_levels || fri_proof.level_openings.len() != num_levels {
+        return false;
     }
-    assert!(final_evaluation_degree_correct);
-
-    println!("*** Verifying evaluation proof and consistency checks for each query ***");
-    for q_start in queries {
-        // println!("Starting to verify query -- {:?}", i+1);
-        println!("Starting to verify query -- {:?}", q_start);
-        let mut domain_size_current = original_domain_size as usize;
-        
-        // Bring query to first half of domain
-        let q_init = (q_start as usize)%(domain_size_current/2);
-
-        // Contains value of element in next folded level for consistency check
-        let mut next_level_value: F = F::one();
-
-        let mut offset = F::GENERATOR;
-
-        for l in 0..levels_to_iterate {
-            let reduction = 1<<fri_config.level_reductions_bits[l];
-            let q = q_init%domain_size_current;
-            
-            println!("Verifying query {:?} at level {:?}",q,l);
-
-            // Extract evaluation proof for the query at the level l
-            let eval_proof = eval_proofs[l].get(&q).unwrap();
-
-            // Verify merkle proof asserting leaf belongs to the commited root
-            assert!(merkle_path_verify::<F,H>(&eval_proof.merkle_proof)); 
-
-            // Contains all evaluations of this level required corresponding to that query for evaluation of next value in the reduced polynomail evaluations
-            // Evaluations will always be sorted in the order of their sorted queries
-            let evaluations = eval_proof.merkle_proof.leaf.clone();
-
-            if l !=0 {
-                // check prev round to current round consistency
-                // (q/reduction) -> maps the query index to the index in corresponding merkle leaf
-                assert_eq!(next_level_value, evaluations[q/reduction], "Consistency check failed for query {:?} between levels {:?} and {:?}", q, l-1, l);
-            }
-
-            let query_addition_factor = domain_size_current/reduction;
-
-            let mut level_query_set: Vec<usize> = Vec::new();
 
-            for i in 0..(1<<fri_config.level_reductions_bits[l]) {
-                let tmp = (q + i * query_addition_factor)%domain_size_current;
-                level_query_set.push(tmp);
+    // The interactive verifier, with its challenges derived from the transcript
+    observe_fri_statement::<F>(transcript, &fri_config, degree);
+    let zk_openings = match zk {
+        Some((zk_config, zk)) => {
+            if num_levels == 0 {
+                return false;
             }
-            level_query_set.sort();
-
-            let mut eval_domain_verifier: GeneralEvaluationDomain<F> = GeneralEvaluationDomain::new(domain_size_current).unwrap();
-            eval_domain_verifier = eval_domain_verifier.get_coset(offset).expect("Error in getting coset");
-
-            let verifier_rand = verifier_randoms[l];
-
-            next_level_value = calcualate_next_level_value(level_query_set, evaluations, verifier_rand, eval_domain_verifier);;
-
-            domain_size_current = domain_size_current>>fri_config.level_reductions_bits[l];
-            offset = offset.pow([reduction as u64]);
-        }
-        let q_final = (q_start as usize)%(domain_size_current);
-        assert_eq!(final_evaluations[q_final], next_level_value);
+            transcript.observe_u64(b"zk salt size", zk_config.salt_size as u64);
+            verifier.receive_zk_commitments(zk.polynomial_root, zk.masking_root, transcript);
+            Some((zk.polynomial_proof, zk.masking_proof))
+        },
+        None => None,
+    };
+    for commitment in fri_proof.level_commitments.into_iter() {
+        verifier.receive_commitment(commitment, transcript);
+        verifier.challenge(transcript);
+    }
+    if verifier.receive_final_polynomial(fri_proof.final_evaluations, fri_proof.pow_nonce, transcript).is_none() {
+        return false;
+    }
+    match zk_openings {
+        Some((polynomial_proof, masking_proof)) => verifier.check_zk_answers(&fri_proof.level_openings, &polynomial_proof, &masking_proof),
+        None => verifier.check_answers(&fri_proof.level_openings),
     }
-    true
 }
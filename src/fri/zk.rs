@@ -0,0 +1,264 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+
+use crate::hashing::hasher::Hasher_;
+
+use crate::merkle_tree::merkle::MerkleMultiProof;
+
+use super::types::FRIProof;
+use super::utils::FriConfigError;
+
+// Opt-in hiding mode. p and a random masking polynomial of the same degree are committed first, and the
+// first level commits p + beta * masking for the verifier's beta. The queries open p and the masking
+// polynomial at the first level's leaves, and the verifier checks that they add up to the first level,
+// which binds the low degree test to p. The folded levels and the final evaluations only depend on
+// p + beta * masking, and are uniformly random whatever p is as long as no more than degree+1 points
+// are opened. The opened evaluations of p themselves are revealed, so callers hiding p at those points
+// have to randomise p beforehand.
+// Every committed leaf is followed by `salt_size` random field elements, hashed element by element with
+// the leaf, so that the unopened leaves of p stay hidden.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZkConfig {
+    pub salt_size: usize,
+}
+
+// Reasons a zk proof cannot be made with a FriConfig and ZkConfig
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ZkConfigError {
+    Fri(FriConfigError),
+    NoSalt, // leaves need at least one salt element
+}
+
+impl From<FriConfigError> for ZkConfigError {
+    fn from(e: FriConfigError) -> Self {
+        ZkConfigError::Fri(e)
+    }
+}
+
+impl fmt::Display for ZkConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkConfigError::Fri(e) => e.fmt(f),
+            ZkConfigError::NoSalt => write!(f, "zero-knowledge leaves need a salt of at least one element"),
+        }
+    }
+}
+
+impl std::error::Error for ZkConfigError {}
+
+impl ZkConfig {
+    // Unsalted leaves would leave the unopened siblings of the openings open to brute force
+    pub fn validate(&self) -> Result<(), ZkConfigError> {
+        if self.salt_size == 0 {
+            return Err(ZkConfigError::NoSalt);
+        }
+        Ok(())
+    }
+}
+
+impl Default for ZkConfig {
+    fn default() -> Self {
+        ZkConfig { salt_size: 1 }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ZkFRIProof<F: PrimeField, H: Hasher_<F>> {
+    pub fri_proof: FRIProof<F, H>, // first level commits p + beta * masking
+    pub polynomial_root: Vec<H::Hash>,
+    pub masking_root: Vec<H::Hash>,
+    pub polynomial_proof: MerkleMultiProof<F, H>, // p at the first level's queried leaves
+    pub masking_proof: MerkleMultiProof<F, H>, // masking polynomial at the same leaves
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{FftField, Field};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::fiat_shamir::random_coins::RandomCoins;
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::interactive::{FriProver, FriVerifier};
+    use crate::fri::prover::{generate_zk_fri_proof_with_transcript, leaf_groupings, query_leaf_indices};
+    use crate::fri::types::FriConfig;
+    use crate::fri::utils::{new_fri_transcript, DEFAULT_DOMAIN_SEPARATOR};
+    use crate::fri::verifier::{verify_fri_proof_with_transcript, verify_zk_fri_proof_with_transcript};
+    use crate::hashing::hasher::Sha256_;
+    use crate::vector_commitment::{merkle::CappedMerkle, VectorCommitment};
+    use super::{ZkConfig, ZkConfigError, ZkFRIProof};
+
+    fn polynomial(factor: u64) -> DensePolynomial<Fq> {
+        DenseUVPolynomial::from_coefficients_vec((0..16).map(|i| Fq::from(i * factor + 1)).collect())
+    }
+
+    fn sample_proof(seed: u64, zk_config: &ZkConfig) -> (FriConfig, ZkFRIProof<Fq, Sha256_<Fq>>) {
+        prove(polynomial(3), seed, zk_config)
+    }
+
+    fn prove(poly: DensePolynomial<Fq>, seed: u64, zk_config: &ZkConfig) -> (FriConfig, ZkFRIProof<Fq, Sha256_<Fq>>) {
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 };
+        let mut rng = StdRng::seed_from_u64(seed);
+        let proof = generate_zk_fri_proof_with_transcript(poly, fri_config.clone(), zk_config, &mut rng, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR));
+        (fri_config, proof)
+    }
+
+    fn verify(fri_config: &FriConfig, zk_config: &ZkConfig, proof: ZkFRIProof<Fq, Sha256_<Fq>>) -> bool {
+        verify_zk_fri_proof_with_transcript(fri_config.clone(), 15, zk_config, proof, &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR))
+    }
+
+    #[test]
+    fn test_zk_proof_verifies() {
+        let zk_config = ZkConfig { salt_size: 2 };
+        let (fri_config, proof) = sample_proof(0, &zk_config);
+        assert!(verify(&fri_config, &zk_config, proof.clone()));
+        assert!(verify(&fri_config, &ZkConfig::default(), sample_proof(1, &ZkConfig::default()).1));
+
+        // openings carry the salts
+        let leaf = &proof.fri_proof.query_eval_proofs[0].merkle_proof.leaves[0];
+        assert_eq!(leaf.len(), 2 + zk_config.salt_size);
+
+        // not a plain proof, nor one with another salt size
+        assert!(!verify_fri_proof_with_transcript(fri_config.clone(), 15, proof.fri_proof.clone(), &mut new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR)));
+        assert!(!verify(&fri_config, &ZkConfig { salt_size: 1 }, proof));
+    }
+
+    #[test]
+    fn test_zk_salts_differ() {
+        let zk_config = ZkConfig::default();
+        let (fri_config, first) = sample_proof(1, &zk_config);
+        let (_, second) = sample_proof(2, &zk_config);
+
+        let salts = |proof: &ZkFRIProof<Fq, Sha256_<Fq>>| -> Vec<Fq> {
+            proof.fri_proof.query_eval_proofs.iter()
+                .flat_map(|p| p.merkle_proof.leaves.iter().map(|leaf| *leaf.last().unwrap()))
+                .collect()
+        };
+        let (first_salts, second_salts) = (salts(&first), salts(&second));
+        assert!(first_salts.iter().all(|salt| !second_salts.contains(salt)));
+        // same polynomial, yet nothing committed matches
+        assert_ne!(first.fri_proof.level_roots[0], second.fri_proof.level_roots[0]);
+        assert_ne!(first.fri_proof.final_evaluations, second.fri_proof.final_evaluations);
+        assert!(verify(&fri_config, &zk_config, first));
+        assert!(verify(&fri_config, &zk_config, second));
+    }
+
+    #[test]
+    fn test_zk_proof_tampered() {
+        let zk_config = ZkConfig::default();
+        let (fri_config, proof) = sample_proof(3, &zk_config);
+
+        let mut bad_salt = proof.clone();
+        *bad_salt.fri_proof.query_eval_proofs[0].merkle_proof.leaves[0].last_mut().unwrap() += Fq::from(1);
+        assert!(!verify(&fri_config, &zk_config, bad_salt));
+
+        let mut bad_masking = proof.clone();
+        bad_masking.masking_root = sample_proof(4, &zk_config).1.masking_root;
+        assert!(!verify(&fri_config, &zk_config, bad_masking));
+
+        let mut swapped_root = proof.clone();
+        swapped_root.masking_root = proof.fri_proof.level_roots[0].clone();
+        assert!(!verify(&fri_config, &zk_config, swapped_root));
+
+        let mut swapped_polynomial = proof.clone();
+        std::mem::swap(&mut swapped_polynomial.polynomial_root, &mut swapped_polynomial.masking_root);
+        std::mem::swap(&mut swapped_polynomial.polynomial_proof, &mut swapped_polynomial.masking_proof);
+        assert!(!verify(&fri_config, &zk_config, swapped_polynomial));
+
+        let mut other_polynomial = proof.clone();
+        let other = prove(polynomial(1000), 3, &zk_config).1;
+        other_polynomial.polynomial_root = other.polynomial_root;
+        other_polynomial.polynomial_proof = other.polynomial_proof;
+        assert!(!verify(&fri_config, &zk_config, other_polynomial));
+
+        let mut bad_opening = proof.clone();
+        bad_opening.polynomial_proof.leaves[0][0] += Fq::from(1);
+        assert!(!verify(&fri_config, &zk_config, bad_opening));
+    }
+
+    // Commits to `committed` as p while proving the low degree polynomial `folded` with an honest mask,
+    // returns the verifier's decision
+    fn run_with_committed_polynomial(committed: &DensePolynomial<Fq>, folded: DensePolynomial<Fq>) -> bool {
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 };
+        let zk_config = ZkConfig::default();
+        let vc = CappedMerkle::<Fq, Sha256_<Fq>>::new(fri_config.merkle_cap_bits);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut prover = FriProver::new_zk(folded, 15, fri_config.clone(), &vc, &zk_config, &mut rng);
+
+        // p over the first level's coset and leaves, with zero salts
+        let domain = GeneralEvaluationDomain::<Fq>::new(64).unwrap().get_coset(Fq::GENERATOR).unwrap();
+        let evaluations = domain.fft(&committed.coeffs);
+        let grouping = &leaf_groupings(fri_config.level_reductions_bits.clone(), 64, 2)[0];
+        let leaves = grouping.chunks(2).map(|leaf| leaf.iter().map(|i| evaluations[*i]).chain([Fq::from(0)]).collect()).collect();
+        let (polynomial_root, polynomial_tree) = vc.commit(leaves);
+
+        let mut verifier = FriVerifier::new_zk(fri_config.clone(), 15, &vc, &zk_config).unwrap();
+        let mut coins = RandomCoins::new(StdRng::seed_from_u64(8));
+        let beta = verifier.receive_zk_commitments(polynomial_root, prover.masking_commitment().unwrap().clone(), &mut coins);
+        let mut commitment = Some(prover.mask(beta));
+        while let Some(level) = commitment {
+            verifier.receive_commitment(level, &mut coins);
+            commitment = prover.commit_round(verifier.challenge(&mut coins));
+        }
+        let queries = verifier.receive_final_polynomial(prover.final_polynomial().to_vec(), 0, &mut coins).unwrap();
+        let (_, masking_opening) = prover.answer_zk_queries(&queries);
+        let polynomial_opening = vc.open(&polynomial_tree, &query_leaf_indices(&queries, 64, &fri_config.level_reductions_bits)[0]);
+        verifier.check_zk_answers(&prover.answer_queries(&queries), &polynomial_opening, &masking_opening)
+    }
+
+    #[test]
+    fn test_zk_rejects_high_degree_polynomial() {
+        let low = polynomial(3);
+        assert!(run_with_committed_polynomial(&low, low.clone()));
+        // degree 39 over a degree 15 bound, every fold and the final polynomial are of low degree
+        let high: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec((0..40).map(|i| Fq::from(i * i + 1)).collect());
+        assert!(!run_with_committed_polynomial(&high, low));
+    }
+
+    #[test]
+    fn test_zk_openings_independent_of_polynomial() {
+        // Same randomness for two polynomials: the masking commitment does not depend on p, and the
+        // first level openings are p + beta * masking, a one time pad as no more than degree+1 points
+        // are opened. Only the openings of p itself show p.
+        let zk_config = ZkConfig::default();
+        let (polynomials, seed) = ([polynomial(3), polynomial(1000)], 5);
+        let proofs = polynomials.clone().map(|poly| prove(poly, seed, &zk_config).1);
+        assert_eq!(proofs[0].masking_root, proofs[1].masking_root);
+        assert_ne!(proofs[0].polynomial_root, proofs[1].polynomial_root);
+        assert_ne!(proofs[0].fri_proof.level_roots[0], proofs[1].fri_proof.level_roots[0]);
+
+        // the same challenges and queries for both
+        let (fri_config, queries) = (prove(polynomial(3), seed, &zk_config).0, [3, 17, 40, 45, 60, 61, 62, 63]);
+        let vc = CappedMerkle::<Fq, Sha256_<Fq>>::new(fri_config.merkle_cap_bits);
+        let domain = GeneralEvaluationDomain::<Fq>::new(64).unwrap();
+        let masks: Vec<Vec<Fq>> = polynomials.iter().map(|poly| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut prover = FriProver::new_zk(poly.clone(), 15, fri_config.clone(), &vc, &zk_config, &mut rng);
+            prover.mask(Fq::from(7u64));
+            prover.commit_round(Fq::from(11u64));
+            prover.commit_round(Fq::from(13u64));
+            let opening = &prover.answer_queries(&queries)[0];
+            assert!(opening.leaf_indices.len() * 2 <= 16);
+            opening.leaf_indices.iter().zip(opening.leaves.iter()).flat_map(|(idx, leaf)| {
+                // leaf idx holds the positions idx and idx + 32 of the coset
+                (0..2).map(move |i| {
+                    let x = Fq::GENERATOR * domain.group_gen().pow([(idx + 32 * i) as u64]);
+                    leaf[i] - poly.evaluate(&x)
+                })
+            }).collect()
+        }).collect();
+        assert_eq!(masks[0], masks[1]);
+        assert!(masks[0].iter().all(|mask| *mask != Fq::from(0)));
+        assert!(proofs.into_iter().all(|proof| verify(&fri_config, &zk_config, proof)));
+    }
+
+    #[test]
+    fn test_zk_requires_salt() {
+        let zk_config = ZkConfig { salt_size: 0 };
+        assert_eq!(zk_config.validate(), Err(ZkConfigError::NoSalt));
+        assert!(std::panic::catch_unwind(|| sample_proof(6, &zk_config)).is_err());
+        let (fri_config, proof) = sample_proof(6, &ZkConfig::default());
+        assert!(!verify(&fri_config, &zk_config, proof));
+    }
+}
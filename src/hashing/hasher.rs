@@ -1,15 +1,29 @@
 use std::{marker::PhantomData, fmt::Debug};
 
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use rs_merkle::{algorithms::Sha256, Hasher};
+use sha3::{Digest, Keccak256};
 
+use crate::fiat_shamir::keccak_transcript::field_to_word;
 
-pub trait Hasher_<F: PrimeField> {
-    type Hash: Clone + PartialEq + Debug + Copy;
+
+// Domain separation tags prepended to the preimages of leaf and inner node hashes, so that no leaf digest
+// can be passed off as a node (or the other way around)
+pub const LEAF_TAG: u8 = 0;
+pub const NODE_TAG: u8 = 1;
+
+pub trait Hasher_<F: PrimeField>: Send + Sync {
+    type Hash: Clone + PartialEq + Debug + Copy + CanonicalSerialize + CanonicalDeserialize;
 
     fn hash(data:F) -> Self::Hash;
 
+    // Leaf hash of a sequence of elements, absorbing LEAF_TAG then every element in order. Injective (up to
+    // collisions) for sequences of the same length, and hash_elements(&[x]) == hash(x).
+    fn hash_elements(data: &[F]) -> Self::Hash;
+
     // fn hash_out_field(data: F) -> Self::Hash;
+    // Inner node hash, absorbing NODE_TAG then both children
     fn hash_two_to_one(data1:Self::Hash, data2: Self::Hash) -> Self::Hash;
 
     fn hash_as_field(hash: Self::Hash) -> F;
@@ -24,14 +38,19 @@ impl<F: PrimeField> Hasher_<F> for Sha256_<F> {
     type Hash = F;
 
     fn hash(data: F) -> Self::Hash {
-        let d: Vec<u8> = data.to_string().into();
+        Self::hash_elements(&[data])
+    }
+
+    fn hash_elements(data: &[F]) -> Self::Hash {
+        let mut d = vec![LEAF_TAG];
+        d.extend(data.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",").into_bytes());
         F::from_le_bytes_mod_order(&Sha256::hash(&d))
     }
 
+    // the children are separated like leaf elements, "1" "23" and "12" "3" must not collide
     fn hash_two_to_one(data1: Self::Hash, data2: Self::Hash) -> Self::Hash {
-        let mut d: Vec<u8> = data1.to_string().into();
-        let mut d1: Vec<u8> = data2.to_string().into();
-        d.append(&mut d1);
+        let mut d = vec![NODE_TAG];
+        d.extend(format!("{},{}", data1, data2).into_bytes());
         let h = Sha256::hash(&d);
         F::from_le_bytes_mod_order(&h)
     }
@@ -39,4 +58,79 @@ impl<F: PrimeField> Hasher_<F> for Sha256_<F> {
     fn hash_as_field(hash: Self::Hash) -> F {
         hash
     }
-}
\ No newline at end of file
+}
+
+// Keccak256 hasher matching the EVM keccak256 opcode: field elements are hashed as 32 byte big endian
+// words after a one byte tag and digests are mapped to the field by reducing them as big endian integers
+#[derive(Clone, Debug)]
+pub struct Keccak256_<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> Hasher_<F> for Keccak256_<F> {
+    type Hash = [u8; 32];
+
+    fn hash(data: F) -> Self::Hash {
+        Self::hash_elements(&[data])
+    }
+
+    // keccak256(abi.encodePacked(bytes1(0x00), words))
+    fn hash_elements(data: &[F]) -> Self::Hash {
+        let mut hasher = Keccak256::new();
+        hasher.update([LEAF_TAG]);
+        for x in data {
+            hasher.update(field_to_word(x));
+        }
+        hasher.finalize().into()
+    }
+
+    // keccak256(abi.encodePacked(bytes1(0x01), left, right))
+    fn hash_two_to_one(data1: Self::Hash, data2: Self::Hash) -> Self::Hash {
+        let mut hasher = Keccak256::new();
+        hasher.update([NODE_TAG]);
+        hasher.update(data1);
+        hasher.update(data2);
+        hasher.finalize().into()
+    }
+
+    fn hash_as_field(hash: Self::Hash) -> F {
+        F::from_be_bytes_mod_order(&hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use super::{Hasher_, Keccak256_, Sha256_};
+
+    #[test]
+    fn test_keccak_hasher() {
+        // keccak256(abi.encodePacked(bytes1(0x00), bytes32(0)))
+        let zero = Keccak256_::<Fq>::hash(Fq::from(0));
+        assert_eq!(hex_digest(&zero), "f39a869f62e75cf5f0bf914688a6b289caf2049435d8e68c5c5e6d05e44913f3");
+        // keccak256(abi.encodePacked(bytes1(0x01), bytes32(0), bytes32(0)))
+        let node = Keccak256_::<Fq>::hash_two_to_one([0u8; 32], [0u8; 32]);
+        assert_eq!(hex_digest(&node), "c07a1e8b7e0057673fdc2affe190d8a960c5fe615663f27b7ce84f3d93ef92a6");
+        assert!(Keccak256_::<Fq>::hash_as_field(zero) != Fq::from(0));
+        assert_eq!(Keccak256_::<Fq>::hash_elements(&[Fq::from(0)]), zero);
+        // a leaf of two words is not an inner node
+        assert!(Keccak256_::<Fq>::hash_elements(&[Fq::from(0), Fq::from(0)]) != node);
+    }
+
+    #[test]
+    fn test_hash_elements() {
+        let elements = [Fq::from(1), Fq::from(23)];
+        assert_eq!(Sha256_::<Fq>::hash_elements(&elements[..1]), Sha256_::<Fq>::hash(elements[0]));
+        // not the hash of the sum, nor of other digits
+        assert!(Sha256_::<Fq>::hash_elements(&elements) != Sha256_::<Fq>::hash_elements(&[Fq::from(12), Fq::from(3)]));
+        assert!(Sha256_::<Fq>::hash_elements(&elements) != Sha256_::<Fq>::hash_elements(&[Fq::from(23), Fq::from(1)]));
+        assert!(Sha256_::<Fq>::hash_elements(&elements) != Sha256_::<Fq>::hash(Fq::from(24)));
+        // leaves and nodes are domain separated, node children are delimited
+        assert!(Sha256_::<Fq>::hash_elements(&elements) != Sha256_::<Fq>::hash_two_to_one(elements[0], elements[1]));
+        assert!(Sha256_::<Fq>::hash_two_to_one(Fq::from(1), Fq::from(23)) != Sha256_::<Fq>::hash_two_to_one(Fq::from(12), Fq::from(3)));
+    }
+
+    fn hex_digest(hash: &[u8; 32]) -> String {
+        hash.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
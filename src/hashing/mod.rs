@@ -1 +1,3 @@
-pub mod hasher;
\ No newline at end of file
+pub mod hasher;
+#[cfg(feature = "r1cs")]
+pub mod poseidon;
@@ -0,0 +1,56 @@
+use std::sync::OnceLock;
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge};
+use ark_crypto_primitives::sponge::{CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+
+use super::hasher::{Hasher_, LEAF_TAG, NODE_TAG};
+
+// Poseidon over BN254's scalar field with width 3 (rate 2), x^5 S-box, 8 full and 57 partial rounds,
+// round constants and MDS matrix from the Grain LFSR
+pub const POSEIDON_RATE: usize = 2;
+pub const POSEIDON_ALPHA: u64 = 5;
+pub const POSEIDON_FULL_ROUNDS: usize = 8;
+pub const POSEIDON_PARTIAL_ROUNDS: usize = 57;
+
+pub fn poseidon_config() -> &'static PoseidonConfig<Fr> {
+    static CONFIG: OnceLock<PoseidonConfig<Fr>> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let (ark, mds) = find_poseidon_ark_and_mds::<Fr>(Fr::MODULUS_BIT_SIZE as u64, POSEIDON_RATE, POSEIDON_FULL_ROUNDS as u64, POSEIDON_PARTIAL_ROUNDS as u64, 0);
+        PoseidonConfig::new(POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_ALPHA, mds, ark, POSEIDON_RATE, 1)
+    })
+}
+
+// Algebraic hasher for proofs verified inside a circuit over BN254 (see crate::r1cs)
+#[derive(Clone, Debug)]
+pub struct PoseidonHasher;
+
+pub fn poseidon_hash(inputs: &[Fr]) -> Fr {
+    let mut sponge = PoseidonSponge::new(poseidon_config());
+    sponge.absorb(&inputs);
+    sponge.squeeze_native_field_elements(1)[0]
+}
+
+impl Hasher_<Fr> for PoseidonHasher {
+    type Hash = Fr;
+
+    fn hash(data: Fr) -> Self::Hash {
+        Self::hash_elements(&[data])
+    }
+
+    // the tag is absorbed as a leading field element
+    fn hash_elements(data: &[Fr]) -> Self::Hash {
+        let mut inputs = vec![Fr::from(LEAF_TAG)];
+        inputs.extend_from_slice(data);
+        poseidon_hash(&inputs)
+    }
+
+    fn hash_two_to_one(data1: Self::Hash, data2: Self::Hash) -> Self::Hash {
+        poseidon_hash(&[Fr::from(NODE_TAG), data1, data2])
+    }
+
+    fn hash_as_field(hash: Self::Hash) -> Fr {
+        hash
+    }
+}
@@ -0,0 +1,110 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::prover::generate_fri_proof_with_degree;
+use crate::fri::types::{FRIProof, FriConfig};
+use crate::fri::utils::FriConfigError;
+use crate::fri::verifier::verify_fri_proof_with_transcript;
+use crate::hashing::hasher::Hasher_;
+
+use super::{codeword_polynomial, leaf_positions, LowDegreeTest};
+
+// FRI as a LowDegreeTest. The codeword is the first level, without levels the final evaluations.
+#[derive(Clone, Debug)]
+pub struct FriLdt<F: PrimeField, H: Hasher_<F>> {
+    pub config: FriConfig,
+    pub degree: u32,
+    _h: PhantomData<(F, H)>,
+}
+
+impl<F: PrimeField + From<i32>, H: Hasher_<F>> LowDegreeTest<F> for FriLdt<F, H> {
+    type Config = FriConfig;
+    type Proof = FRIProof<F, H>;
+    type Commitment = Vec<H::Hash>; // first level root cap, empty without levels
+    type Error = FriConfigError;
+
+    fn new(config: FriConfig, degree: u32) -> Result<Self, FriConfigError> {
+        config.validate::<F>(degree)?;
+        Ok(FriLdt { config, degree, _h: PhantomData })
+    }
+
+    fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    fn domain(&self) -> GeneralEvaluationDomain<F> {
+        let size = self.config.blow_up_factor as usize * (self.degree as usize + 1);
+        GeneralEvaluationDomain::<F>::new(size).unwrap().get_coset(F::GENERATOR).expect("Error in getting coset")
+    }
+
+    fn merkle_cap_bits(&self) -> u32 {
+        self.config.merkle_cap_bits
+    }
+
+    fn prove(&self, codeword: &[F], transcript: &mut impl TranscriptProtocol<F>) -> (Vec<H::Hash>, FRIProof<F, H>) {
+        let polynomial = codeword_polynomial(&self.domain(), codeword, self.degree);
+        let proof = generate_fri_proof_with_degree::<F, H>(polynomial, self.degree, self.config.clone(), transcript);
+        (proof.level_roots.first().cloned().unwrap_or_default(), proof)
+    }
+
+    fn verify(&self, commitment: &Vec<H::Hash>, proof: FRIProof<F, H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+        if proof.level_roots.first().cloned().unwrap_or_default() != *commitment {
+            return false;
+        }
+        verify_fri_proof_with_transcript(self.config.clone(), self.degree, proof, transcript)
+    }
+
+    fn opened_values(&self, proof: &FRIProof<F, H>) -> Option<Vec<(usize, F)>> {
+        let domain_size = self.domain().size();
+        match (self.config.level_reductions_bits.first(), proof.query_eval_proofs.first()) {
+            (Some(bits), Some(first_level)) => {
+                let multi_proof = &first_level.merkle_proof;
+                leaf_positions(&multi_proof.leaf_indices, &multi_proof.leaves, domain_size, 1 << bits)
+            },
+            // the final evaluations are the whole codeword
+            (None, _) if proof.final_evaluations.len() == domain_size => Some(proof.final_evaluations.iter().copied().enumerate().collect()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain};
+
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::types::FriConfig;
+    use crate::fri::utils::new_fri_transcript;
+    use crate::hashing::hasher::Sha256_;
+    use crate::ldt::LowDegreeTest;
+    use super::FriLdt;
+
+    #[test]
+    fn test_fri_ldt() {
+        // the second codeword is of degree 40, below the bound
+        let polynomials = [64u64, 41].map(|len| DensePolynomial::from_coefficients_vec((0..len).map(Fq::from).collect::<Vec<_>>()));
+        let configs = [
+            FriConfig { num_query: 6, blow_up_factor: 4, last_polynomial_degree: 3, merkle_cap_bits: 1, level_reductions_bits: vec![2, 2], pow_bits: 0 },
+            FriConfig { num_query: 6, blow_up_factor: 4, last_polynomial_degree: 63, merkle_cap_bits: 1, level_reductions_bits: vec![], pow_bits: 0 },
+        ];
+        for config in configs {
+            let ldt = FriLdt::<Fq, Sha256_<Fq>>::new(config, 63).unwrap();
+            for polynomial in &polynomials {
+                let codeword = ldt.domain().fft(&polynomial.coeffs);
+                let (commitment, proof) = ldt.prove(&codeword, &mut new_fri_transcript(b"ldt"));
+                assert!(ldt.verify(&commitment, proof.clone(), &mut new_fri_transcript(b"ldt")));
+                if !commitment.is_empty() {
+                    assert!(!ldt.verify(&vec![], proof.clone(), &mut new_fri_transcript(b"ldt")));
+                }
+
+                let opened = ldt.opened_values(&proof).unwrap();
+                assert!(!opened.is_empty());
+                assert!(opened.iter().all(|(position, value)| codeword[*position] == *value));
+            }
+        }
+        assert!(FriLdt::<Fq, Sha256_<Fq>>::new(FriConfig { num_query: 0, blow_up_factor: 4, last_polynomial_degree: 0, merkle_cap_bits: 0, level_reductions_bits: vec![6], pow_bits: 0 }, 63).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::utils::FriConfigError;
+
+pub mod fri;
+pub mod stir;
+
+// A low degree test of codewords, the evaluations over domain() of polynomials of degree at most degree().
+// Protocols built on it (e.g. the STARK) commit to their own oracles over the same domain, and check them
+// against the codeword values the proof opened.
+pub trait LowDegreeTest<F: PrimeField>: Sized {
+    type Config: Clone;
+    type Proof;
+    type Commitment: Clone + PartialEq;
+    // Why a config cannot be used, every test shares the FRI reasons
+    type Error: std::error::Error + From<FriConfigError>;
+
+    // Validates `config` for polynomials of degree `degree`
+    fn new(config: Self::Config, degree: u32) -> Result<Self, Self::Error>;
+
+    fn degree(&self) -> u32;
+
+    fn domain(&self) -> GeneralEvaluationDomain<F>;
+
+    // Cap of the test's Merkle trees, for the trees other protocols commit over the same domain
+    fn merkle_cap_bits(&self) -> u32;
+
+    // Commits to `codeword` and proves that it is of low degree, which it must be
+    fn prove(&self, codeword: &[F], transcript: &mut impl TranscriptProtocol<F>) -> (Self::Commitment, Self::Proof);
+
+    fn verify(&self, commitment: &Self::Commitment, proof: Self::Proof, transcript: &mut impl TranscriptProtocol<F>) -> bool;
+
+    // Codeword values opened by `proof` as (position in domain(), value), sorted by position. Only
+    // authenticated once verify has accepted the proof, which consumes it. None if the proof is malformed.
+    fn opened_values(&self, proof: &Self::Proof) -> Option<Vec<(usize, F)>>;
+}
+
+// Polynomial with the evaluations `codeword` over `domain`, which must be of degree at most `degree`
+pub(crate) fn codeword_polynomial<F: PrimeField>(domain: &GeneralEvaluationDomain<F>, codeword: &[F], degree: u32) -> DensePolynomial<F> {
+    assert_eq!(codeword.len(), domain.size(), "Codeword does not cover the domain");
+    let coeffs = domain.ifft(codeword);
+    assert!(coeffs[degree as usize + 1..].iter().all(|c| c.is_zero()), "Codeword is not of low degree");
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+// (position, value) of leaves opened in a commitment over a domain of size `domain_size`, where leaf j holds
+// the `leaf_size` positions j, j + n/leaf_size, ...
+pub(crate) fn leaf_positions<F: PrimeField>(leaf_indices: &[usize], leaves: &[Vec<F>], domain_size: usize, leaf_size: usize) -> Option<Vec<(usize, F)>> {
+    if leaf_indices.len() != leaves.len() || leaves.iter().any(|leaf| leaf.len() != leaf_size) {
+        return None;
+    }
+    let stride = domain_size / leaf_size;
+    let mut opened = Vec::new();
+    for (j, leaf) in leaf_indices.iter().zip(leaves.iter()) {
+        opened.extend(leaf.iter().enumerate().map(|(m, v)| (j + m * stride, *v)));
+    }
+    opened.sort_unstable_by_key(|(position, _)| *position);
+    Some(opened)
+}
@@ -0,0 +1,94 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::hashing::hasher::Hasher_;
+use crate::stir::prover::generate_stir_proof_with_degree;
+use crate::stir::types::{StirConfig, StirConfigError, StirProof};
+use crate::stir::utils::stir_domain;
+use crate::stir::verifier::verify_stir_proof_with_transcript;
+
+use super::{codeword_polynomial, leaf_positions, LowDegreeTest};
+
+// STIR as a LowDegreeTest. The codeword is the initial commitment, opened at the first round's shift queries.
+#[derive(Clone, Debug)]
+pub struct StirLdt<F: PrimeField, H: Hasher_<F>> {
+    pub config: StirConfig,
+    pub degree: u32,
+    _h: PhantomData<(F, H)>,
+}
+
+impl<F: PrimeField + From<i32>, H: Hasher_<F>> LowDegreeTest<F> for StirLdt<F, H> {
+    type Config = StirConfig;
+    type Proof = StirProof<F, H>;
+    type Commitment = Vec<H::Hash>;
+    type Error = StirConfigError;
+
+    fn new(config: StirConfig, degree: u32) -> Result<Self, StirConfigError> {
+        config.validate::<F>(degree)?;
+        Ok(StirLdt { config, degree, _h: PhantomData })
+    }
+
+    fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    fn domain(&self) -> GeneralEvaluationDomain<F> {
+        stir_domain(self.config.domain_size(self.degree, 0) as usize)
+    }
+
+    fn merkle_cap_bits(&self) -> u32 {
+        self.config.merkle_cap_bits
+    }
+
+    fn prove(&self, codeword: &[F], transcript: &mut impl TranscriptProtocol<F>) -> (Vec<H::Hash>, StirProof<F, H>) {
+        let polynomial = codeword_polynomial(&self.domain(), codeword, self.degree);
+        let proof = generate_stir_proof_with_degree::<F, H>(polynomial, self.degree, self.config.clone(), transcript);
+        (proof.initial_root.clone(), proof)
+    }
+
+    fn verify(&self, commitment: &Vec<H::Hash>, proof: StirProof<F, H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+        proof.initial_root == *commitment && verify_stir_proof_with_transcript(self.config.clone(), self.degree, proof, transcript)
+    }
+
+    fn opened_values(&self, proof: &StirProof<F, H>) -> Option<Vec<(usize, F)>> {
+        let first_opening = proof.rounds.first().map(|round| &round.query_proof).unwrap_or(&proof.final_query_proof);
+        let multi_proof = &first_opening.merkle_proof;
+        leaf_positions(&multi_proof.leaf_indices, &multi_proof.leaves, self.domain().size(), self.config.folding_factor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain};
+
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::utils::new_fri_transcript;
+    use crate::hashing::hasher::Sha256_;
+    use crate::ldt::LowDegreeTest;
+    use crate::stir::types::StirConfig;
+    use super::StirLdt;
+
+    #[test]
+    fn test_stir_ldt() {
+        let polynomial = DensePolynomial::from_coefficients_vec((0..64u64).map(Fq::from).collect());
+        let config = StirConfig { blow_up_factor: 4, folding_factor_bits: 2, num_rounds: 1, num_queries: vec![5, 3], ood_samples: 1, merkle_cap_bits: 1 };
+        let ldt = StirLdt::<Fq, Sha256_<Fq>>::new(config, 63).unwrap();
+
+        // a codeword of degree 40, below the bound
+        let lower = DensePolynomial::from_coefficients_vec((0..41u64).map(Fq::from).collect());
+        let (commitment, proof) = ldt.prove(&ldt.domain().fft(&lower.coeffs), &mut new_fri_transcript(b"ldt"));
+        assert!(ldt.verify(&commitment, proof, &mut new_fri_transcript(b"ldt")));
+
+        let codeword = ldt.domain().fft(&polynomial.coeffs);
+        let (commitment, proof) = ldt.prove(&codeword, &mut new_fri_transcript(b"ldt"));
+        assert!(ldt.verify(&commitment, proof.clone(), &mut new_fri_transcript(b"ldt")));
+        assert!(!ldt.verify(&proof.rounds[0].root, proof.clone(), &mut new_fri_transcript(b"ldt")));
+
+        let opened = ldt.opened_values(&proof).unwrap();
+        assert!(!opened.is_empty());
+        assert!(opened.iter().all(|(position, value)| codeword[*position] == *value));
+    }
+}
@@ -0,0 +1,14 @@
+pub mod fiat_shamir;
+pub mod hashing;
+pub mod merkle_tree;
+pub mod vector_commitment;
+pub mod fields;
+pub mod fri;
+pub mod stark;
+pub mod basefold;
+pub mod circle;
+pub mod stir;
+pub mod ldt;
+pub mod solidity;
+#[cfg(feature = "r1cs")]
+pub mod r1cs;
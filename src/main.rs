@@ -1,8 +1,329 @@
-mod fiat_shamir;
-mod hashing;
-mod merkle_tree;
-mod fields;
-mod fri;
-fn main() {
-    println!("Hello, world!");
+use std::{fs, path::{Path, PathBuf}, process::ExitCode};
+
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_serialize::CanonicalSerialize;
+use clap::{Parser, Subcommand};
+
+use fri_commitment::fields::{goldilocks_field::Fq, serde_utils::field_from_str};
+use fri_commitment::fri::{
+    prover::generate_fri_proof_with_degree,
+    utils::{new_fri_transcript, DEFAULT_DOMAIN_SEPARATOR},
+    schedule::{optimal_reduction_schedule, HasherCosts, ScheduleObjective},
+    security::SoundnessRegime,
+    types::{FRIProof, FriConfig},
+    verifier::verify_fri_proof,
+};
+use fri_commitment::hashing::hasher::Sha256_;
+
+// The command line tool works over Goldilocks with the SHA-256 hasher
+type Hasher = Sha256_<Fq>;
+type Proof = FRIProof<Fq, Hasher>;
+
+// Exit codes: proof rejected, and any other failure (I/O, parsing, invalid config)
+const EXIT_REJECTED: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+#[derive(Parser)]
+#[command(name = "fri-commitment", about = "Prove and verify FRI low degree tests over Goldilocks")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prove that a polynomial has degree at most len(coeffs) - 1
+    Prove {
+        /// JSON array of coefficients (decimal strings or integers), lowest degree first
+        #[arg(long)]
+        poly: PathBuf,
+        /// TOML FriConfig
+        #[arg(long)]
+        config: PathBuf,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Verify a proof, exits with 1 if it is rejected
+    Verify {
+        #[arg(long)]
+        proof: PathBuf,
+        #[arg(long)]
+        config: PathBuf,
+        #[arg(long)]
+        degree: u32,
+    },
+    /// Print the structure and size breakdown of a proof
+    Inspect {
+        proof: PathBuf,
+    },
+    /// Print a config reaching the given security level
+    Params {
+        #[arg(long)]
+        security: u32,
+        #[arg(long, default_value_t = (1 << 16) - 1)]
+        degree: u32,
+        #[arg(long, default_value_t = 8)]
+        blow_up: u32,
+        #[arg(long, default_value_t = 0)]
+        last_degree: u32,
+        #[arg(long, default_value_t = 0)]
+        cap_bits: u32,
+        /// Grinding bits the prover has to find before the queries are drawn
+        #[arg(long, default_value_t = 0)]
+        pow_bits: u32,
+        /// Use the proven soundness bound instead of the ethSTARK conjecture
+        #[arg(long)]
+        proven: bool,
+    },
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+}
+
+fn read_config(path: &Path) -> Result<FriConfig, String> {
+    let text = String::from_utf8(read_file(path)?).map_err(|e| format!("{}: {}", path.display(), e))?;
+    toml::from_str(&text).map_err(|e| format!("invalid config {}: {}", path.display(), e))
+}
+
+// Polynomial and its degree bound, one less than the number of coefficients given. Zero leading
+// coefficients count towards the bound even though the polynomial drops them.
+fn read_polynomial(path: &Path) -> Result<(DensePolynomial<Fq>, u32), String> {
+    let values: Vec<serde_json::Value> = serde_json::from_slice(&read_file(path)?)
+        .map_err(|e| format!("invalid polynomial {}: {}", path.display(), e))?;
+    let coeffs = values.iter().map(|v| {
+        let parsed = match v {
+            serde_json::Value::String(s) => field_from_str::<Fq>(s),
+            serde_json::Value::Number(n) => n.as_u64().map(Fq::from),
+            _ => None,
+        };
+        parsed.ok_or_else(|| format!("invalid coefficient {} in {}", v, path.display()))
+    }).collect::<Result<Vec<Fq>, String>>()?;
+    if coeffs.is_empty() {
+        return Err(format!("no coefficients in {}", path.display()));
+    }
+    let degree = u32::try_from(coeffs.len() - 1).map_err(|_| format!("too many coefficients in {}", path.display()))?;
+    Ok((DenseUVPolynomial::from_coefficients_vec(coeffs), degree))
+}
+
+fn read_proof(path: &Path) -> Result<Proof, String> {
+    Proof::from_bytes(&read_file(path)?).map_err(|e| format!("invalid proof {}: {}", path.display(), e))
+}
+
+fn prove(poly: &Path, config: &Path, out: &Path) -> Result<(), String> {
+    let (polynomial, degree) = read_polynomial(poly)?;
+    let fri_config = read_config(config)?;
+    fri_config.validate::<Fq>(degree).map_err(|e| format!("invalid config for degree {}: {}", degree, e))?;
+
+    let mut transcript = new_fri_transcript(DEFAULT_DOMAIN_SEPARATOR);
+    let proof = generate_fri_proof_with_degree::<Fq, Hasher>(polynomial, degree, fri_config, &mut transcript);
+    let bytes = proof.to_bytes();
+    fs::write(out, &bytes).map_err(|e| format!("cannot write {}: {}", out.display(), e))?;
+    println!("wrote {} byte proof for degree {} to {}", bytes.len(), degree, out.display());
+    Ok(())
+}
+
+fn verify(proof: &Path, config: &Path, degree: u32) -> Result<bool, String> {
+    let fri_proof = read_proof(proof)?;
+    let fri_config = read_config(config)?;
+    fri_config.validate::<Fq>(degree).map_err(|e| format!("invalid config for degree {}: {}", degree, e))?;
+    Ok(verify_fri_proof(fri_config, degree, fri_proof))
+}
+
+fn inspect(proof: &Path) -> Result<String, String> {
+    let fri_proof = read_proof(proof)?;
+    let total = fri_proof.compressed_size();
+    let final_bytes = fri_proof.final_evaluations.compressed_size();
+    let root_bytes = fri_proof.level_roots.compressed_size();
+    let opening_bytes = fri_proof.query_eval_proofs.compressed_size();
+    let nonce_bytes = fri_proof.pow_nonce.compressed_size();
+
+    let mut report = String::new();
+    report += &format!("levels: {}\n", fri_proof.level_roots.len());
+    report += &format!("final evaluations: {}\n", fri_proof.final_evaluations.len());
+    for (l, (root, eval_proof)) in fri_proof.level_roots.iter().zip(fri_proof.query_eval_proofs.iter()).enumerate() {
+        let merkle_proof = &eval_proof.merkle_proof;
+        report += &format!(
+            "level {}: cap {}, opened leaves {}, leaf size {}, sibling hashes {}, {} bytes\n",
+            l,
+            root.len(),
+            merkle_proof.leaf_indices.len(),
+            merkle_proof.leaves.first().map_or(0, |leaf| leaf.len()),
+            merkle_proof.num_hashes(),
+            eval_proof.compressed_size(),
+        );
+    }
+    report += &format!(
+        "bytes: {} total = {} header + {} final evaluations + {} level roots + {} openings + {} pow nonce\n",
+        total, total - final_bytes - root_bytes - opening_bytes - nonce_bytes, final_bytes, root_bytes, opening_bytes, nonce_bytes,
+    );
+    Ok(report)
+}
+
+fn params(security: u32, degree: u32, blow_up: u32, last_degree: u32, cap_bits: u32, pow_bits: u32, proven: bool) -> Result<String, String> {
+    let regime = if proven { SoundnessRegime::Proven } else { SoundnessRegime::Conjectured };
+    let mut fri_config = FriConfig::for_security::<Fq>(security, degree, blow_up, pow_bits, regime).map_err(|e| e.to_string())?;
+
+    fri_config.last_polynomial_degree = last_degree;
+    fri_config.merkle_cap_bits = cap_bits;
+    fri_config.level_reductions_bits = optimal_reduction_schedule(
+        degree, blow_up, last_degree, cap_bits, fri_config.num_query, &HasherCosts::of::<Fq, Hasher>(), ScheduleObjective::ProofSize,
+    ).ok_or_else(|| format!("no reduction schedule folds degree {} to {} with a cap of {} bits", degree, last_degree, cap_bits))?;
+    fri_config.validate::<Fq>(degree).map_err(|e| e.to_string())?;
+
+    // The proven bound depends on the arities, top up queries for the chosen schedule
+    let achieved = |c: &FriConfig| {
+        let bits = c.security_bits::<Fq>(degree);
+        if proven { bits.proven } else { bits.conjectured }
+    };
+    while achieved(&fri_config) < security as f64 {
+        fri_config.num_query += 1;
+    }
+
+    let bits = fri_config.security_bits::<Fq>(degree);
+    let mut output = format!(
+        "# degree {}\n# security: {:.1} bits conjectured, {:.1} bits proven\n",
+        degree, bits.conjectured, bits.proven,
+    );
+    output += &toml::to_string(&fri_config).map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+fn run(command: Command) -> Result<ExitCode, String> {
+    match command {
+        Command::Prove { poly, config, out } => prove(&poly, &config, &out).map(|_| ExitCode::SUCCESS),
+        Command::Verify { proof, config, degree } => {
+            if verify(&proof, &config, degree)? {
+                println!("proof accepted");
+                Ok(ExitCode::SUCCESS)
+            } else {
+                println!("proof rejected");
+                Ok(ExitCode::from(EXIT_REJECTED))
+            }
+        }
+        Command::Inspect { proof } => inspect(&proof).map(|report| {
+            print!("{}", report);
+            ExitCode::SUCCESS
+        }),
+        Command::Params { security, degree, blow_up, last_degree, cap_bits, pow_bits, proven } => {
+            params(security, degree, blow_up, last_degree, cap_bits, pow_bits, proven).map(|output| {
+                print!("{}", output);
+                ExitCode::SUCCESS
+            })
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    run(Cli::parse().command).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(EXIT_ERROR)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    // Fresh directory under the system temp dir for one test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fri-commitment-cli-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_inputs(dir: &Path) -> (PathBuf, PathBuf) {
+        let poly = dir.join("coeffs.json");
+        let coeffs: Vec<String> = (0..32).map(|i| (i * 11 + 5).to_string()).collect();
+        fs::write(&poly, serde_json::to_string(&coeffs).unwrap()).unwrap();
+
+        let config = dir.join("cfg.toml");
+        fs::write(&config, "num_query = 12\nblow_up_factor = 4\nlast_polynomial_degree = 1\nmerkle_cap_bits = 1\nlevel_reductions_bits = [2, 2]\n").unwrap();
+        (poly, config)
+    }
+
+    #[test]
+    fn test_prove_verify_inspect() {
+        let dir = temp_dir("prove");
+        let (poly, config) = write_inputs(&dir);
+        let out = dir.join("proof.bin");
+
+        prove(&poly, &config, &out).unwrap();
+        assert_eq!(verify(&out, &config, 31), Ok(true));
+        // the degree is part of the statement
+        assert!(verify(&out, &config, 15).is_err());
+
+        let report = inspect(&out).unwrap();
+        assert!(report.starts_with("levels: 2\nfinal evaluations: 8\n"));
+        let total = fs::read(&out).unwrap().len();
+        assert!(report.contains(&format!("bytes: {} total = 1 header", total)));
+
+        assert!(report.ends_with("+ 8 pow nonce\n"));
+
+        // flip the low byte of the first final evaluation, after the version and the length prefix
+        let mut bytes = fs::read(&out).unwrap();
+        bytes[9] ^= 1;
+        fs::write(&out, &bytes).unwrap();
+        assert_eq!(verify(&out, &config, 31), Ok(false));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_zero_leading_coefficients() {
+        let dir = temp_dir("leading");
+        let poly = dir.join("coeffs.json");
+        let out = dir.join("proof.bin");
+        let config = dir.join("cfg.toml");
+
+        // the degree comes from the number of coefficients, zero leading ones included
+        for (coeffs, degree, level_reductions_bits) in [("[\"1\", \"2\", \"3\", \"0\"]", 3, "[2]"), ("[\"0\", \"0\"]", 1, "[1]")] {
+            fs::write(&poly, coeffs).unwrap();
+            fs::write(&config, format!("num_query = 4\nblow_up_factor = 4\nlast_polynomial_degree = 0\nmerkle_cap_bits = 0\nlevel_reductions_bits = {}\n", level_reductions_bits)).unwrap();
+            prove(&poly, &config, &out).unwrap();
+            assert_eq!(verify(&out, &config, degree), Ok(true));
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let dir = temp_dir("invalid");
+        let (poly, config) = write_inputs(&dir);
+
+        fs::write(&poly, "[\"1\", \"-2\"]").unwrap();
+        assert!(prove(&poly, &config, &dir.join("proof.bin")).is_err());
+        fs::write(&poly, "[1, 2, 3]").unwrap();
+        assert!(prove(&poly, &config, &dir.join("proof.bin")).is_err());
+        fs::write(&poly, "[]").unwrap();
+        assert!(prove(&poly, &config, &dir.join("proof.bin")).unwrap_err().contains("no coefficients"));
+
+        fs::write(dir.join("proof.bin"), [0u8; 4]).unwrap();
+        assert!(verify(&dir.join("proof.bin"), &config, 31).is_err());
+        assert!(inspect(&dir.join("missing.bin")).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_params() {
+        let output = params(30, 1023, 8, 1, 2, 0, false).unwrap();
+        let fri_config: FriConfig = toml::from_str(&output).unwrap();
+        assert_eq!(fri_config.validate::<Fq>(1023), Ok(()));
+        assert!(fri_config.security_bits::<Fq>(1023).conjectured >= 30.0);
+
+        let proven: FriConfig = toml::from_str(&params(15, 1023, 8, 1, 2, 0, true).unwrap()).unwrap();
+        assert!(proven.security_bits::<Fq>(1023).proven >= 15.0);
+
+        // grinding is kept in the config and replaces queries
+        let ground: FriConfig = toml::from_str(&params(30, 1023, 8, 1, 2, 9, false).unwrap()).unwrap();
+        assert_eq!(ground.pow_bits, 9);
+        assert_eq!(ground.num_query, fri_config.num_query - 3);
+
+        // Goldilocks alone cannot reach 100 bits
+        assert!(params(100, 65535, 8, 0, 0, 0, false).unwrap_err().contains("challenge field too small"));
+    }
 }
@@ -0,0 +1,232 @@
+use ark_ff::PrimeField;
+
+use crate::hashing::hasher::Hasher_;
+
+// Merkle tree where every node has `arity` children, leaves are hashed as in the binary tree
+// and a node hashes its children by chaining hash_two_to_one over them, left to right
+#[derive(Clone)]
+pub struct KaryMerkleTree<F: PrimeField, H: Hasher_<F>> {
+    pub root: Option<H::Hash>,
+    arity: usize,
+    levels: Vec<Vec<H::Hash>>, // leaf hashes first, root last
+    leaves: Vec<Vec<F>>,
+    depth: u32,
+}
+
+// Batched opening of several leaves, siblings which the verifier can recompute are omitted
+#[derive(Clone, Debug)]
+pub struct KaryMultiProof<F: PrimeField, H: Hasher_<F>> {
+    pub leaves: Vec<Vec<F>>,
+    pub leaf_indices: Vec<usize>, // sorted and deduplicated, leaves[i] is the leaf at leaf_indices[i]
+    proof: Vec<H::Hash>, // missing siblings, level by level from the leaves up, in increasing index order
+}
+
+fn hash_children<F: PrimeField, H: Hasher_<F>>(children: &[H::Hash]) -> H::Hash {
+    children[1..].iter().fold(children[0], |acc, child| H::hash_two_to_one(acc, *child))
+}
+
+// Number of levels of a tree with `arity` children per node and at least `num_leaves` leaves
+pub fn kary_depth(arity: usize, num_leaves: usize) -> u32 {
+    let mut depth = 0;
+    let mut capacity = 1usize;
+    while capacity < num_leaves {
+        capacity = match capacity.checked_mul(arity) {
+            Some(c) => c,
+            None => return u32::MAX,
+        };
+        depth += 1;
+    }
+    depth
+}
+
+impl<F: PrimeField, H: Hasher_<F>> KaryMultiProof<F, H> {
+    // Returns the opened leaf at `idx`, if it is part of the proof
+    pub fn leaf(&self, idx: usize) -> Option<&Vec<F>> {
+        self.leaf_indices.binary_search(&idx).ok().map(|i| &self.leaves[i])
+    }
+
+    pub fn num_hashes(&self) -> usize {
+        self.proof.len()
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> KaryMerkleTree<F, H> {
+    pub fn new(arity: usize) -> Self {
+        assert!(arity >= 2, "Arity must be at least 2");
+        Self { root: None, arity, levels: Vec::new(), leaves: Vec::new(), depth: 0 }
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    pub fn insert(&mut self, leaves: Vec<Vec<F>>) {
+        self.leaves.extend(leaves);
+    }
+
+    // Leaf at `idx`, padding leaves included
+    pub fn leaf(&self, idx: usize) -> Vec<F> {
+        self.leaves.get(idx).cloned().unwrap_or_else(|| vec![F::ZERO])
+    }
+
+    pub fn compute_tree(&mut self) -> H::Hash {
+        // Extend len to a power of the arity, padding leaves are not stored
+        self.depth = kary_depth(self.arity, self.leaves.len());
+        let num_leaves = self.arity.pow(self.depth);
+        let mut level: Vec<H::Hash> = (0..num_leaves).map(|idx| H::hash_elements(&self.leaf(idx))).collect();
+        self.levels = Vec::with_capacity(self.depth as usize + 1);
+        for _ in 0..self.depth {
+            let next = level.chunks(self.arity).map(hash_children::<F, H>).collect();
+            self.levels.push(level);
+            level = next;
+        }
+        self.levels.push(level);
+        let root = self.levels[self.depth as usize][0];
+        self.root = Some(root);
+        root
+    }
+
+    // Opens all leaves in `indices` at once, sharing siblings between the paths
+    pub fn multi_proof(&self, indices: &[usize]) -> KaryMultiProof<F, H> {
+        let mut leaf_indices = indices.to_vec();
+        leaf_indices.sort_unstable();
+        leaf_indices.dedup();
+
+        let leaves = leaf_indices.iter().map(|idx| self.leaf(*idx)).collect();
+
+        let mut proof: Vec<H::Hash> = Vec::new();
+        let mut curr_indices = leaf_indices.clone();
+        for level in 0..self.depth as usize {
+            let mut j = 0;
+            while j < curr_indices.len() {
+                let parent = curr_indices[j] / self.arity;
+                for child in parent * self.arity..(parent + 1) * self.arity {
+                    if j < curr_indices.len() && curr_indices[j] == child {
+                        // opened, verifier recomputes it
+                        j += 1;
+                    } else {
+                        proof.push(self.levels[level][child]);
+                    }
+                }
+            }
+            curr_indices = curr_indices.iter().map(|idx| idx / self.arity).collect();
+            curr_indices.dedup();
+        }
+
+        KaryMultiProof { leaves, leaf_indices, proof }
+    }
+}
+
+// Verifies a batched opening against `root` for a tree with arity^depth leaves
+pub fn verify_kary_multi_proof<F: PrimeField, H: Hasher_<F>>(root: &H::Hash, arity: usize, depth: u32, proof: &KaryMultiProof<F, H>) -> bool {
+    if arity < 2 || proof.leaves.len() != proof.leaf_indices.len() {
+        return false;
+    }
+    let num_leaves = match arity.checked_pow(depth) {
+        Some(n) => n,
+        None => return false,
+    };
+    // Indices must be strictly increasing and inside the tree
+    if proof.leaf_indices.windows(2).any(|w| w[0] >= w[1]) || proof.leaf_indices.iter().any(|i| *i >= num_leaves) {
+        return false;
+    }
+
+    let mut siblings = proof.proof.iter();
+    // (index, hash) pairs of the current level, kept sorted by index
+    let mut nodes: Vec<(usize, H::Hash)> = proof.leaf_indices.iter().zip(proof.leaves.iter())
+        .map(|(idx, leaf)| (*idx, H::hash_elements(leaf)))
+        .collect();
+
+    for _ in 0..depth {
+        let mut next_nodes: Vec<(usize, H::Hash)> = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            let parent = nodes[i].0 / arity;
+            let mut children = Vec::with_capacity(arity);
+            for child in parent * arity..(parent + 1) * arity {
+                if i < nodes.len() && nodes[i].0 == child {
+                    children.push(nodes[i].1);
+                    i += 1;
+                } else {
+                    match siblings.next() {
+                        Some(sibling) => children.push(*sibling),
+                        None => return false,
+                    }
+                }
+            }
+            next_nodes.push((parent, hash_children::<F, H>(&children)));
+        }
+        nodes = next_nodes;
+    }
+
+    siblings.next().is_none() && nodes.iter().all(|(_, hash)| hash == root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields::goldilocks_field::Fq, hashing::hasher::Sha256_, merkle_tree::merkle::MerkleTree};
+
+    fn build_tree(num_leaves: usize, arity: usize) -> KaryMerkleTree<Fq, Sha256_<Fq>> {
+        let mut tree = KaryMerkleTree::<Fq, Sha256_<Fq>>::new(arity);
+        tree.insert((0..num_leaves).map(|i| vec![Fq::from(i as u64 + 1), Fq::from(3 * i as u64)]).collect());
+        tree.compute_tree();
+        tree
+    }
+
+    #[test]
+    fn test_kary_multi_proof() {
+        // 50 leaves padded to 64
+        let tree = build_tree(50, 4);
+        let root = tree.root.unwrap();
+        assert_eq!(kary_depth(4, 50), 3);
+
+        let multi_proof = tree.multi_proof(&[7, 4, 5, 49, 7, 63]);
+        assert_eq!(multi_proof.leaf_indices, vec![4, 5, 7, 49, 63]);
+        assert_eq!(multi_proof.leaf(49), Some(&tree.leaf(49)));
+        assert_eq!(multi_proof.leaf(6), None);
+        assert!(verify_kary_multi_proof(&root, 4, 3, &multi_proof));
+
+        // a single index needs arity - 1 siblings per level
+        let single = tree.multi_proof(&[21]);
+        assert_eq!(single.num_hashes(), 3 * 3);
+        assert!(verify_kary_multi_proof(&root, 4, 3, &single));
+    }
+
+    #[test]
+    fn test_binary_matches_merkle_tree() {
+        let leaves: Vec<Vec<Fq>> = (0..16).map(|i| vec![Fq::from(i as u64), Fq::from(7u64)]).collect();
+        let mut kary = KaryMerkleTree::<Fq, Sha256_<Fq>>::new(2);
+        kary.insert(leaves.clone());
+        let mut binary = MerkleTree::<Fq, Sha256_<Fq>>::new(0);
+        binary.insert(leaves).unwrap();
+        assert_eq!(vec![kary.compute_tree()], binary.compute_tree().unwrap());
+    }
+
+    #[test]
+    fn test_kary_multi_proof_tampered() {
+        let tree = build_tree(27, 3);
+        let root = tree.root.unwrap();
+        let multi_proof = tree.multi_proof(&[1, 2, 13, 26]);
+        assert!(verify_kary_multi_proof(&root, 3, 3, &multi_proof));
+
+        let mut bad_leaf = multi_proof.clone();
+        bad_leaf.leaves[2][0] += Fq::from(1);
+        assert!(!verify_kary_multi_proof(&root, 3, 3, &bad_leaf));
+
+        let mut bad_index = multi_proof.clone();
+        bad_index.leaf_indices[0] = 0;
+        assert!(!verify_kary_multi_proof(&root, 3, 3, &bad_index));
+
+        let mut bad_sibling = multi_proof.clone();
+        bad_sibling.proof[0] += Fq::from(1);
+        assert!(!verify_kary_multi_proof(&root, 3, 3, &bad_sibling));
+
+        let mut missing_sibling = multi_proof.clone();
+        missing_sibling.proof.pop();
+        assert!(!verify_kary_multi_proof(&root, 3, 3, &missing_sibling));
+
+        assert!(!verify_kary_multi_proof(&root, 3, 4, &multi_proof));
+        assert!(!verify_kary_multi_proof(&root, 4, 3, &multi_proof));
+    }
+}
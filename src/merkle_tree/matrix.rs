@@ -0,0 +1,270 @@
+use ark_ff::PrimeField;
+
+use crate::hashing::hasher::Hasher_;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowMajorMatrix<F: PrimeField> {
+    pub values: Vec<F>,
+    pub width: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatrixDimensions {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<F: PrimeField> RowMajorMatrix<F> {
+    pub fn new(values: Vec<F>, width: usize) -> Self {
+        assert!(width > 0 && values.len().is_multiple_of(width), "Values do not fill rows of the given width");
+        RowMajorMatrix { values, width }
+    }
+
+    pub fn height(&self) -> usize {
+        self.values.len() / self.width
+    }
+
+    pub fn row(&self, idx: usize) -> &[F] {
+        &self.values[idx * self.width..(idx + 1) * self.width]
+    }
+
+    pub fn dimensions(&self) -> MatrixDimensions {
+        MatrixDimensions { width: self.width, height: self.height() }
+    }
+}
+
+// Commits several matrices with power of two heights in one tree. Leaf i hashes row i of the tallest
+// matrices, and the rows of matrices with 2^k rows are injected into the layer with 2^k nodes:
+// node = H(H(left, right), H(rows)). Each row is hashed element by element, and the row hashes of the
+// matrices sharing a height are chained in insertion order, so values cannot move between columns or matrices.
+pub struct MatrixMerkleTree<F: PrimeField, H: Hasher_<F>> {
+    pub root_cap: Option<Vec<H::Hash>>,
+    matrices: Vec<RowMajorMatrix<F>>,
+    layers: Vec<Vec<H::Hash>>, // leaf hashes first, cap last
+    merkle_cap_bits: u32,
+}
+
+// Row of every committed matrix at a query index, in the order the matrices were inserted
+#[derive(Clone, Debug)]
+pub struct MatrixOpening<F: PrimeField, H: Hasher_<F>> {
+    pub rows: Vec<Vec<F>>,
+    proof: Vec<H::Hash>, // one sibling per layer below the cap
+}
+
+impl<F: PrimeField, H: Hasher_<F>> MatrixOpening<F, H> {
+    pub fn num_hashes(&self) -> usize {
+        self.proof.len()
+    }
+}
+
+fn rows_hash<'a, F: PrimeField, H: Hasher_<F>>(mut rows: impl Iterator<Item = &'a [F]>) -> H::Hash {
+    let first = H::hash_elements(rows.next().expect("No rows to hash"));
+    rows.fold(first, |acc, row| H::hash_two_to_one(acc, H::hash_elements(row)))
+}
+
+// log2 of the tallest height, None if some height is not a power of two or the cap is above the shortest matrix
+fn tree_depth(dimensions: &[MatrixDimensions], merkle_cap_bits: u32) -> Option<u32> {
+    if dimensions.is_empty() || dimensions.iter().any(|d| !d.height.is_power_of_two()) {
+        return None;
+    }
+    let min_height = dimensions.iter().map(|d| d.height).min().unwrap();
+    if min_height.trailing_zeros() < merkle_cap_bits {
+        return None;
+    }
+    Some(dimensions.iter().map(|d| d.height).max().unwrap().trailing_zeros())
+}
+
+impl<F: PrimeField, H: Hasher_<F>> MatrixMerkleTree<F, H> {
+    pub fn new(merkle_cap_bits: u32) -> Self {
+        Self { root_cap: None, matrices: Vec::new(), layers: Vec::new(), merkle_cap_bits }
+    }
+
+    pub fn insert(&mut self, matrix: RowMajorMatrix<F>) {
+        self.matrices.push(matrix);
+    }
+
+    pub fn dimensions(&self) -> Vec<MatrixDimensions> {
+        self.matrices.iter().map(|m| m.dimensions()).collect()
+    }
+
+    // Hash of the rows at `idx` of the matrices with `height` rows, None if there are none
+    fn injected_hash(&self, height: usize, idx: usize) -> Option<H::Hash> {
+        let mut rows = self.matrices.iter().filter(|m| m.height() == height).map(|m| m.row(idx)).peekable();
+        rows.peek()?;
+        Some(rows_hash::<F, H>(rows))
+    }
+
+    pub fn compute_tree(&mut self) -> Vec<H::Hash> {
+        let depth = tree_depth(&self.dimensions(), self.merkle_cap_bits)
+            .expect("Heights must be powers of two and at least 2^merkle_cap_bits");
+        let max_height = 1usize << depth;
+
+        let mut layer: Vec<H::Hash> = (0..max_height).map(|idx| self.injected_hash(max_height, idx).unwrap()).collect();
+        self.layers = Vec::with_capacity((depth - self.merkle_cap_bits) as usize + 1);
+        for level in 1..=(depth - self.merkle_cap_bits) {
+            let height = max_height >> level;
+            let next = (0..height).map(|idx| {
+                let node = H::hash_two_to_one(layer[2 * idx], layer[2 * idx + 1]);
+                match self.injected_hash(height, idx) {
+                    Some(rows) => H::hash_two_to_one(node, rows),
+                    None => node,
+                }
+            }).collect();
+            self.layers.push(layer);
+            layer = next;
+        }
+        self.layers.push(layer.clone());
+        self.root_cap = Some(layer.clone());
+        layer
+    }
+
+    // Opens row `idx` of the tallest matrices, and row idx >> (log2 of the height ratio) of the others
+    pub fn open(&self, idx: usize) -> MatrixOpening<F, H> {
+        let max_height = self.layers[0].len();
+        assert!(idx < max_height, "Index out of the tree");
+        let rows = self.matrices.iter().map(|m| m.row(idx / (max_height / m.height())).to_vec()).collect();
+        let proof = self.layers[..self.layers.len() - 1].iter().enumerate()
+            .map(|(level, layer)| layer[(idx >> level) ^ 1])
+            .collect();
+        MatrixOpening { rows, proof }
+    }
+}
+
+// Verifies the opening at `idx` against `root_cap` for matrices of the given dimensions, in insertion order
+pub fn verify_matrix_opening<F: PrimeField, H: Hasher_<F>>(root_cap: &[H::Hash], dimensions: &[MatrixDimensions], idx: usize, opening: &MatrixOpening<F, H>) -> bool {
+    if !root_cap.len().is_power_of_two() || opening.rows.len() != dimensions.len()
+        || opening.rows.iter().zip(dimensions.iter()).any(|(row, d)| row.len() != d.width) {
+        return false;
+    }
+    let cap_bits = root_cap.len().trailing_zeros();
+    let depth = match tree_depth(dimensions, cap_bits) {
+        Some(depth) => depth,
+        None => return false,
+    };
+    if idx >> depth != 0 || opening.proof.len() != (depth - cap_bits) as usize {
+        return false;
+    }
+
+    let injected_hash = |height: usize| -> Option<H::Hash> {
+        let mut rows = opening.rows.iter().zip(dimensions.iter())
+            .filter(|(_, d)| d.height == height)
+            .map(|(row, _)| row.as_slice())
+            .peekable();
+        rows.peek()?;
+        Some(rows_hash::<F, H>(rows))
+    };
+
+    let mut current = injected_hash(1 << depth).unwrap();
+    let mut node_idx = idx;
+    for (level, sibling) in opening.proof.iter().enumerate() {
+        current = if node_idx.is_multiple_of(2) {
+            H::hash_two_to_one(current, *sibling)
+        } else {
+            H::hash_two_to_one(*sibling, current)
+        };
+        node_idx /= 2;
+        if let Some(rows) = injected_hash(1 << (depth as usize - level - 1)) {
+            current = H::hash_two_to_one(current, rows);
+        }
+    }
+    root_cap[node_idx] == current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields::goldilocks_field::Fq, hashing::hasher::Sha256_, merkle_tree::merkle::MerkleTree};
+
+    fn matrix(height: usize, width: usize, seed: u64) -> RowMajorMatrix<Fq> {
+        RowMajorMatrix::new((0..(height * width) as u64).map(|i| Fq::from(i * seed + 1)).collect(), width)
+    }
+
+    fn build_tree(merkle_cap_bits: u32) -> MatrixMerkleTree<Fq, Sha256_<Fq>> {
+        let mut tree = MatrixMerkleTree::new(merkle_cap_bits);
+        tree.insert(matrix(8, 2, 3));
+        tree.insert(matrix(32, 3, 5));
+        tree.insert(matrix(8, 1, 7));
+        tree.insert(matrix(32, 2, 11));
+        tree.insert(matrix(4, 4, 13));
+        tree.compute_tree();
+        tree
+    }
+
+    #[test]
+    fn test_matrix_opening() {
+        for merkle_cap_bits in [0, 2] {
+            let tree = build_tree(merkle_cap_bits);
+            let root_cap = tree.root_cap.clone().unwrap();
+            let dimensions = tree.dimensions();
+            for idx in [0, 5, 22, 31] {
+                let opening = tree.open(idx);
+                assert_eq!(opening.rows[1], tree.matrices[1].row(idx));
+                assert_eq!(opening.rows[0], tree.matrices[0].row(idx / 4));
+                assert_eq!(opening.rows[4], tree.matrices[4].row(idx / 8));
+                assert_eq!(opening.num_hashes(), 5 - merkle_cap_bits as usize);
+                assert!(verify_matrix_opening(&root_cap, &dimensions, idx, &opening));
+            }
+        }
+    }
+
+    // A row of one element hashes as a leaf of one element does
+    #[test]
+    fn test_single_matrix_matches_merkle_tree() {
+        let m = matrix(16, 1, 2);
+        let mut tree = MatrixMerkleTree::<Fq, Sha256_<Fq>>::new(1);
+        tree.insert(m.clone());
+        let mut merkle = MerkleTree::<Fq, Sha256_<Fq>>::new(1);
+        merkle.insert((0..16).map(|i| m.row(i).to_vec()).collect()).unwrap();
+        assert_eq!(tree.compute_tree(), merkle.compute_tree().unwrap());
+    }
+
+    #[test]
+    fn test_matrix_opening_tampered() {
+        let tree = build_tree(1);
+        let root_cap = tree.root_cap.clone().unwrap();
+        let dimensions = tree.dimensions();
+        let opening = tree.open(13);
+
+        // a row of an injected matrix
+        let mut bad_row = opening.clone();
+        bad_row.rows[4][2] += Fq::from(1);
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &bad_row));
+
+        // value moved between two columns of a row
+        let mut moved_column = opening.clone();
+        moved_column.rows[1][0] += Fq::from(1000);
+        moved_column.rows[1][1] -= Fq::from(1000);
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &moved_column));
+
+        // value moved between the rows of two matrices of the same height
+        let mut moved_matrix = opening.clone();
+        moved_matrix.rows[0][0] += Fq::from(1000);
+        moved_matrix.rows[2][0] -= Fq::from(1000);
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &moved_matrix));
+
+        // and between matrices of different heights
+        let mut moved_height = opening.clone();
+        moved_height.rows[0][0] += Fq::from(1000);
+        moved_height.rows[1][0] -= Fq::from(1000);
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &moved_height));
+
+        let mut bad_sibling = opening.clone();
+        bad_sibling.proof[3] += Fq::from(1);
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &bad_sibling));
+
+        let mut missing_sibling = opening.clone();
+        missing_sibling.proof.pop();
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 13, &missing_sibling));
+
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 12, &opening));
+        assert!(!verify_matrix_opening(&root_cap, &dimensions, 45, &opening));
+
+        // other dimensions
+        let mut swapped = dimensions.clone();
+        swapped.swap(0, 2);
+        assert!(!verify_matrix_opening(&root_cap, &swapped, 13, &opening));
+        let mut taller = dimensions.clone();
+        taller[4].height = 8;
+        assert!(!verify_matrix_opening(&root_cap, &taller, 13, &opening));
+    }
+}
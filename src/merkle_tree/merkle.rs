@@ -1,42 +1,115 @@
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate, Write};
 
 use crate::hashing::hasher::Hasher_;
+use super::storage::{HashLayer, LayerSink, LeafStorage, LowerLayers, StorageError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // TODO : generalise for different kind of leaves (hash_n_or_noop (< HASH_OUT size leaves, hash_n_to_m size leaves)
 #[derive(Clone)]
 pub struct MerkleTree<F: PrimeField, H: Hasher_<F>> {
     pub root_cap: Option<Vec<H::Hash>>,
-    levels: Vec<Vec<H::Hash>>, // Precompute hash values at each level
-    pub leaves: Vec<Vec<F>>,
+    levels: Vec<HashLayer<H::Hash>>, // Precompute hash values at each level
+    pub leaves: LeafStorage<F>,
+    lower_layers: LowerLayers,
+    cached_layers: u32, // layers right below the cap which are always kept in memory
     depth: u32,
     merkle_cap_bits: u32, // bits
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
 pub struct MerkleProof<F: PrimeField, H: Hasher_<F>> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::field_vec"))]
     pub leaf: Vec<F>,
     pub leaf_idx: usize,
     // merkle_cap_bits: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::hash_vec"))]
     proof: Vec<H::Hash>, // [L1, L2, ...] one neighbour corresponding to each level
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::hash_vec"))]
     root_cap: Vec<H::Hash>, // indexes of each node to determine left or right direction
 }
 
+// Batched opening of several leaves against the same root cap.
+// Siblings which the verifier can recompute from other opened leaves are omitted,
+// and the root cap is not carried along (the verifier already holds it).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(bound = ""))]
+pub struct MerkleMultiProof<F: PrimeField, H: Hasher_<F>> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::field_vec_vec"))]
+    pub leaves: Vec<Vec<F>>,
+    pub leaf_indices: Vec<usize>, // sorted and deduplicated, leaves[i] is the leaf at leaf_indices[i]
+    #[cfg_attr(feature = "serde", serde(with = "crate::fields::serde_utils::hash_vec"))]
+    proof: Vec<H::Hash>, // missing siblings, level by level from the leaves up, in increasing index order
+}
+
+impl<F: PrimeField, H: Hasher_<F>> MerkleMultiProof<F, H> {
+    // Returns the opened leaf at `idx`, if it is part of the proof
+    pub fn leaf(&self, idx: usize) -> Option<&Vec<F>> {
+        self.leaf_indices.binary_search(&idx).ok().map(|i| &self.leaves[i])
+    }
+
+    pub fn num_hashes(&self) -> usize {
+        self.proof.len()
+    }
+
+    pub fn siblings(&self) -> &[H::Hash] {
+        &self.proof
+    }
+
+    // Recovers the authentication path (one sibling per level below the cap) of the opened leaf `idx`,
+    // None if the leaf is not opened or the proof runs out of siblings
+    pub fn path(&self, idx: usize, depth: u32, cap_bits: u32) -> Option<Vec<H::Hash>> {
+        if self.leaf(idx).is_none() || cap_bits > depth || self.leaves.len() != self.leaf_indices.len() {
+            return None;
+        }
+        let mut siblings = self.proof.iter();
+        let mut nodes: Vec<(usize, H::Hash)> = self.leaf_indices.iter().zip(self.leaves.iter())
+            .map(|(i, leaf)| (*i, H::hash_elements(leaf)))
+            .collect();
+        let mut path = Vec::new();
+        let mut current = idx;
+        for _ in 0..depth-cap_bits {
+            let mut next_nodes: Vec<(usize, H::Hash)> = Vec::with_capacity(nodes.len());
+            let mut i = 0;
+            while i < nodes.len() {
+                let (node_idx, hash) = nodes[i];
+                let (left, right) = if node_idx.is_multiple_of(2) && i+1 < nodes.len() && nodes[i+1].0 == node_idx+1 {
+                    i += 1;
+                    (hash, nodes[i].1)
+                } else {
+                    let neighbour = *siblings.next()?;
+                    if node_idx.is_multiple_of(2) { (hash, neighbour) } else { (neighbour, hash) }
+                };
+                if node_idx/2 == current/2 {
+                    path.push(if current.is_multiple_of(2) { right } else { left });
+                }
+                next_nodes.push((node_idx/2, H::hash_two_to_one(left, right)));
+                i += 1;
+            }
+            nodes = next_nodes;
+            current /= 2;
+        }
+        Some(path)
+    }
+}
+
 pub fn merkle_path_verify<F: PrimeField, H: Hasher_<F>>(proof: &MerkleProof<F, H>) -> bool {
     let depth = proof.proof.len();
 
     let mut curr_idx = proof.leaf_idx;
-    // TODO Change point addition when integrate sponge hasher
-    let mut computed_val = H::hash(proof.leaf.iter().sum());//proof.leaf;
+    let mut computed_val = H::hash_elements(&proof.leaf);
     // compute root
     for i in 0..depth {
-        if curr_idx%2 == 0 {
+        if curr_idx.is_multiple_of(2) {
             // we have l node
-            let neighbour = proof.proof[i as usize]; // extracted right node
+            let neighbour = proof.proof[i]; // extracted right node
             computed_val = H::hash_two_to_one(computed_val, neighbour);
-            curr_idx = curr_idx/2;
+            curr_idx /= 2;
         } else {
             // we have r node
-            let neighbour = proof.proof[i as usize];
+            let neighbour = proof.proof[i];
             computed_val = H::hash_two_to_one(neighbour, computed_val); // extracted left
             curr_idx = (curr_idx-1)/2;
         }
@@ -44,68 +117,170 @@ pub fn merkle_path_verify<F: PrimeField, H: Hasher_<F>>(proof: &MerkleProof<F, H
     computed_val == proof.root_cap[curr_idx]
 }
 
+// Verifies a batched opening of at least one leaf against `root_cap` for a tree with 2^depth leaves
+pub fn verify_multi_proof<F: PrimeField, H: Hasher_<F>>(root_cap: &[H::Hash], depth: u32, proof: &MerkleMultiProof<F, H>) -> bool {
+    if !root_cap.len().is_power_of_two() || proof.leaves.len() != proof.leaf_indices.len() {
+        return false;
+    }
+    // an empty opening would verify against any root
+    if proof.leaf_indices.is_empty() || depth >= usize::BITS {
+        return false;
+    }
+    let cap_bits = root_cap.len().trailing_zeros();
+    if cap_bits > depth {
+        return false;
+    }
+    // Indices must be strictly increasing and inside the tree
+    if proof.leaf_indices.windows(2).any(|w| w[0] >= w[1]) || proof.leaf_indices.iter().any(|i| *i >= 1<<depth) {
+        return false;
+    }
+
+    let mut siblings = proof.proof.iter();
+    // (index, hash) pairs of the current level, kept sorted by index
+    let mut nodes: Vec<(usize, H::Hash)> = proof.leaf_indices.iter().zip(proof.leaves.iter())
+        .map(|(idx, leaf)| (*idx, H::hash_elements(leaf)))
+        .collect();
+
+    for _ in 0..depth-cap_bits {
+        let mut next_nodes: Vec<(usize, H::Hash)> = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            let (idx, hash) = nodes[i];
+            let parent = if idx.is_multiple_of(2) {
+                if i+1 < nodes.len() && nodes[i+1].0 == idx+1 {
+                    // both children opened, sibling not needed
+                    i += 1;
+                    H::hash_two_to_one(hash, nodes[i].1)
+                } else {
+                    match siblings.next() {
+                        Some(neighbour) => H::hash_two_to_one(hash, *neighbour),
+                        None => return false,
+                    }
+                }
+            } else {
+                match siblings.next() {
+                    Some(neighbour) => H::hash_two_to_one(*neighbour, hash),
+                    None => return false,
+                }
+            };
+            next_nodes.push((idx/2, parent));
+            i += 1;
+        }
+        nodes = next_nodes;
+    }
+
+    siblings.next().is_none() && nodes.iter().all(|(idx, hash)| root_cap[*idx] == *hash)
+}
+
 impl<F: PrimeField, H: Hasher_<F>> MerkleTree<F, H> {
     // Start a new merkle tree
     pub fn new(merkle_cap_bits: u32) -> Self {
+        Self::with_storage(merkle_cap_bits, LeafStorage::Memory(Vec::new()), LowerLayers::Memory, 0)
+    }
+
+    // Tree over leaves which may live outside memory. Only the cap and the `cached_layers` layers
+    // below it are guaranteed to be held in memory, the others are kept as `lower_layers` says.
+    pub fn with_storage(merkle_cap_bits: u32, leaves: LeafStorage<F>, lower_layers: LowerLayers, cached_layers: u32) -> Self {
         Self {
                 root_cap: None,
                 levels: Vec::new(),
-                leaves: Vec::new(),
+                leaves,
+                lower_layers,
+                cached_layers,
                 depth: 0,
-                merkle_cap_bits 
+                merkle_cap_bits
             }
     }
 
-    pub fn insert(&mut self, leaves: Vec<Vec<F>>) {
-        self.leaves.extend(leaves);
+    pub fn insert(&mut self, leaves: Vec<Vec<F>>) -> Result<(), StorageError> {
+        match &mut self.leaves {
+            LeafStorage::Memory(stored) => stored.extend(leaves),
+            _ => return Err(StorageError::LeavesNotInMemory),
+        }
+        Ok(())
     }
 
-    pub fn compute_tree(&mut self) -> Vec<H::Hash> {
-        // Extend len to power of two
-        let new_len = self.leaves.len().next_power_of_two();
-        self.leaves.resize(new_len, vec![F::ZERO]);
-
-        let num_levels = (new_len as f64).log2() as usize;
+    pub fn num_leaves(&self) -> usize {
+        self.leaves.len()
+    }
 
-        self.depth = num_levels as u32;
+    // Leaf at `idx`, padding leaves included
+    pub fn leaf(&self, idx: usize) -> Vec<F> {
+        self.leaves.leaf(idx)
+    }
 
-        let mut levels: Vec<Vec<H::Hash>> = Vec::new();
+    // Fails only if the lower layers are kept out of memory and cannot be written
+    pub fn compute_tree(&mut self) -> Result<Vec<H::Hash>, StorageError> {
+        // Extend len to power of two, padding leaves are not stored
+        let new_len = self.leaves.len().next_power_of_two();
 
-        let first_level = self.leaves.iter().map(|l| H::hash(l.iter().sum())).collect();
-        levels.push(first_level);
+        let num_levels = new_len.trailing_zeros() as usize;
 
         let last_level = num_levels-self.merkle_cap_bits as usize;
 
         assert!(last_level >= 1);
 
-        for i in 0..last_level {
-            let current_layer = levels[i].clone();
-            let next_layer = current_layer.chunks(2).map(|ips|{// [TODO] change 2 to arity
-                H::hash_two_to_one(ips[0], ips[1])
-            }).collect::<Vec<H::Hash>>();
-            levels.push(next_layer);
+        // Single pass over the leaves, each layer is written in order as soon as both children of a node are known
+        let mut sinks: Vec<LayerSink<H::Hash>> = (0..=last_level)
+            .map(|i| LayerSink::new(&self.lower_layers, i, last_level - i <= self.cached_layers as usize))
+            .collect::<Result<_, _>>()?;
+        let mut pending: Vec<Option<H::Hash>> = vec![None; last_level];
+        let mut cap = Vec::new();
+        for idx in 0..new_len {
+            let mut hash = H::hash_elements(&self.leaves.leaf(idx));
+            let mut level = 0;
+            loop {
+                sinks[level].push(hash)?;
+                if level == last_level {
+                    cap.push(hash);
+                    break;
+                }
+                match pending[level].take() {
+                    Some(left) => {
+                        hash = H::hash_two_to_one(left, hash);// [TODO] change 2 to arity
+                        level += 1;
+                    },
+                    None => {
+                        pending[level] = Some(hash);
+                        break;
+                    },
+                }
+            }
         }
-        self.levels = levels;
-        assert_eq!(self.levels[last_level].len(), (2 as usize).pow(self.merkle_cap_bits as u32)); // Top most level will always have one value [TODO] replace with CAP
-        self.root_cap = Some(self.levels[last_level].clone());
-        self.levels[last_level].clone()
+        // the tree is only updated once every layer is stored
+        self.levels = sinks.into_iter().map(|sink| sink.finish()).collect::<Result<_, _>>()?;
+        self.depth = num_levels as u32;
+        assert_eq!(cap.len(), 2_usize.pow(self.merkle_cap_bits)); // Top most level will always have one value [TODO] replace with CAP
+        self.root_cap = Some(cap.clone());
+        Ok(cap)
+    }
+
+    // Hash of node `idx` at `level` (0 being the leaf hashes), recomputed from the layers below when not stored
+    fn node(&self, level: usize, idx: usize) -> H::Hash {
+        if let Some(hash) = self.levels[level].get(idx) {
+            return hash;
+        }
+        if level == 0 {
+            return H::hash_elements(&self.leaves.leaf(idx));
+        }
+        H::hash_two_to_one(self.node(level-1, 2*idx), self.node(level-1, 2*idx+1))
     }
 
     pub fn proof(&self, idx: usize) -> MerkleProof<F, H>{
-        let leaf_val = self.leaves[idx].clone();
+        let leaf_val = self.leaves.leaf(idx);
         // proof: Vec<F>, // [L1, L2, ...] one neighbour corresponding to each level // length will be depth
         let mut proof: Vec<H::Hash> = Vec::new();
         let mut curr_idx = idx;
-        let last_level = self.depth-self.merkle_cap_bits as u32;
+        let last_level = self.depth-self.merkle_cap_bits;
         for i in 0..last_level {
-            if curr_idx%2 == 0 {
+            if curr_idx.is_multiple_of(2) {
                 // we have l node
-                let neighbour = self.levels[i as usize][curr_idx+1];
+                let neighbour = self.node(i as usize, curr_idx+1);
                 proof.push(neighbour);
-                curr_idx = curr_idx/2;
+                curr_idx /= 2;
             } else {
                 // we have r node
-                let neighbour = self.levels[i as usize][curr_idx-1];
+                let neighbour = self.node(i as usize, curr_idx-1);
                 proof.push(neighbour);
                 curr_idx = (curr_idx-1)/2;
             }
@@ -119,9 +294,123 @@ impl<F: PrimeField, H: Hasher_<F>> MerkleTree<F, H> {
         }
 
     }
+
+    // Opens all leaves in `indices` at once, sharing siblings between the paths
+    pub fn multi_proof(&self, indices: &[usize]) -> MerkleMultiProof<F, H> {
+        let mut leaf_indices = indices.to_vec();
+        leaf_indices.sort_unstable();
+        leaf_indices.dedup();
+
+        let leaves = leaf_indices.iter().map(|idx| self.leaves.leaf(*idx)).collect();
+
+        let mut proof: Vec<H::Hash> = Vec::new();
+        let mut curr_indices = leaf_indices.clone();
+        let last_level = self.depth-self.merkle_cap_bits;
+        for i in 0..last_level as usize {
+            let mut j = 0;
+            while j < curr_indices.len() {
+                let idx = curr_indices[j];
+                if idx.is_multiple_of(2) && j+1 < curr_indices.len() && curr_indices[j+1] == idx+1 {
+                    // sibling is opened as well, verifier recomputes it
+                    j += 1;
+                } else {
+                    proof.push(self.node(i, idx ^ 1));
+                }
+                j += 1;
+            }
+            curr_indices = curr_indices.iter().map(|idx| idx/2).collect();
+            curr_indices.dedup();
+        }
+
+        MerkleMultiProof {
+            leaves,
+            leaf_indices,
+            proof,
+        }
+    }
     
 }
 
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for MerkleProof<F, H> {
+    fn serialize_with_mode<W: Write>(&self, mut writer: W, compress: Compress) -> Result<(), SerializationError> {
+        self.leaf.serialize_with_mode(&mut writer, compress)?;
+        self.leaf_idx.serialize_with_mode(&mut writer, compress)?;
+        self.proof.serialize_with_mode(&mut writer, compress)?;
+        self.root_cap.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.leaf.serialized_size(compress)
+            + self.leaf_idx.serialized_size(compress)
+            + self.proof.serialized_size(compress)
+            + self.root_cap.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> Valid for MerkleProof<F, H> {
+    fn check(&self) -> Result<(), SerializationError> {
+        // a path of usize::BITS or more siblings shifts every index down to zero
+        let cap_idx = u32::try_from(self.proof.len()).ok().and_then(|len| self.leaf_idx.checked_shr(len)).unwrap_or(0);
+        if !self.root_cap.len().is_power_of_two() || cap_idx >= self.root_cap.len() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalDeserialize for MerkleProof<F, H> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let proof = MerkleProof {
+            leaf: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            leaf_idx: usize::deserialize_with_mode(&mut reader, compress, validate)?,
+            proof: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            root_cap: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        if let Validate::Yes = validate {
+            proof.check()?;
+        }
+        Ok(proof)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for MerkleMultiProof<F, H> {
+    fn serialize_with_mode<W: Write>(&self, mut writer: W, compress: Compress) -> Result<(), SerializationError> {
+        self.leaves.serialize_with_mode(&mut writer, compress)?;
+        self.leaf_indices.serialize_with_mode(&mut writer, compress)?;
+        self.proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.leaves.serialized_size(compress)
+            + self.leaf_indices.serialized_size(compress)
+            + self.proof.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> Valid for MerkleMultiProof<F, H> {
+    fn check(&self) -> Result<(), SerializationError> {
+        // one leaf per index, indices strictly increasing
+        if self.leaves.len() != self.leaf_indices.len() || self.leaf_indices.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalDeserialize for MerkleMultiProof<F, H> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let proof = MerkleMultiProof {
+            leaves: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            leaf_indices: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            proof: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        if let Validate::Yes = validate {
+            proof.check()?;
+        }
+        Ok(proof)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,17 +423,207 @@ mod tests {
 
         let leaf: Vec<Fq> = (0..4).map(|i| Fq::from(i as u32)).collect();
 
-        let leaves: Vec<Vec<Fq>> = (0..num_leaves).map(|i| leaf.iter().map(|l| l.clone()*Fq::from(i)).collect::<Vec<Fq>>()).collect();
-
-        tree.insert(leaves);
+        let leaves: Vec<Vec<Fq>> = (0..num_leaves).map(|i| leaf.iter().map(|l| *l*Fq::from(i)).collect::<Vec<Fq>>()).collect();
 
-        let root = tree.compute_tree();
+        tree.insert(leaves).unwrap();
 
-        println!("root {:?}", root);
+        let root = tree.compute_tree().unwrap();
+        assert_eq!(root.len(), 4);
 
         let merkle_proof = tree.proof(1);
+        assert_eq!(merkle_proof.leaf, tree.leaf(1));
+        assert!(merkle_path_verify(&merkle_proof));
+
+        let mut bad_proof = merkle_proof.clone();
+        bad_proof.leaf[0] += Fq::from(1);
+        assert!(!merkle_path_verify(&bad_proof));
+    }
+
+    fn build_tree(num_leaves: usize, leaf_size: usize, merkle_cap_bits: u32) -> MerkleTree<Fq, Sha256_<Fq>> {
+        let mut tree = MerkleTree::<Fq, Sha256_<Fq>>::new(merkle_cap_bits);
+        let leaves: Vec<Vec<Fq>> = (0..num_leaves)
+            .map(|i| (0..leaf_size).map(|j| Fq::from((i * leaf_size + j) as u64 + 1)).collect())
+            .collect();
+        tree.insert(leaves).unwrap();
+        tree.compute_tree().unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_merkle_multi_proof() {
+        let tree = build_tree(16, 4, 2);
+        let root_cap = tree.root_cap.clone().unwrap();
+
+        // includes siblings (4, 5), a duplicate and leaves under the same cap node
+        let multi_proof = tree.multi_proof(&[5, 4, 0, 13, 5, 15]);
+        assert_eq!(multi_proof.leaf_indices, vec![0, 4, 5, 13, 15]);
+        assert_eq!(multi_proof.leaf(13), Some(&tree.leaf(13)));
+        assert_eq!(multi_proof.leaf(3), None);
+        assert!(verify_multi_proof(&root_cap, 4, &multi_proof));
+
+        // a single index is the same as a regular merkle path
+        let single = tree.multi_proof(&[9]);
+        assert_eq!(single.num_hashes(), tree.proof(9).proof.len());
+        assert!(verify_multi_proof(&root_cap, 4, &single));
+    }
+
+    #[test]
+    fn test_merkle_multi_proof_paths() {
+        let tree = build_tree(16, 4, 1);
+        let multi_proof = tree.multi_proof(&[0, 1, 6, 11, 12]);
+        for idx in [0, 1, 6, 11, 12] {
+            assert_eq!(multi_proof.path(idx, 4, 1), Some(tree.proof(idx).proof));
+        }
+        assert_eq!(multi_proof.path(2, 4, 1), None);
+    }
+
+    #[test]
+    fn test_merkle_multi_proof_tampered() {
+        let tree = build_tree(16, 4, 1);
+        let root_cap = tree.root_cap.clone().unwrap();
+        let multi_proof = tree.multi_proof(&[1, 6, 7, 12]);
+
+        let mut bad_leaf = multi_proof.clone();
+        bad_leaf.leaves[2][0] += Fq::from(1);
+        assert!(!verify_multi_proof(&root_cap, 4, &bad_leaf));
+
+        // the leaf keeps its sum but its values are moved around
+        let mut moved_value = multi_proof.clone();
+        moved_value.leaves[2][0] += Fq::from(1);
+        moved_value.leaves[2][1] -= Fq::from(1);
+        assert!(!verify_multi_proof(&root_cap, 4, &moved_value));
+        let mut swapped = multi_proof.clone();
+        swapped.leaves[2].swap(0, 1);
+        assert!(!verify_multi_proof(&root_cap, 4, &swapped));
+
+        let mut bad_index = multi_proof.clone();
+        bad_index.leaf_indices[0] = 0;
+        assert!(!verify_multi_proof(&root_cap, 4, &bad_index));
+
+        let mut bad_sibling = multi_proof.clone();
+        bad_sibling.proof[0] += Fq::from(1);
+        assert!(!verify_multi_proof(&root_cap, 4, &bad_sibling));
+
+        let mut extra_sibling = multi_proof.clone();
+        extra_sibling.proof.push(Fq::from(1));
+        assert!(!verify_multi_proof(&root_cap, 4, &extra_sibling));
+
+        let mut missing_sibling = multi_proof.clone();
+        missing_sibling.proof.pop();
+        assert!(!verify_multi_proof(&root_cap, 4, &missing_sibling));
+
+        assert!(!verify_multi_proof(&root_cap, 5, &multi_proof));
+        // depths whose leaf count does not fit in usize
+        assert!(!verify_multi_proof(&root_cap, 64, &multi_proof));
+        assert!(!verify_multi_proof(&root_cap, u32::MAX, &multi_proof));
+
+        // nothing opened, nothing to recompute the root from
+        let empty = tree.multi_proof(&[]);
+        assert!(empty.leaf_indices.is_empty() && empty.num_hashes() == 0);
+        assert!(!verify_multi_proof(&root_cap, 4, &empty));
+    }
+
+    #[test]
+    fn test_merkle_proof_serialization() {
+        let tree = build_tree(16, 4, 2);
+        let root_cap = tree.root_cap.clone().unwrap();
+
+        let proof = tree.proof(11);
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proof.compressed_size());
+        let decoded = MerkleProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded.leaf, proof.leaf);
+        assert_eq!(decoded.leaf_idx, proof.leaf_idx);
+        assert!(merkle_path_verify(&decoded));
+
+        let multi_proof = tree.multi_proof(&[2, 3, 8, 14]);
+        let mut bytes = Vec::new();
+        multi_proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = MerkleMultiProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded.leaf_indices, multi_proof.leaf_indices);
+        assert!(verify_multi_proof(&root_cap, 4, &decoded));
+
+        // truncated input
+        assert!(MerkleMultiProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..bytes.len()-1]).is_err());
+
+        // path longer than the bit width of the leaf index
+        let mut long_path = proof.clone();
+        long_path.proof = vec![long_path.proof[0]; usize::BITS as usize + 1];
+        let mut bytes = Vec::new();
+        long_path.serialize_compressed(&mut bytes).unwrap();
+        let decoded = MerkleProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(!merkle_path_verify(&decoded));
 
-        let verify = merkle_path_verify(&merkle_proof);
-        println!("verify : {:?}", verify);
+        // leaf index beyond the root cap
+        let mut bad_idx = proof.clone();
+        bad_idx.leaf_idx = 1 << (bad_idx.proof.len() + 2);
+        let mut bytes = Vec::new();
+        bad_idx.serialize_compressed(&mut bytes).unwrap();
+        assert!(MerkleProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..]).is_err());
+
+        // leaf indices out of order
+        let mut unsorted = multi_proof.clone();
+        unsorted.leaf_indices.swap(0, 1);
+        let mut bytes = Vec::new();
+        unsorted.serialize_compressed(&mut bytes).unwrap();
+        assert!(MerkleMultiProof::<Fq, Sha256_<Fq>>::deserialize_compressed(&bytes[..]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_merkle_proof_json() {
+        let tree = build_tree(16, 4, 2);
+        let root_cap = tree.root_cap.clone().unwrap();
+
+        let proof = tree.proof(6);
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: MerkleProof<Fq, Sha256_<Fq>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.leaf, proof.leaf);
+        assert!(merkle_path_verify(&decoded));
+
+        let multi_proof = tree.multi_proof(&[0, 7, 9]);
+        let json = serde_json::to_string(&multi_proof).unwrap();
+        let decoded: MerkleMultiProof<Fq, Sha256_<Fq>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.leaves, multi_proof.leaves);
+        assert!(verify_multi_proof(&root_cap, 4, &decoded));
+    }
+
+    #[test]
+    fn test_merkle_multi_proof_size() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let hash_size = std::mem::size_of::<<Sha256_<Fq> as Hasher_<Fq>>::Hash>();
+        let field_size = std::mem::size_of::<Fq>();
+
+        let depth = 12;
+        let leaf_size = 4;
+        let merkle_cap_bits = 2;
+        let tree = build_tree(1<<depth, leaf_size, merkle_cap_bits);
+        let root_cap = tree.root_cap.clone().unwrap();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for num_queries in [32, 64, 128] {
+            let queries: Vec<usize> = (0..num_queries).map(|_| rng.gen_range(0..1<<depth)).collect();
+
+            let mut distinct = queries.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            // one path per distinct query, each carrying its own copy of the root cap
+            let single_bytes: usize = distinct.iter().map(|q| {
+                let p = tree.proof(*q);
+                assert!(merkle_path_verify(&p));
+                (p.proof.len() + p.root_cap.len()) * hash_size + p.leaf.len() * field_size
+            }).sum();
+
+            let multi_proof = tree.multi_proof(&queries);
+            assert!(verify_multi_proof(&root_cap, depth, &multi_proof));
+            let multi_bytes = multi_proof.num_hashes() * hash_size
+                + multi_proof.leaves.len() * leaf_size * field_size
+                + multi_proof.leaf_indices.len() * std::mem::size_of::<usize>();
+
+            // sibling sharing saves at least a tenth of the bytes at these query counts
+            assert!(multi_bytes * 10 < single_bytes * 9);
+        }
     }
 }
\ No newline at end of file
@@ -1 +1,4 @@
-pub mod merkle;
\ No newline at end of file
+pub mod merkle;
+pub mod storage;
+pub mod kary;
+pub mod matrix;
@@ -0,0 +1,373 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use memmap2::Mmap;
+
+// Operations a tree's storage does not support, or which failed on it
+#[derive(Debug)]
+pub enum StorageError {
+    LeavesNotInMemory, // only memory backed leaves can be inserted into
+    Io(io::Error), // reading or writing out-of-core layers
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::LeavesNotInMemory => write!(f, "leaves can only be inserted in memory backed trees"),
+            StorageError::Io(e) => write!(f, "layer storage failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io(e) => Some(e),
+            StorageError::LeavesNotInMemory => None,
+        }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+// Leaves of a MerkleTree. Leaves past the end (up to the next power of two) read as a single zero.
+#[derive(Clone)]
+pub enum LeafStorage<F: PrimeField> {
+    Memory(Vec<Vec<F>>),
+    Mmap(MmapLeaves<F>),
+    // produced on demand, e.g. evaluations recomputed from the polynomial
+    Computed(ComputedLeaves<F>),
+}
+
+impl<F: PrimeField> LeafStorage<F> {
+    pub fn len(&self) -> usize {
+        match self {
+            LeafStorage::Memory(leaves) => leaves.len(),
+            LeafStorage::Mmap(leaves) => leaves.num_leaves,
+            LeafStorage::Computed(leaves) => leaves.num_leaves,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn leaf(&self, idx: usize) -> Vec<F> {
+        if idx >= self.len() {
+            return vec![F::ZERO];
+        }
+        match self {
+            LeafStorage::Memory(leaves) => leaves[idx].clone(),
+            LeafStorage::Mmap(leaves) => leaves.leaf(idx),
+            LeafStorage::Computed(leaves) => (leaves.f)(idx),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ComputedLeaves<F: PrimeField> {
+    num_leaves: usize,
+    f: Arc<dyn Fn(usize) -> Vec<F> + Send + Sync>,
+}
+
+impl<F: PrimeField> ComputedLeaves<F> {
+    pub fn new(num_leaves: usize, f: impl Fn(usize) -> Vec<F> + Send + Sync + 'static) -> Self {
+        ComputedLeaves { num_leaves, f: Arc::new(f) }
+    }
+}
+
+// A read-only mapping, kept alive with the file it maps. Files created for a tree's layers are removed once
+// the last clone of the mapping is dropped.
+pub(crate) struct MappedFile {
+    mmap: Mmap,
+    _file: File,
+    owned_path: Option<PathBuf>,
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.mmap
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        if let Some(path) = &self.owned_path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// Maps `file`, which is only written before being mapped and never afterwards: files the crate writes
+// are created fresh with create_new, so no other tree or mapping can truncate them.
+fn map_file(file: File, owned_path: Option<PathBuf>) -> io::Result<Arc<MappedFile>> {
+    // Safety: see above, the file is not truncated or written to while mapped
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(Arc::new(MappedFile { mmap, _file: file, owned_path }))
+}
+
+// Creates a file that did not exist before in `dir`, named after `prefix`
+fn create_unique(dir: &Path, prefix: &str) -> io::Result<(File, PathBuf)> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    loop {
+        let path = dir.join(format!("{}_{}_{}.bin", prefix, std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+        match OpenOptions::new().read(true).write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Fixed width leaves stored back to back in a file, elements uncompressed
+#[derive(Clone)]
+pub struct MmapLeaves<F: PrimeField> {
+    mmap: Arc<MappedFile>,
+    num_leaves: usize,
+    leaf_size: usize,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> MmapLeaves<F> {
+    // Writes `leaves` (each of `leaf_size` elements) to `path`, which must not exist yet, and maps it
+    pub fn create(path: &Path, leaves: impl IntoIterator<Item = Vec<F>>, leaf_size: usize) -> io::Result<Self> {
+        let mut writer = BufWriter::new(OpenOptions::new().read(true).write(true).create_new(true).open(path)?);
+        for leaf in leaves {
+            if leaf.len() != leaf_size {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "leaf size mismatch"));
+            }
+            leaf.iter().try_for_each(|e| e.serialize_uncompressed(&mut writer))
+                .map_err(io::Error::other)?;
+        }
+        let file = writer.into_inner()?;
+        file.sync_all()?;
+        Self::from_mapping(map_file(file, None)?, leaf_size)
+    }
+
+    // Maps the leaves written to `path` by `create`. The file must not be modified while mapped.
+    pub fn open(path: &Path, leaf_size: usize) -> io::Result<Self> {
+        Self::from_mapping(map_file(File::open(path)?, None)?, leaf_size)
+    }
+
+    fn from_mapping(mmap: Arc<MappedFile>, leaf_size: usize) -> io::Result<Self> {
+        let leaf_bytes = leaf_size * F::ZERO.uncompressed_size();
+        if leaf_bytes == 0 || !mmap.len().is_multiple_of(leaf_bytes) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "file is not a whole number of leaves"));
+        }
+        Ok(MmapLeaves { num_leaves: mmap.len() / leaf_bytes, mmap, leaf_size, _f: PhantomData })
+    }
+
+    fn leaf(&self, idx: usize) -> Vec<F> {
+        let elem_bytes = F::ZERO.uncompressed_size();
+        let start = idx * self.leaf_size * elem_bytes;
+        self.mmap[start..start + self.leaf_size * elem_bytes].chunks(elem_bytes)
+            .map(|bytes| F::deserialize_uncompressed_unchecked(bytes).expect("Corrupted leaf file"))
+            .collect()
+    }
+}
+
+// Where the hash layers below the cached upper layers of a tree are kept
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LowerLayers {
+    #[default]
+    Memory,
+    // one file per layer in this directory, created afresh by every compute_tree and removed with the last
+    // tree using it, so the directory can be shared between trees
+    Mmap(PathBuf),
+    // not stored, recomputed from the closest stored layer below (or the leaves) when needed
+    Recompute,
+}
+
+#[derive(Clone)]
+pub(crate) enum HashLayer<T> {
+    Memory(Vec<T>),
+    Mmap { mmap: Arc<MappedFile>, hash_bytes: usize, _t: PhantomData<T> },
+    Recompute,
+}
+
+impl<T: CanonicalDeserialize + Copy> HashLayer<T> {
+    // None if the layer is not stored
+    pub(crate) fn get(&self, idx: usize) -> Option<T> {
+        match self {
+            HashLayer::Memory(hashes) => Some(hashes[idx]),
+            HashLayer::Mmap { mmap, hash_bytes, .. } => Some(
+                T::deserialize_uncompressed_unchecked(&mmap[idx * hash_bytes..(idx + 1) * hash_bytes]).expect("Corrupted layer file")
+            ),
+            HashLayer::Recompute => None,
+        }
+    }
+}
+
+// Receives the hashes of one layer, in index order, while the tree is built
+pub(crate) enum LayerSink<T> {
+    Memory(Vec<T>),
+    File { writer: BufWriter<File>, path: PathBuf, hash_bytes: usize },
+    Skip,
+}
+
+impl<T: CanonicalSerialize + CanonicalDeserialize + Copy> LayerSink<T> {
+    pub(crate) fn new(storage: &LowerLayers, level: usize, cached: bool) -> io::Result<Self> {
+        Ok(match storage {
+            _ if cached => LayerSink::Memory(Vec::new()),
+            LowerLayers::Memory => LayerSink::Memory(Vec::new()),
+            LowerLayers::Mmap(dir) => {
+                let (file, path) = create_unique(dir, &format!("layer_{}", level))?;
+                LayerSink::File { writer: BufWriter::new(file), path, hash_bytes: 0 }
+            },
+            LowerLayers::Recompute => LayerSink::Skip,
+        })
+    }
+
+    pub(crate) fn push(&mut self, hash: T) -> io::Result<()> {
+        match self {
+            LayerSink::Memory(hashes) => hashes.push(hash),
+            LayerSink::File { writer, hash_bytes, .. } => {
+                *hash_bytes = hash.uncompressed_size();
+                hash.serialize_uncompressed(writer).map_err(io::Error::other)?;
+            },
+            LayerSink::Skip => {},
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> io::Result<HashLayer<T>> {
+        Ok(match self {
+            LayerSink::Memory(hashes) => HashLayer::Memory(hashes),
+            LayerSink::File { writer, path, hash_bytes } => {
+                let file = writer.into_inner()?;
+                file.sync_all()?;
+                HashLayer::Mmap { mmap: map_file(file, Some(path))?, hash_bytes, _t: PhantomData }
+            },
+            LayerSink::Skip => HashLayer::Recompute,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::fields::goldilocks_field::Fq;
+    use crate::hashing::hasher::Sha256_;
+    use crate::merkle_tree::merkle::{merkle_path_verify, verify_multi_proof, MerkleTree};
+    use super::{ComputedLeaves, LeafStorage, LowerLayers, MmapLeaves, StorageError};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fri-commitment-merkle-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn leaf(i: usize) -> Vec<Fq> {
+        (0..4u64).map(|j| Fq::from(i as u64 * 31 + j)).collect()
+    }
+
+    #[test]
+    fn test_out_of_core_trees() {
+        // 27 leaves, padded to 32
+        let num_leaves = 27;
+        let mut reference = MerkleTree::<Fq, Sha256_<Fq>>::new(1);
+        reference.insert((0..num_leaves).map(leaf).collect()).unwrap();
+        let root_cap = reference.compute_tree().unwrap();
+
+        let dir = temp_dir("storage");
+        let mmap_leaves = MmapLeaves::create(&dir.join("leaves.bin"), (0..num_leaves).map(leaf), 4).unwrap();
+        assert_eq!(MmapLeaves::<Fq>::open(&dir.join("leaves.bin"), 4).unwrap().leaf(26), leaf(26));
+        let sources = [
+            LeafStorage::Mmap(mmap_leaves),
+            LeafStorage::Computed(ComputedLeaves::new(num_leaves, leaf)),
+        ];
+        let layers = [LowerLayers::Memory, LowerLayers::Mmap(dir.join("layers")), LowerLayers::Recompute];
+        std::fs::create_dir_all(dir.join("layers")).unwrap();
+
+        for source in sources.iter() {
+            for lower in layers.iter() {
+                for cached_layers in [0, 2] {
+                    let mut tree = MerkleTree::<Fq, Sha256_<Fq>>::with_storage(1, source.clone(), lower.clone(), cached_layers);
+                    assert_eq!(tree.compute_tree().unwrap(), root_cap);
+                    for idx in [0, 13, 26, 31] {
+                        let proof = tree.proof(idx);
+                        assert_eq!(proof.leaf, reference.leaf(idx));
+                        assert!(merkle_path_verify(&proof));
+                    }
+                    let multi_proof = tree.multi_proof(&[3, 4, 17, 30]);
+                    assert_eq!(multi_proof.siblings(), reference.multi_proof(&[3, 4, 17, 30]).siblings());
+                    assert!(verify_multi_proof(&root_cap, 5, &multi_proof));
+                }
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_shared_layer_dir() {
+        let dir = temp_dir("shared");
+        let lower = LowerLayers::Mmap(dir.clone());
+        let source = |offset: usize| LeafStorage::Computed(ComputedLeaves::new(16, move |i| leaf(i + offset)));
+
+        // a clone keeps reading the layers it was built with while the tree and another tree rebuild theirs
+        let mut tree = MerkleTree::<Fq, Sha256_<Fq>>::with_storage(0, source(0), lower.clone(), 0);
+        let root_cap = tree.compute_tree().unwrap();
+        let clone = tree.clone();
+        let mut other = MerkleTree::<Fq, Sha256_<Fq>>::with_storage(0, source(100), lower.clone(), 0);
+        other.compute_tree().unwrap();
+        tree.leaves = source(100);
+        assert_eq!(tree.compute_tree().unwrap(), other.compute_tree().unwrap());
+        for idx in [0, 7, 15] {
+            let proof = clone.proof(idx);
+            assert_eq!(proof.leaf, leaf(idx));
+            assert!(merkle_path_verify(&proof));
+        }
+        assert!(verify_multi_proof(&root_cap, 4, &clone.multi_proof(&[1, 2, 9])));
+
+        // layer files go away with the last tree mapping them
+        drop((tree, clone, other));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_insert_requires_memory_leaves() {
+        let mut tree = MerkleTree::<Fq, Sha256_<Fq>>::with_storage(0, LeafStorage::Computed(ComputedLeaves::new(4, leaf)), LowerLayers::Memory, 0);
+        assert!(matches!(tree.insert(vec![leaf(4)]), Err(StorageError::LeavesNotInMemory)));
+        assert_eq!(tree.num_leaves(), 4);
+    }
+
+    #[test]
+    fn test_unwritable_layer_dir() {
+        let dir = temp_dir("unwritable");
+        // a regular file in place of the layer directory, no layer file can be created under it
+        let not_a_dir = dir.join("layers");
+        std::fs::write(&not_a_dir, []).unwrap();
+        let mut tree = MerkleTree::<Fq, Sha256_<Fq>>::with_storage(0, LeafStorage::Computed(ComputedLeaves::new(8, leaf)), LowerLayers::Mmap(not_a_dir), 0);
+        assert!(matches!(tree.compute_tree(), Err(StorageError::Io(_))));
+        assert!(tree.root_cap.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_leaves_invalid() {
+        let dir = temp_dir("invalid");
+        assert!(MmapLeaves::create(&dir.join("leaves.bin"), vec![vec![Fq::from(1u64)], vec![]], 1).is_err());
+        // existing files are never overwritten
+        std::fs::write(dir.join("odd.bin"), [0u8; 12]).unwrap();
+        assert!(MmapLeaves::create(&dir.join("odd.bin"), vec![vec![Fq::from(1u64)]], 1).is_err());
+        assert!(MmapLeaves::<Fq>::open(&dir.join("odd.bin"), 1).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
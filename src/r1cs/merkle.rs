@@ -0,0 +1,78 @@
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::hashing::hasher::{LEAF_TAG, NODE_TAG};
+use crate::hashing::poseidon::poseidon_config;
+
+// In-circuit PoseidonHasher
+pub fn poseidon_hash_var(cs: ConstraintSystemRef<Fr>, inputs: &[FpVar<Fr>]) -> Result<FpVar<Fr>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(cs, poseidon_config());
+    sponge.absorb(&inputs)?;
+    Ok(sponge.squeeze_field_elements(1)?.remove(0))
+}
+
+// Enforces that `leaf` is the leaf at `index_bits` (little endian, one bit per tree level) of a tree
+// committed to `root_cap`, given its authentication path up to the cap
+pub fn enforce_merkle_path(
+    cs: ConstraintSystemRef<Fr>,
+    leaf: &[FpVar<Fr>],
+    index_bits: &[Boolean<Fr>],
+    path: &[FpVar<Fr>],
+    root_cap: &[FpVar<Fr>],
+) -> Result<(), SynthesisError> {
+    assert_eq!(index_bits.len(), path.len() + root_cap.len().trailing_zeros() as usize);
+    // same leaf and node hashes as PoseidonHasher, each prefixed with its domain tag
+    let leaf_input: Vec<FpVar<Fr>> = std::iter::once(FpVar::constant(Fr::from(LEAF_TAG))).chain(leaf.iter().cloned()).collect();
+    let mut current = poseidon_hash_var(cs.clone(), &leaf_input)?;
+    let node_tag = FpVar::constant(Fr::from(NODE_TAG));
+    for (bit, sibling) in index_bits.iter().zip(path.iter()) {
+        let left = bit.select(sibling, &current)?;
+        let right = bit.select(&current, sibling)?;
+        current = poseidon_hash_var(cs.clone(), &[node_tag.clone(), left, right])?;
+    }
+    // the remaining bits select the cap node
+    let cap_bits: Vec<Boolean<Fr>> = index_bits[path.len()..].iter().rev().cloned().collect();
+    let root = FpVar::conditionally_select_power_of_two_vector(&cap_bits, root_cap)?;
+    current.enforce_equal(&root)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use crate::hashing::poseidon::PoseidonHasher;
+    use crate::merkle_tree::merkle::MerkleTree;
+    use super::enforce_merkle_path;
+
+    #[test]
+    fn test_merkle_path_gadget() {
+        let leaves: Vec<Vec<Fr>> = (0..16u64).map(|i| vec![Fr::from(i), Fr::from(i * i)]).collect();
+        let mut tree = MerkleTree::<Fr, PoseidonHasher>::new(1);
+        tree.insert(leaves.clone()).unwrap();
+        let root_cap = tree.compute_tree().unwrap();
+
+        for (idx, tampered, moved) in [(5, false, false), (12, false, false), (5, true, false), (5, false, true)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut leaf = leaves[idx].clone();
+            if tampered {
+                leaf[0] += Fr::from(1u64);
+            }
+            // same sum, different leaf
+            if moved {
+                leaf[0] += Fr::from(1u64);
+                leaf[1] -= Fr::from(1u64);
+            }
+            let leaf_var: Vec<FpVar<Fr>> = leaf.iter().map(|v| FpVar::new_witness(cs.clone(), || Ok(*v)).unwrap()).collect();
+            let bits: Vec<Boolean<Fr>> = (0..4).map(|i| Boolean::new_witness(cs.clone(), || Ok((idx >> i) & 1 == 1)).unwrap()).collect();
+            let path: Vec<FpVar<Fr>> = tree.multi_proof(&[idx]).path(idx, 4, 1).unwrap().iter().map(|v| FpVar::new_witness(cs.clone(), || Ok(*v)).unwrap()).collect();
+            assert_eq!(path.len(), 3);
+            let cap: Vec<FpVar<Fr>> = root_cap.iter().map(|v| FpVar::new_input(cs.clone(), || Ok(*v)).unwrap()).collect();
+            enforce_merkle_path(cs.clone(), &leaf_var, &bits, &path, &cap).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), !tampered && !moved);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod transcript;
+pub mod merkle;
+pub mod verifier;
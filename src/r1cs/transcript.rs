@@ -0,0 +1,100 @@
+use ark_bn254::Fr;
+use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_ff::FftField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::fiat_shamir::poseidon_transcript::domain_separator_elements;
+use crate::fri::types::FriConfig;
+use crate::hashing::poseidon::poseidon_config;
+
+// In-circuit counterpart of PoseidonTranscript, absorbing and squeezing the same elements
+#[derive(Clone)]
+pub struct PoseidonTranscriptVar {
+    sponge: PoseidonSpongeVar<Fr>,
+}
+
+impl PoseidonTranscriptVar {
+    pub fn new(cs: ConstraintSystemRef<Fr>, domain_separator: &[u8]) -> Result<Self, SynthesisError> {
+        let mut sponge = PoseidonSpongeVar::new(cs, poseidon_config());
+        let separator: Vec<FpVar<Fr>> = domain_separator_elements(domain_separator).into_iter().map(FpVar::constant).collect();
+        sponge.absorb(&separator)?;
+        Ok(PoseidonTranscriptVar { sponge })
+    }
+
+    pub fn observe_element(&mut self, elem: &FpVar<Fr>) -> Result<(), SynthesisError> {
+        self.sponge.absorb(elem)
+    }
+
+    pub fn observe_elements(&mut self, elems: &[FpVar<Fr>]) -> Result<(), SynthesisError> {
+        self.sponge.absorb(&elems)
+    }
+
+    pub fn observe_u64(&mut self, value: u64) -> Result<(), SynthesisError> {
+        self.sponge.absorb(&FpVar::constant(Fr::from(value)))
+    }
+
+    pub fn get_challenge(&mut self) -> Result<FpVar<Fr>, SynthesisError> {
+        Ok(self.sponge.squeeze_field_elements(1)?.remove(0))
+    }
+
+    // Low `num_bits` bits (little endian) of each of `n` squeezed elements, the in-circuit
+    // counterpart of get_challenge_indices for num_bits <= 32
+    pub fn get_challenge_index_bits(&mut self, n: usize, num_bits: usize) -> Result<Vec<Vec<Boolean<Fr>>>, SynthesisError> {
+        assert!(num_bits <= 32);
+        self.sponge.squeeze_field_elements(n)?.iter().map(|e| {
+            let mut bits = e.to_bits_le()?;
+            bits.truncate(num_bits);
+            Ok(bits)
+        }).collect()
+    }
+}
+
+// Mirrors observe_fri_statement, the statement is fixed by the circuit so nothing here costs constraints
+pub fn observe_fri_statement_var(transcript: &mut PoseidonTranscriptVar, fri_config: &FriConfig, degree: u32) -> Result<(), SynthesisError> {
+    transcript.observe_u64(fri_config.num_query as u64)?;
+    transcript.observe_u64(fri_config.blow_up_factor as u64)?;
+    transcript.observe_u64(fri_config.last_polynomial_degree as u64)?;
+    transcript.observe_u64(fri_config.merkle_cap_bits as u64)?;
+    transcript.observe_u64(fri_config.level_reductions_bits.len() as u64)?;
+    for bits in fri_config.level_reductions_bits.iter() {
+        transcript.observe_u64(*bits as u64)?;
+    }
+    transcript.observe_u64(fri_config.pow_bits as u64)?;
+    transcript.observe_u64(degree as u64)?;
+    transcript.observe_element(&FpVar::constant(Fr::GENERATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use crate::fiat_shamir::{fiat_shamir::TranscriptProtocol, poseidon_transcript::PoseidonTranscript};
+    use super::PoseidonTranscriptVar;
+
+    #[test]
+    fn test_transcript_var_matches_native() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let elements: Vec<Fr> = (0..5u64).map(|i| Fr::from(i * 7 + 3)).collect();
+
+        let mut native = PoseidonTranscript::new(b"test");
+        native.observe_elements(b"elems", &elements);
+        native.observe_u64(b"int", 9);
+        let challenge = native.get_challenge(b"challenge");
+        let indices = native.get_challenge_indices(b"indices", 3);
+
+        let mut transcript = PoseidonTranscriptVar::new(cs.clone(), b"test").unwrap();
+        let element_vars: Vec<FpVar<Fr>> = elements.iter().map(|e| FpVar::new_witness(cs.clone(), || Ok(*e)).unwrap()).collect();
+        transcript.observe_elements(&element_vars).unwrap();
+        transcript.observe_u64(9).unwrap();
+        assert_eq!(transcript.get_challenge().unwrap().value().unwrap(), challenge);
+        let bits = transcript.get_challenge_index_bits(3, 20).unwrap();
+        for (index, bits) in indices.iter().zip(bits.iter()) {
+            let value = bits.iter().enumerate().map(|(i, b)| (b.value().unwrap() as u32) << i).sum::<u32>();
+            assert_eq!(value, index % (1 << 20));
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
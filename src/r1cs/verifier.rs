@@ -0,0 +1,349 @@
+use ark_bn254::Fr;
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode};
+use tracing::debug;
+
+use crate::fiat_shamir::{fiat_shamir::TranscriptProtocol, poseidon_transcript::PoseidonTranscript};
+use crate::fri::types::{FRIProof, FriConfig};
+use crate::fri::utils::observe_fri_statement;
+use crate::hashing::poseidon::PoseidonHasher;
+
+use super::merkle::enforce_merkle_path;
+use super::transcript::{observe_fri_statement_var, PoseidonTranscriptVar};
+
+// Shape of one committed level, as seen by the circuit
+struct LevelShape {
+    domain_bits: usize,
+    leaf_bits: usize, // log2 of the number of leaves, i.e. the tree depth
+    reduction: usize,
+}
+
+fn level_shapes(fri_config: &FriConfig, degree: u32) -> Vec<LevelShape> {
+    let mut domain_bits = ((degree as usize + 1) * fri_config.blow_up_factor as usize).trailing_zeros() as usize;
+    fri_config.level_reductions_bits.iter().map(|bits| {
+        let shape = LevelShape { domain_bits, leaf_bits: domain_bits - *bits as usize, reduction: 1 << bits };
+        domain_bits -= *bits as usize;
+        shape
+    }).collect()
+}
+
+// Opened leaf and its authentication path up to the cap
+#[derive(Clone, Debug)]
+pub struct LevelOpening {
+    pub leaf: Vec<Fr>,
+    pub path: Vec<Fr>,
+}
+
+// A FRIProof rearranged for the circuit: since query positions are only known in-circuit, the batched
+// openings are split into one fixed size opening per query and level
+#[derive(Clone, Debug)]
+pub struct FRIProofWitness {
+    pub final_evaluations: Vec<Fr>,
+    pub level_roots: Vec<Vec<Fr>>,
+    pub pow_nonce: Fr,
+    pub query_openings: Vec<Vec<LevelOpening>>, // [query][level]
+}
+
+impl FRIProofWitness {
+    // Replays the transcript to find the queried leaves. Leaves missing from the proof are replaced by
+    // zeros, which fail the circuit instead of panicking here.
+    pub fn new(fri_config: &FriConfig, degree: u32, fri_proof: &FRIProof<Fr, PoseidonHasher>, domain_separator: &[u8]) -> Self {
+        let shapes = level_shapes(fri_config, degree);
+        let mut transcript = PoseidonTranscript::new(domain_separator);
+        observe_fri_statement::<Fr>(&mut transcript, fri_config, degree);
+        for root in fri_proof.level_roots.iter() {
+            transcript.observe_elements(b"merkle_root", root);
+            let _: Fr = transcript.get_challenge(b"alpha");
+        }
+        transcript.observe_elements(b"final evals", &fri_proof.final_evaluations);
+        if fri_config.pow_bits > 0 {
+            // a failing nonce fails the circuit
+            transcript.check_pow(fri_config.pow_bits, fri_proof.pow_nonce);
+        }
+        let queries = transcript.get_challenge_indices(b"challenge indices", fri_config.num_query as usize);
+
+        let original_domain = 1usize << shapes.first().map_or(0, |s| s.domain_bits);
+        let query_openings = queries.iter().map(|q_start| {
+            let q_init = (*q_start as usize) % (original_domain/2).max(1);
+            shapes.iter().enumerate().map(|(l, shape)| {
+                let leaf_idx = q_init % (1 << shape.leaf_bits);
+                let path_len = shape.leaf_bits - fri_config.merkle_cap_bits as usize;
+                let merkle_proof = fri_proof.query_eval_proofs.get(l).map(|p| &p.merkle_proof);
+                let leaf = merkle_proof.and_then(|p| p.leaf(leaf_idx)).filter(|leaf| leaf.len() == shape.reduction);
+                let path = merkle_proof.and_then(|p| p.path(leaf_idx, shape.leaf_bits as u32, fri_config.merkle_cap_bits));
+                LevelOpening {
+                    leaf: leaf.cloned().unwrap_or_else(|| vec![Fr::zero(); shape.reduction]),
+                    path: path.filter(|p| p.len() == path_len).unwrap_or_else(|| vec![Fr::zero(); path_len]),
+                }
+            }).collect()
+        }).collect();
+
+        FRIProofWitness {
+            final_evaluations: fri_proof.final_evaluations.clone(),
+            level_roots: fri_proof.level_roots.clone(),
+            pow_nonce: Fr::from(fri_proof.pow_nonce),
+            query_openings,
+        }
+    }
+}
+
+pub struct LevelOpeningVar {
+    pub leaf: Vec<FpVar<Fr>>,
+    pub path: Vec<FpVar<Fr>>,
+}
+
+pub struct FRIProofVar {
+    pub final_evaluations: Vec<FpVar<Fr>>,
+    pub level_roots: Vec<Vec<FpVar<Fr>>>,
+    pub pow_nonce: FpVar<Fr>,
+    pub query_openings: Vec<Vec<LevelOpeningVar>>,
+}
+
+impl FRIProofVar {
+    // Allocates a proof of the shape fixed by `fri_config` and `degree`. `witness` may be None when only
+    // generating constraints (setup mode). The roots, final evaluations and nonce are allocated with `mode`,
+    // the openings are always witnesses.
+    pub fn new_variable(cs: ConstraintSystemRef<Fr>, fri_config: &FriConfig, degree: u32, witness: Option<&FRIProofWitness>, mode: AllocationMode) -> Result<Self, SynthesisError> {
+        let shapes = level_shapes(fri_config, degree);
+        let cap_size = 1usize << fri_config.merkle_cap_bits;
+        let final_size = (fri_config.blow_up_factor * (fri_config.last_polynomial_degree + 1)) as usize;
+
+        let alloc = |value: Option<&Fr>, mode: AllocationMode| {
+            FpVar::new_variable(cs.clone(), || value.copied().ok_or(SynthesisError::AssignmentMissing), mode)
+        };
+        let final_evaluations = (0..final_size)
+            .map(|i| alloc(witness.and_then(|w| w.final_evaluations.get(i)), mode))
+            .collect::<Result<Vec<_>, _>>()?;
+        let level_roots = (0..shapes.len())
+            .map(|l| (0..cap_size).map(|i| alloc(witness.and_then(|w| w.level_roots.get(l)).and_then(|r| r.get(i)), mode)).collect())
+            .collect::<Result<Vec<_>, _>>()?;
+        let pow_nonce = alloc(witness.map(|w| &w.pow_nonce), mode)?;
+        let query_openings = (0..fri_config.num_query as usize).map(|q| {
+            shapes.iter().enumerate().map(|(l, shape)| {
+                let opening = witness.and_then(|w| w.query_openings.get(q)).and_then(|o| o.get(l));
+                let path_len = shape.leaf_bits - fri_config.merkle_cap_bits as usize;
+                Ok(LevelOpeningVar {
+                    leaf: (0..shape.reduction).map(|i| alloc(opening.and_then(|o| o.leaf.get(i)), AllocationMode::Witness)).collect::<Result<_, _>>()?,
+                    path: (0..path_len).map(|i| alloc(opening.and_then(|o| o.path.get(i)), AllocationMode::Witness)).collect::<Result<_, _>>()?,
+                })
+            }).collect()
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(FRIProofVar { final_evaluations, level_roots, pow_nonce, query_openings })
+    }
+}
+
+// In-circuit calcualate_next_level_value: evaluates at `rando` the interpolant of the points (xs, ys)
+pub fn calculate_next_level_value_var(xs: &[FpVar<Fr>], ys: &[FpVar<Fr>], rando: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+    assert_eq!(xs.len(), ys.len());
+    let c: FpVar<Fr> = xs.iter().fold(FpVar::one(), |acc, x| acc * (rando - x));
+    let mut next_level_val = FpVar::zero();
+    for (i, (xi, yi)) in xs.iter().zip(ys.iter()).enumerate() {
+        let denom = xs.iter().enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(rando - xi, |acc, (_, xj)| acc * (xi - xj));
+        next_level_val += (yi * &c).mul_by_inverse(&denom)?;
+    }
+    Ok(next_level_val)
+}
+
+// Enforces that `proof` passes verify_fri_proof_with_transcript on a PoseidonTranscript::new(domain_separator),
+// for proofs generated with the PoseidonHasher over BN254's scalar field
+pub fn enforce_fri_verification(cs: ConstraintSystemRef<Fr>, fri_config: &FriConfig, degree: u32, domain_separator: &[u8], proof: &FRIProofVar) -> Result<(), SynthesisError> {
+    // the shape of the circuit depends on the config, it must be valid
+    if fri_config.validate::<Fr>(degree).is_err() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let shapes = level_shapes(fri_config, degree);
+    let original_domain_bits = ((degree as usize + 1) * fri_config.blow_up_factor as usize).trailing_zeros() as usize;
+
+    let mut transcript = PoseidonTranscriptVar::new(cs.clone(), domain_separator)?;
+    observe_fri_statement_var(&mut transcript, fri_config, degree)?;
+    let mut verifier_randoms = Vec::with_capacity(shapes.len());
+    for root in proof.level_roots.iter() {
+        transcript.observe_elements(root)?;
+        verifier_randoms.push(transcript.get_challenge()?);
+    }
+    transcript.observe_elements(&proof.final_evaluations)?;
+    // check_pow: the nonce is absorbed and the low pow_bits bits of the next squeeze vanish
+    if fri_config.pow_bits > 0 {
+        transcript.observe_element(&proof.pow_nonce)?;
+        for bit in transcript.get_challenge_index_bits(1, fri_config.pow_bits as usize)?.remove(0) {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
+    } else {
+        proof.pow_nonce.enforce_equal(&FpVar::zero())?;
+    }
+    // queries are brought to the first half of the original domain, only their low bits matter
+    let query_bits = transcript.get_challenge_index_bits(fri_config.num_query as usize, original_domain_bits - 1)?;
+
+    // Final polynomial: coefficients above last_polynomial_degree of the interpolant over the final coset vanish,
+    // sum_j y_j w^(-jk) = 0, linear in the evaluations
+    let final_size = proof.final_evaluations.len();
+    let final_domain: GeneralEvaluationDomain<Fr> = GeneralEvaluationDomain::new(final_size).unwrap();
+    for k in (fri_config.last_polynomial_degree as usize + 1)..final_size {
+        let step = final_domain.group_gen_inv().pow([k as u64]);
+        let terms: Vec<FpVar<Fr>> = proof.final_evaluations.iter().enumerate()
+            .map(|(j, y)| y * step.pow([j as u64]))
+            .collect();
+        let coeff: FpVar<Fr> = terms.iter().sum();
+        coeff.enforce_equal(&FpVar::zero())?;
+    }
+
+    for (bits, openings) in query_bits.iter().zip(proof.query_openings.iter()) {
+        let mut next_level_value = FpVar::zero();
+        let mut offset = Fr::GENERATOR;
+        for (l, shape) in shapes.iter().enumerate() {
+            // position in the level's domain and its split into leaf index and position inside the leaf
+            let mut position: Vec<Boolean<Fr>> = bits.iter().take(shape.domain_bits).cloned().collect();
+            position.resize(shape.domain_bits, Boolean::FALSE);
+            let (leaf_bits, in_leaf_bits) = position.split_at(shape.leaf_bits);
+            let opening = &openings[l];
+
+            enforce_merkle_path(cs.clone(), &opening.leaf, leaf_bits, &opening.path, &proof.level_roots[l])?;
+
+            if l != 0 {
+                let in_leaf_be: Vec<Boolean<Fr>> = in_leaf_bits.iter().rev().cloned().collect();
+                FpVar::conditionally_select_power_of_two_vector(&in_leaf_be, &opening.leaf)?.enforce_equal(&next_level_value)?;
+            }
+
+            // leaf values sit at offset * w^(leaf index + i * num leaves)
+            let generator = GeneralEvaluationDomain::<Fr>::new(1 << shape.domain_bits).unwrap().group_gen();
+            let mut base = FpVar::constant(offset);
+            for (i, bit) in leaf_bits.iter().enumerate() {
+                let power = generator.pow([1u64 << i]);
+                base *= bit.select(&FpVar::constant(power), &FpVar::one())?;
+            }
+            let step = generator.pow([1u64 << shape.leaf_bits]);
+            let xs: Vec<FpVar<Fr>> = (0..shape.reduction).map(|i| &base * step.pow([i as u64])).collect();
+            next_level_value = calculate_next_level_value_var(&xs, &opening.leaf, &verifier_randoms[l])?;
+
+            offset = offset.pow([shape.reduction as u64]);
+        }
+        if !shapes.is_empty() {
+            let final_bits: Vec<Boolean<Fr>> = bits.iter().take(final_size.trailing_zeros() as usize).rev().cloned().collect();
+            FpVar::conditionally_select_power_of_two_vector(&final_bits, &proof.final_evaluations)?.enforce_equal(&next_level_value)?;
+        }
+    }
+    Ok(())
+}
+
+// Number of constraints of the verifier circuit for `fri_config` and `degree`
+pub fn fri_verifier_constraint_count(fri_config: &FriConfig, degree: u32) -> Result<usize, SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    let proof = FRIProofVar::new_variable(cs.clone(), fri_config, degree, None, AllocationMode::Witness)?;
+    enforce_fri_verification(cs.clone(), fri_config, degree, b"", &proof)?;
+    let num_constraints = cs.num_constraints();
+    debug!(num_constraints, num_witness = cs.num_witness_variables(), "fri verifier circuit");
+    Ok(num_constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_r1cs_std::alloc::AllocationMode;
+    use ark_relations::r1cs::ConstraintSystem;
+    use crate::fiat_shamir::poseidon_transcript::PoseidonTranscript;
+    use crate::fri::{prover::generate_fri_proof_with_transcript, types::{FRIProof, FriConfig}, verifier::verify_fri_proof_with_transcript};
+    use crate::fri::utils::DEFAULT_DOMAIN_SEPARATOR;
+    use crate::hashing::poseidon::PoseidonHasher;
+    use super::{enforce_fri_verification, fri_verifier_constraint_count, FRIProofVar, FRIProofWitness};
+
+    fn sample_proof() -> (FriConfig, FRIProof<Fr, PoseidonHasher>) {
+        let coeffs: Vec<Fr> = (0..16u64).map(|i| Fr::from(i * 3 + 1)).collect();
+        let poly: DensePolynomial<Fr> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 4, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 };
+        let fri_proof = generate_fri_proof_with_transcript::<Fr, PoseidonHasher>(poly, fri_config.clone(), &mut PoseidonTranscript::new(DEFAULT_DOMAIN_SEPARATOR));
+        (fri_config, fri_proof)
+    }
+
+    // Synthesises the verifier on `fri_proof`, returns whether the constraints are satisfied and their number
+    fn check_in_circuit(fri_config: &FriConfig, fri_proof: &FRIProof<Fr, PoseidonHasher>) -> (bool, usize) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let witness = FRIProofWitness::new(fri_config, 15, fri_proof, DEFAULT_DOMAIN_SEPARATOR);
+        let proof_var = FRIProofVar::new_variable(cs.clone(), fri_config, 15, Some(&witness), AllocationMode::Input).unwrap();
+        enforce_fri_verification(cs.clone(), fri_config, 15, DEFAULT_DOMAIN_SEPARATOR, &proof_var).unwrap();
+        (cs.is_satisfied().unwrap(), cs.num_constraints())
+    }
+
+    #[test]
+    fn test_fri_gadget_accepts_valid_proof() {
+        let (fri_config, fri_proof) = sample_proof();
+        assert!(verify_fri_proof_with_transcript(fri_config.clone(), 15, fri_proof.clone(), &mut PoseidonTranscript::new(DEFAULT_DOMAIN_SEPARATOR)));
+
+        let (satisfied, num_constraints) = check_in_circuit(&fri_config, &fri_proof);
+        assert!(satisfied);
+        // the circuit shape only depends on the config and degree
+        assert_eq!(fri_verifier_constraint_count(&fri_config, 15).unwrap(), num_constraints);
+    }
+
+    #[test]
+    fn test_fri_gadget_rejects_tampered_proof() {
+        let (fri_config, fri_proof) = sample_proof();
+
+        let mut tampered = fri_proof.clone();
+        tampered.final_evaluations[0] += Fr::from(1u64);
+        assert!(!check_in_circuit(&fri_config, &tampered).0);
+
+        let mut tampered = fri_proof.clone();
+        tampered.level_roots[1][0] += Fr::from(1u64);
+        assert!(!check_in_circuit(&fri_config, &tampered).0);
+
+        for level in 0..2 {
+            let mut tampered = fri_proof.clone();
+            tampered.query_eval_proofs[level].merkle_proof.leaves[0][0] += Fr::from(1u64);
+            assert!(!check_in_circuit(&fri_config, &tampered).0);
+        }
+
+        // proof for another statement
+        let mut other_config = fri_config.clone();
+        other_config.num_query = 5;
+        let other = generate_fri_proof_with_transcript::<Fr, PoseidonHasher>(
+            DenseUVPolynomial::from_coefficients_vec((0..16u64).map(Fr::from).collect()), other_config, &mut PoseidonTranscript::new(DEFAULT_DOMAIN_SEPARATOR));
+        assert!(!check_in_circuit(&fri_config, &other).0);
+    }
+
+    #[test]
+    fn test_fri_gadget_grinding() {
+        let (mut fri_config, _) = sample_proof();
+        fri_config.pow_bits = 6;
+        let poly = DenseUVPolynomial::from_coefficients_vec((0..16u64).map(|i| Fr::from(i * 3 + 1)).collect());
+        let fri_proof = generate_fri_proof_with_transcript::<Fr, PoseidonHasher>(poly, fri_config.clone(), &mut PoseidonTranscript::new(DEFAULT_DOMAIN_SEPARATOR));
+        assert!(check_in_circuit(&fri_config, &fri_proof).0);
+
+        // the first nonce the native verifier rejects
+        let verifies = |pow_nonce| {
+            let proof = FRIProof { pow_nonce, ..fri_proof.clone() };
+            verify_fri_proof_with_transcript(fri_config.clone(), 15, proof, &mut PoseidonTranscript::new(DEFAULT_DOMAIN_SEPARATOR))
+        };
+        let bad_nonce = (fri_proof.pow_nonce + 1..).find(|n| !verifies(*n)).unwrap();
+        assert!(!check_in_circuit(&fri_config, &FRIProof { pow_nonce: bad_nonce, ..fri_proof.clone() }).0);
+
+        // without grinding the nonce must be 0
+        let (fri_config, fri_proof) = sample_proof();
+        assert!(!check_in_circuit(&fri_config, &FRIProof { pow_nonce: 1, ..fri_proof }).0);
+    }
+
+    #[test]
+    fn test_fri_gadget_constraint_count() {
+        let (fri_config, _) = sample_proof();
+        let count = fri_verifier_constraint_count(&fri_config, 15).unwrap();
+        // each pair of queries costs the same (the sponge squeezes two indices per permutation)
+        let with_queries = |num_query: u32| {
+            let mut config = fri_config.clone();
+            config.num_query = num_query;
+            fri_verifier_constraint_count(&config, 15).unwrap()
+        };
+        let per_pair = with_queries(6) - count;
+        assert!(per_pair > 0);
+        assert_eq!(with_queries(8), count + 2 * per_pair);
+
+        let mut invalid = fri_config;
+        invalid.num_query = 0;
+        assert!(fri_verifier_constraint_count(&invalid, 15).is_err());
+    }
+}
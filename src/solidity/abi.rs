@@ -0,0 +1,147 @@
+use ark_ff::PrimeField;
+use sha3::{Digest, Keccak256};
+
+use crate::fiat_shamir::keccak_transcript::field_to_word;
+use crate::fri::types::FRIProof;
+use crate::hashing::hasher::Keccak256_;
+
+pub const VERIFY_PROOF_SIGNATURE: &str = "verifyProof((uint256[],bytes32[],uint256[],uint256[],bytes32[],uint256[],uint256))";
+
+pub fn verify_proof_selector() -> [u8; 4] {
+    let hash = Keccak256::digest(VERIFY_PROOF_SIGNATURE.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Flattens the proof into the arrays of the generated contract's Proof struct:
+// final evaluations, level roots, leaf indices, leaf values, siblings and (leaves, siblings) counts per level.
+// The proof of work nonce follows them in the struct.
+pub fn flatten_proof<F: PrimeField>(proof: &FRIProof<F, Keccak256_<F>>) -> Vec<Vec<[u8; 32]>> {
+    let mut leaf_indices = Vec::new();
+    let mut leaf_values = Vec::new();
+    let mut siblings = Vec::new();
+    let mut counts = Vec::new();
+    for query_proof in proof.query_eval_proofs.iter() {
+        let merkle_proof = &query_proof.merkle_proof;
+        leaf_indices.extend(merkle_proof.leaf_indices.iter().map(|i| u64_to_word(*i as u64)));
+        leaf_values.extend(merkle_proof.leaves.iter().flatten().map(field_to_word));
+        siblings.extend_from_slice(merkle_proof.siblings());
+        counts.push(u64_to_word(merkle_proof.leaf_indices.len() as u64));
+        counts.push(u64_to_word(merkle_proof.num_hashes() as u64));
+    }
+    vec![
+        proof.final_evaluations.iter().map(field_to_word).collect(),
+        proof.level_roots.iter().flatten().copied().collect(),
+        leaf_indices,
+        leaf_values,
+        siblings,
+        counts,
+    ]
+}
+
+// ABI encoding of the proof as the single (tuple) argument of verifyProof
+pub fn encode_proof<F: PrimeField>(proof: &FRIProof<F, Keccak256_<F>>) -> Vec<u8> {
+    let arrays = flatten_proof(proof);
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    // offset of the tuple, then the offset of each array relative to the start of the tuple, and the nonce
+    let mut offset = 32 * (arrays.len() + 1);
+    for array in arrays.iter() {
+        head.extend_from_slice(&u64_to_word(offset as u64));
+        tail.extend_from_slice(&u64_to_word(array.len() as u64));
+        for word in array.iter() {
+            tail.extend_from_slice(word);
+        }
+        offset += 32 * (array.len() + 1);
+    }
+    head.extend_from_slice(&u64_to_word(proof.pow_nonce));
+    let mut encoded = u64_to_word(32).to_vec();
+    encoded.extend(head);
+    encoded.extend(tail);
+    encoded
+}
+
+// Calldata of a verifyProof call
+pub fn encode_verify_proof_call<F: PrimeField>(proof: &FRIProof<F, Keccak256_<F>>) -> Vec<u8> {
+    let mut calldata = verify_proof_selector().to_vec();
+    calldata.extend(encode_proof(proof));
+    calldata
+}
+
+fn u64_to_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use crate::fiat_shamir::keccak_transcript::{field_to_word, KeccakTranscript};
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::prover::generate_fri_proof_with_transcript;
+    use crate::fri::types::{FRIProof, FriConfig};
+    use crate::fri::utils::DEFAULT_DOMAIN_SEPARATOR;
+    use crate::fri::verifier::verify_fri_proof_with_transcript;
+    use crate::hashing::hasher::Keccak256_;
+    use super::{encode_verify_proof_call, flatten_proof, verify_proof_selector};
+
+    fn sample_proof() -> (FriConfig, FRIProof<Fq, Keccak256_<Fq>>) {
+        let coeffs: Vec<Fq> = (0..16).map(|i| Fq::from(i * 3 + 1)).collect();
+        let poly: DensePolynomial<Fq> = DenseUVPolynomial::from_coefficients_vec(coeffs);
+        let fri_config = FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 };
+        let mut transcript = KeccakTranscript::new(DEFAULT_DOMAIN_SEPARATOR);
+        let fri_proof = generate_fri_proof_with_transcript::<Fq, Keccak256_<Fq>>(poly, fri_config.clone(), &mut transcript);
+        (fri_config, fri_proof)
+    }
+
+    fn word(bytes: &[u8], i: usize) -> usize {
+        let w = &bytes[i*32..(i+1)*32];
+        assert!(w[..24].iter().all(|b| *b == 0));
+        u64::from_be_bytes(w[24..].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn test_keccak_proof_verifies() {
+        let (fri_config, fri_proof) = sample_proof();
+        assert!(verify_fri_proof_with_transcript(fri_config.clone(), 15, fri_proof.clone(), &mut KeccakTranscript::new(DEFAULT_DOMAIN_SEPARATOR)));
+        assert!(!verify_fri_proof_with_transcript(fri_config, 15, fri_proof, &mut KeccakTranscript::new(b"other")));
+    }
+
+    #[test]
+    fn test_abi_encoding() {
+        let (fri_config, fri_proof) = sample_proof();
+        let calldata = encode_verify_proof_call(&fri_proof);
+        assert_eq!(calldata[..4], verify_proof_selector());
+
+        let args = &calldata[4..];
+        assert_eq!(args.len() % 32, 0);
+        // single dynamic tuple argument
+        assert_eq!(word(args, 0), 32);
+        let tuple = &args[32..];
+        let arrays = flatten_proof(&fri_proof);
+        for (i, array) in arrays.iter().enumerate() {
+            let offset = word(tuple, i);
+            assert_eq!(offset % 32, 0);
+            let start = offset/32;
+            assert_eq!(word(tuple, start), array.len());
+            for (j, w) in array.iter().enumerate() {
+                assert_eq!(&tuple[(start+1+j)*32..(start+2+j)*32], w);
+            }
+        }
+        assert_eq!(word(tuple, arrays.len()), fri_proof.pow_nonce as usize);
+        let last = word(tuple, arrays.len()-1)/32;
+        assert_eq!(tuple.len(), (last + 1 + arrays[arrays.len()-1].len()) * 32);
+
+        // shapes expected by the contract
+        let num_levels = fri_config.level_reductions_bits.len();
+        assert_eq!(arrays[0].len(), 8);
+        assert_eq!(arrays[0][0], field_to_word(&fri_proof.final_evaluations[0]));
+        assert_eq!(arrays[1].len(), num_levels * 2);
+        assert_eq!(arrays[5].len(), num_levels * 2);
+        let leaves: usize = arrays[5].iter().step_by(2).map(|w| word(w, 0)).sum();
+        let siblings: usize = arrays[5].iter().skip(1).step_by(2).map(|w| word(w, 0)).sum();
+        assert_eq!(arrays[2].len(), leaves);
+        assert_eq!(arrays[3].len(), fri_proof.query_eval_proofs.iter().map(|p| p.merkle_proof.leaves.iter().map(|l| l.len()).sum::<usize>()).sum::<usize>());
+        assert_eq!(arrays[4].len(), siblings);
+    }
+}
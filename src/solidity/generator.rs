@@ -0,0 +1,389 @@
+use std::fmt::Write;
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+
+use crate::fiat_shamir::keccak_transcript::KeccakTranscript;
+use crate::fri::types::FriConfig;
+use crate::fri::utils::{observe_fri_statement, FriConfigError};
+use crate::hashing::hasher::{LEAF_TAG, NODE_TAG};
+
+// Generates a self-contained Solidity contract verifying FRIProofs for `fri_config` and `degree`,
+// produced with the Keccak256_ hasher on a KeccakTranscript::new(domain_separator).
+// The statement (config, degree, coset offset) is fixed at generation time, the contract replays the
+// transcript from there. Proofs are passed in the flattened layout of super::abi::encode_proof.
+pub fn generate_verifier_contract<F: PrimeField>(contract_name: &str, fri_config: &FriConfig, degree: u32, domain_separator: &[u8]) -> Result<String, FriConfigError> {
+    fri_config.validate::<F>(degree)?;
+
+    let mut transcript = KeccakTranscript::new(domain_separator);
+    observe_fri_statement::<F>(&mut transcript, fri_config, degree);
+
+    let original_domain_size = fri_config.blow_up_factor as usize * (degree as usize + 1);
+    let final_domain_size = fri_config.blow_up_factor as usize * (fri_config.last_polynomial_degree as usize + 1);
+    let final_domain: GeneralEvaluationDomain<F> = GeneralEvaluationDomain::new(final_domain_size).unwrap();
+
+    let mut out = String::new();
+    writeln!(out, "// SPDX-License-Identifier: MIT").unwrap();
+    writeln!(out, "// Generated by fri-commitment, do not edit.").unwrap();
+    writeln!(out, "// degree: {}, num_query: {}, blow_up_factor: {}, last_polynomial_degree: {}, merkle_cap_bits: {}, level_reductions_bits: {:?}, pow_bits: {}",
+        degree, fri_config.num_query, fri_config.blow_up_factor, fri_config.last_polynomial_degree, fri_config.merkle_cap_bits, fri_config.level_reductions_bits, fri_config.pow_bits).unwrap();
+    writeln!(out, "pragma solidity ^0.8.20;").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "contract {} {{", contract_name).unwrap();
+    writeln!(out, "    uint256 internal constant P = {};", F::MODULUS).unwrap();
+    writeln!(out, "    uint256 internal constant NUM_QUERY = {};", fri_config.num_query).unwrap();
+    writeln!(out, "    uint256 internal constant NUM_LEVELS = {};", fri_config.level_reductions_bits.len()).unwrap();
+    writeln!(out, "    uint256 internal constant CAP_BITS = {};", fri_config.merkle_cap_bits).unwrap();
+    writeln!(out, "    uint256 internal constant CAP_SIZE = {};", 1u64 << fri_config.merkle_cap_bits).unwrap();
+    writeln!(out, "    uint256 internal constant ORIGINAL_DOMAIN_SIZE = {};", original_domain_size).unwrap();
+    writeln!(out, "    uint256 internal constant FINAL_DOMAIN_SIZE = {};", final_domain_size).unwrap();
+    writeln!(out, "    uint256 internal constant LAST_POLYNOMIAL_DEGREE = {};", fri_config.last_polynomial_degree).unwrap();
+    writeln!(out, "    uint256 internal constant FINAL_GENERATOR_INV = {};", final_domain.group_gen_inv().into_bigint()).unwrap();
+    writeln!(out, "    uint256 internal constant POW_BITS = {};", fri_config.pow_bits).unwrap();
+    writeln!(out, "    bytes1 internal constant LEAF_TAG = 0x{:02x};", LEAF_TAG).unwrap();
+    writeln!(out, "    bytes1 internal constant NODE_TAG = 0x{:02x};", NODE_TAG).unwrap();
+    writeln!(out, "    // transcript state after the domain separator and the statement").unwrap();
+    writeln!(out, "    bytes32 internal constant INITIAL_STATE = 0x{};", to_hex(&transcript.state())).unwrap();
+    out.push_str(CONTRACT_TYPES);
+
+    // Evaluation domain of each committed level: offset * generator^i, leaves group reductionRoot^j multiples
+    writeln!(out, "    function levels() internal pure returns (Level[] memory ls) {{").unwrap();
+    writeln!(out, "        ls = new Level[](NUM_LEVELS);").unwrap();
+    let mut domain_size = original_domain_size;
+    let mut offset = F::GENERATOR;
+    for (l, bits) in fri_config.level_reductions_bits.iter().enumerate() {
+        let reduction = 1usize << bits;
+        let num_leaves = domain_size/reduction;
+        let generator = GeneralEvaluationDomain::<F>::new(domain_size).unwrap().group_gen();
+        writeln!(out, "        ls[{}] = Level({{domainSize: {}, numLeaves: {}, reduction: {}, depth: {}, offset: {}, generator: {}, reductionRoot: {}}});",
+            l, domain_size, num_leaves, reduction, num_leaves.trailing_zeros(), offset.into_bigint(), generator.into_bigint(),
+            generator.pow([num_leaves as u64]).into_bigint()).unwrap();
+        domain_size = num_leaves;
+        offset = offset.pow([reduction as u64]);
+    }
+    writeln!(out, "    }}").unwrap();
+    out.push_str(CONTRACT_BODY);
+    writeln!(out, "}}").unwrap();
+    Ok(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const CONTRACT_TYPES: &str = r#"
+    struct Proof {
+        uint256[] finalEvaluations;
+        bytes32[] levelRoots; // CAP_SIZE roots per level
+        uint256[] leafIndices; // opened leaves of every level, sorted within a level
+        uint256[] leafValues; // reduction values per opened leaf
+        bytes32[] siblings; // multi proof siblings of every level
+        uint256[] counts; // (opened leaves, siblings) per level
+        uint256 powNonce; // 0 without grinding
+    }
+
+    struct Level {
+        uint256 domainSize;
+        uint256 numLeaves;
+        uint256 reduction;
+        uint256 depth;
+        uint256 offset;
+        uint256 generator;
+        uint256 reductionRoot;
+    }
+
+    // Position of a level's opening in the flattened proof arrays
+    struct Opening {
+        uint256 leafStart;
+        uint256 numLeaves;
+        uint256 valueStart;
+        uint256 siblingStart;
+        uint256 numSiblings;
+        uint256 rootStart;
+    }
+
+    uint256 internal constant NOT_FOUND = type(uint256).max;
+
+"#;
+
+const CONTRACT_BODY: &str = r#"
+    function verifyProof(Proof calldata proof) external pure returns (bool) {
+        if (proof.finalEvaluations.length != FINAL_DOMAIN_SIZE
+            || proof.levelRoots.length != NUM_LEVELS * CAP_SIZE
+            || proof.counts.length != 2 * NUM_LEVELS
+            || proof.powNonce > type(uint64).max) {
+            return false;
+        }
+        if (!checkCanonical(proof.finalEvaluations) || !checkCanonical(proof.leafValues)) {
+            return false;
+        }
+        Level[] memory ls = levels();
+        (bool ok, Opening[] memory os) = openings(proof, ls);
+        if (!ok || !checkFinalDegree(proof.finalEvaluations)) {
+            return false;
+        }
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            if (!verifyOpening(proof, ls[l], os[l])) {
+                return false;
+            }
+        }
+        (bool powOk, uint256[] memory alphas, uint256[] memory queries) = replayTranscript(proof);
+        if (!powOk) {
+            return false;
+        }
+        for (uint256 i = 0; i < NUM_QUERY; i++) {
+            if (!verifyQuery(proof, ls, os, alphas, queries[i])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function checkCanonical(uint256[] calldata values) internal pure returns (bool) {
+        for (uint256 i = 0; i < values.length; i++) {
+            if (values[i] >= P) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // Splits the flattened arrays into the openings of each level
+    function openings(Proof calldata proof, Level[] memory ls) internal pure returns (bool ok, Opening[] memory os) {
+        os = new Opening[](NUM_LEVELS);
+        uint256 leafStart = 0;
+        uint256 valueStart = 0;
+        uint256 siblingStart = 0;
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            uint256 numLeaves = proof.counts[2 * l];
+            uint256 numSiblings = proof.counts[2 * l + 1];
+            if (numLeaves > proof.leafIndices.length || numSiblings > proof.siblings.length) {
+                return (false, os);
+            }
+            os[l] = Opening(leafStart, numLeaves, valueStart, siblingStart, numSiblings, l * CAP_SIZE);
+            leafStart += numLeaves;
+            valueStart += numLeaves * ls[l].reduction;
+            siblingStart += numSiblings;
+        }
+        ok = leafStart == proof.leafIndices.length
+            && valueStart == proof.leafValues.length
+            && siblingStart == proof.siblings.length;
+    }
+
+    // Same messages and labels as KeccakTranscript in the prover, powOk is false if the nonce fails the
+    // proof of work
+    function replayTranscript(Proof calldata proof) internal pure returns (bool powOk, uint256[] memory alphas, uint256[] memory queries) {
+        bytes32 state = INITIAL_STATE;
+        alphas = new uint256[](NUM_LEVELS);
+        uint256[] memory cap = new uint256[](CAP_SIZE);
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            for (uint256 i = 0; i < CAP_SIZE; i++) {
+                cap[i] = uint256(proof.levelRoots[l * CAP_SIZE + i]) % P;
+            }
+            state = keccak256(abi.encodePacked(state, "merkle_root", cap));
+            state = keccak256(abi.encodePacked(state, "alpha"));
+            alphas[l] = uint256(state) % P;
+        }
+        uint256[] memory finalEvaluations = proof.finalEvaluations;
+        state = keccak256(abi.encodePacked(state, "final evals", finalEvaluations));
+        if (POW_BITS > 0) {
+            state = keccak256(abi.encodePacked(state, "pow nonce", proof.powNonce));
+            state = keccak256(abi.encodePacked(state, "pow"));
+            powOk = uint256(uint32(uint256(state))) % (1 << POW_BITS) == 0;
+        } else {
+            powOk = proof.powNonce == 0;
+        }
+        queries = new uint256[](NUM_QUERY);
+        for (uint256 i = 0; i < NUM_QUERY; i++) {
+            state = keccak256(abi.encodePacked(state, "challenge indices"));
+            queries[i] = uint256(uint32(uint256(state)));
+        }
+    }
+
+    // The interpolant of the final evaluations over the coset has no coefficient above LAST_POLYNOMIAL_DEGREE,
+    // i.e. sum_j evals[j] * generator^(-jk) vanishes for every larger k
+    function checkFinalDegree(uint256[] calldata evals) internal pure returns (bool) {
+        for (uint256 k = LAST_POLYNOMIAL_DEGREE + 1; k < FINAL_DOMAIN_SIZE; k++) {
+            uint256 step = expmod(FINAL_GENERATOR_INV, k);
+            uint256 x = 1;
+            uint256 acc = 0;
+            for (uint256 j = 0; j < FINAL_DOMAIN_SIZE; j++) {
+                acc = addmod(acc, mulmod(evals[j], x, P), P);
+                x = mulmod(x, step, P);
+            }
+            if (acc != 0) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // Mirrors Keccak256_::hash_elements, every value of the leaf is hashed as its own word
+    function hashLeaf(uint256[] calldata values, uint256 start, uint256 len) internal pure returns (bytes32) {
+        uint256[] memory leaf = new uint256[](len);
+        for (uint256 j = 0; j < len; j++) {
+            leaf[j] = values[start + j];
+        }
+        return keccak256(abi.encodePacked(LEAF_TAG, leaf));
+    }
+
+    function hashNode(bytes32 left, bytes32 right) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(NODE_TAG, left, right));
+    }
+
+    // Batched Merkle opening of one level against its root cap, mirrors verify_multi_proof
+    function verifyOpening(Proof calldata proof, Level memory lv, Opening memory op) internal pure returns (bool) {
+        uint256 n = op.numLeaves;
+        uint256[] memory idx = new uint256[](n);
+        bytes32[] memory hashes = new bytes32[](n);
+        for (uint256 i = 0; i < n; i++) {
+            idx[i] = proof.leafIndices[op.leafStart + i];
+            if (idx[i] >= lv.numLeaves || (i > 0 && idx[i] <= idx[i - 1])) {
+                return false;
+            }
+            hashes[i] = hashLeaf(proof.leafValues, op.valueStart + i * lv.reduction, lv.reduction);
+        }
+        uint256 s = op.siblingStart;
+        for (uint256 d = CAP_BITS; d < lv.depth; d++) {
+            uint256 m = 0;
+            for (uint256 i = 0; i < n; i++) {
+                if (idx[i] % 2 == 0 && i + 1 < n && idx[i + 1] == idx[i] + 1) {
+                    // both children opened
+                    hashes[m] = hashNode(hashes[i], hashes[i + 1]);
+                    i++;
+                } else {
+                    if (s == op.siblingStart + op.numSiblings) {
+                        return false;
+                    }
+                    hashes[m] = idx[i] % 2 == 0
+                        ? hashNode(hashes[i], proof.siblings[s])
+                        : hashNode(proof.siblings[s], hashes[i]);
+                    s++;
+                }
+                idx[m] = idx[i] / 2;
+                m++;
+            }
+            n = m;
+        }
+        if (s != op.siblingStart + op.numSiblings) {
+            return false;
+        }
+        for (uint256 i = 0; i < n; i++) {
+            if (hashes[i] != proof.levelRoots[op.rootStart + idx[i]]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function findLeaf(Proof calldata proof, Opening memory op, uint256 leafIndex, uint256 reduction) internal pure returns (uint256) {
+        for (uint256 i = 0; i < op.numLeaves; i++) {
+            if (proof.leafIndices[op.leafStart + i] == leafIndex) {
+                return op.valueStart + i * reduction;
+            }
+        }
+        return NOT_FOUND;
+    }
+
+    // Folds a query through every level, checking consistency with the previous level and the final evaluations
+    function verifyQuery(Proof calldata proof, Level[] memory ls, Opening[] memory os, uint256[] memory alphas, uint256 query) internal pure returns (bool) {
+        uint256 q = query % (ORIGINAL_DOMAIN_SIZE / 2);
+        uint256 value = 0;
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            uint256 pos = q % ls[l].domainSize;
+            uint256 start = findLeaf(proof, os[l], pos % ls[l].numLeaves, ls[l].reduction);
+            if (start == NOT_FOUND) {
+                return false;
+            }
+            if (l != 0 && proof.leafValues[start + pos / ls[l].numLeaves] != value) {
+                return false;
+            }
+            uint256 base = mulmod(ls[l].offset, expmod(ls[l].generator, pos % ls[l].numLeaves), P);
+            value = fold(proof, start, ls[l], base, alphas[l]);
+        }
+        return NUM_LEVELS == 0 || proof.finalEvaluations[query % FINAL_DOMAIN_SIZE] == value;
+    }
+
+    // Evaluates at alpha the interpolant of a leaf's values over base * reductionRoot^i
+    function fold(Proof calldata proof, uint256 start, Level memory lv, uint256 base, uint256 alpha) internal pure returns (uint256) {
+        uint256[] memory xs = new uint256[](lv.reduction);
+        xs[0] = base;
+        uint256 c = addmod(alpha, P - base, P);
+        for (uint256 i = 1; i < lv.reduction; i++) {
+            xs[i] = mulmod(xs[i - 1], lv.reductionRoot, P);
+            c = mulmod(c, addmod(alpha, P - xs[i], P), P);
+        }
+        uint256 num = 0;
+        uint256 den = 1;
+        for (uint256 i = 0; i < lv.reduction; i++) {
+            uint256 d = addmod(alpha, P - xs[i], P);
+            for (uint256 k = 0; k < lv.reduction; k++) {
+                if (k != i) {
+                    d = mulmod(d, addmod(xs[i], P - xs[k], P), P);
+                }
+            }
+            // num / den + values[i] * c / d
+            num = addmod(mulmod(num, d, P), mulmod(mulmod(proof.leafValues[start + i], c, P), den, P), P);
+            den = mulmod(den, d, P);
+        }
+        return mulmod(num, expmod(den, P - 2), P);
+    }
+
+    function expmod(uint256 base, uint256 exponent) internal pure returns (uint256 result) {
+        result = 1;
+        while (exponent > 0) {
+            if ((exponent & 1) == 1) {
+                result = mulmod(result, base, P);
+            }
+            base = mulmod(base, base, P);
+            exponent >>= 1;
+        }
+    }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::types::FriConfig;
+    use crate::fri::utils::{FriConfigError, DEFAULT_DOMAIN_SEPARATOR};
+    use super::generate_verifier_contract;
+
+    fn golden_config() -> FriConfig {
+        FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1 , merkle_cap_bits: 1, level_reductions_bits: vec![1,2], pow_bits: 0 }
+    }
+
+    // Regenerate testdata/FriVerifier.sol with UPDATE_GOLDEN=1 after an intended change
+    #[test]
+    fn test_generated_contract_golden() {
+        let contract = generate_verifier_contract::<Fq>("FriVerifier", &golden_config(), 15, DEFAULT_DOMAIN_SEPARATOR).unwrap();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/solidity/testdata/FriVerifier.sol");
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            std::fs::write(path, &contract).unwrap();
+        }
+        assert_eq!(contract, std::fs::read_to_string(path).unwrap());
+    }
+
+    #[test]
+    fn test_generated_contract() {
+        let contract = generate_verifier_contract::<Fq>("FriVerifier", &golden_config(), 15, DEFAULT_DOMAIN_SEPARATOR).unwrap();
+        assert!(contract.contains("uint256 internal constant P = 18446744069414584321;"));
+        assert!(contract.contains("ls[1] = Level({domainSize: 32, numLeaves: 8, reduction: 4, depth: 3,"));
+        assert_eq!(contract.matches('{').count(), contract.matches('}').count());
+
+        // the statement is baked into the contract
+        let other_separator = generate_verifier_contract::<Fq>("FriVerifier", &golden_config(), 15, b"other").unwrap();
+        assert_ne!(contract, other_separator);
+        let mut more_queries = golden_config();
+        more_queries.num_query += 1;
+        assert_ne!(contract.replace("NUM_QUERY = 8", "NUM_QUERY = 9"), generate_verifier_contract::<Fq>("FriVerifier", &more_queries, 15, DEFAULT_DOMAIN_SEPARATOR).unwrap());
+
+        // grinding is part of the statement too
+        let ground = generate_verifier_contract::<Fq>("FriVerifier", &FriConfig { pow_bits: 10, ..golden_config() }, 15, DEFAULT_DOMAIN_SEPARATOR).unwrap();
+        assert!(ground.contains("uint256 internal constant POW_BITS = 10;"));
+        assert_ne!(contract.replace("POW_BITS = 0", "POW_BITS = 10"), ground);
+
+        let mut invalid = golden_config();
+        invalid.num_query = 0;
+        assert_eq!(generate_verifier_contract::<Fq>("FriVerifier", &invalid, 15, DEFAULT_DOMAIN_SEPARATOR), Err(FriConfigError::NoQueries));
+    }
+}
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MIT
+// Generated by fri-commitment, do not edit.
+// degree: 15, num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1, merkle_cap_bits: 1, level_reductions_bits: [1, 2], pow_bits: 0
+pragma solidity ^0.8.20;
+
+contract FriVerifier {
+    uint256 internal constant P = 18446744069414584321;
+    uint256 internal constant NUM_QUERY = 8;
+    uint256 internal constant NUM_LEVELS = 2;
+    uint256 internal constant CAP_BITS = 1;
+    uint256 internal constant CAP_SIZE = 2;
+    uint256 internal constant ORIGINAL_DOMAIN_SIZE = 64;
+    uint256 internal constant FINAL_DOMAIN_SIZE = 8;
+    uint256 internal constant LAST_POLYNOMIAL_DEGREE = 1;
+    uint256 internal constant FINAL_GENERATOR_INV = 1099511627520;
+    uint256 internal constant POW_BITS = 0;
+    bytes1 internal constant LEAF_TAG = 0x00;
+    bytes1 internal constant NODE_TAG = 0x01;
+    // transcript state after the domain separator and the statement
+    bytes32 internal constant INITIAL_STATE = 0xd003bdf8dee0924a3d67cbd30bb9a1eebbac98360f93b06f81a3c5a3a4e636a5;
+
+    struct Proof {
+        uint256[] finalEvaluations;
+        bytes32[] levelRoots; // CAP_SIZE roots per level
+        uint256[] leafIndices; // opened leaves of every level, sorted within a level
+        uint256[] leafValues; // reduction values per opened leaf
+        bytes32[] siblings; // multi proof siblings of every level
+        uint256[] counts; // (opened leaves, siblings) per level
+        uint256 powNonce; // 0 without grinding
+    }
+
+    struct Level {
+        uint256 domainSize;
+        uint256 numLeaves;
+        uint256 reduction;
+        uint256 depth;
+        uint256 offset;
+        uint256 generator;
+        uint256 reductionRoot;
+    }
+
+    // Position of a level's opening in the flattened proof arrays
+    struct Opening {
+        uint256 leafStart;
+        uint256 numLeaves;
+        uint256 valueStart;
+        uint256 siblingStart;
+        uint256 numSiblings;
+        uint256 rootStart;
+    }
+
+    uint256 internal constant NOT_FOUND = type(uint256).max;
+
+    function levels() internal pure returns (Level[] memory ls) {
+        ls = new Level[](NUM_LEVELS);
+        ls[0] = Level({domainSize: 64, numLeaves: 32, reduction: 2, depth: 5, offset: 7, generator: 549755813888, reductionRoot: 18446744069414584320});
+        ls[1] = Level({domainSize: 32, numLeaves: 8, reduction: 4, depth: 3, offset: 49, generator: 70368744161280, reductionRoot: 281474976710656});
+    }
+
+    function verifyProof(Proof calldata proof) external pure returns (bool) {
+        if (proof.finalEvaluations.length != FINAL_DOMAIN_SIZE
+            || proof.levelRoots.length != NUM_LEVELS * CAP_SIZE
+            || proof.counts.length != 2 * NUM_LEVELS
+            || proof.powNonce > type(uint64).max) {
+            return false;
+        }
+        if (!checkCanonical(proof.finalEvaluations) || !checkCanonical(proof.leafValues)) {
+            return false;
+        }
+        Level[] memory ls = levels();
+        (bool ok, Opening[] memory os) = openings(proof, ls);
+        if (!ok || !checkFinalDegree(proof.finalEvaluations)) {
+            return false;
+        }
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            if (!verifyOpening(proof, ls[l], os[l])) {
+                return false;
+            }
+        }
+        (bool powOk, uint256[] memory alphas, uint256[] memory queries) = replayTranscript(proof);
+        if (!powOk) {
+            return false;
+        }
+        for (uint256 i = 0; i < NUM_QUERY; i++) {
+            if (!verifyQuery(proof, ls, os, alphas, queries[i])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function checkCanonical(uint256[] calldata values) internal pure returns (bool) {
+        for (uint256 i = 0; i < values.length; i++) {
+            if (values[i] >= P) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // Splits the flattened arrays into the openings of each level
+    function openings(Proof calldata proof, Level[] memory ls) internal pure returns (bool ok, Opening[] memory os) {
+        os = new Opening[](NUM_LEVELS);
+        uint256 leafStart = 0;
+        uint256 valueStart = 0;
+        uint256 siblingStart = 0;
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            uint256 numLeaves = proof.counts[2 * l];
+            uint256 numSiblings = proof.counts[2 * l + 1];
+            if (numLeaves > proof.leafIndices.length || numSiblings > proof.siblings.length) {
+                return (false, os);
+            }
+            os[l] = Opening(leafStart, numLeaves, valueStart, siblingStart, numSiblings, l * CAP_SIZE);
+            leafStart += numLeaves;
+            valueStart += numLeaves * ls[l].reduction;
+            siblingStart += numSiblings;
+        }
+        ok = leafStart == proof.leafIndices.length
+            && valueStart == proof.leafValues.length
+            && siblingStart == proof.siblings.length;
+    }
+
+    // Same messages and labels as KeccakTranscript in the prover, powOk is false if the nonce fails the
+    // proof of work
+    function replayTranscript(Proof calldata proof) internal pure returns (bool powOk, uint256[] memory alphas, uint256[] memory queries) {
+        bytes32 state = INITIAL_STATE;
+        alphas = new uint256[](NUM_LEVELS);
+        uint256[] memory cap = new uint256[](CAP_SIZE);
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            for (uint256 i = 0; i < CAP_SIZE; i++) {
+                cap[i] = uint256(proof.levelRoots[l * CAP_SIZE + i]) % P;
+            }
+            state = keccak256(abi.encodePacked(state, "merkle_root", cap));
+            state = keccak256(abi.encodePacked(state, "alpha"));
+            alphas[l] = uint256(state) % P;
+        }
+        uint256[] memory finalEvaluations = proof.finalEvaluations;
+        state = keccak256(abi.encodePacked(state, "final evals", finalEvaluations));
+        if (POW_BITS > 0) {
+            state = keccak256(abi.encodePacked(state, "pow nonce", proof.powNonce));
+            state = keccak256(abi.encodePacked(state, "pow"));
+            powOk = uint256(uint32(uint256(state))) % (1 << POW_BITS) == 0;
+        } else {
+            powOk = proof.powNonce == 0;
+        }
+        queries = new uint256[](NUM_QUERY);
+        for (uint256 i = 0; i < NUM_QUERY; i++) {
+            state = keccak256(abi.encodePacked(state, "challenge indices"));
+            queries[i] = uint256(uint32(uint256(state)));
+        }
+    }
+
+    // The interpolant of the final evaluations over the coset has no coefficient above LAST_POLYNOMIAL_DEGREE,
+    // i.e. sum_j evals[j] * generator^(-jk) vanishes for every larger k
+    function checkFinalDegree(uint256[] calldata evals) internal pure returns (bool) {
+        for (uint256 k = LAST_POLYNOMIAL_DEGREE + 1; k < FINAL_DOMAIN_SIZE; k++) {
+            uint256 step = expmod(FINAL_GENERATOR_INV, k);
+            uint256 x = 1;
+            uint256 acc = 0;
+            for (uint256 j = 0; j < FINAL_DOMAIN_SIZE; j++) {
+                acc = addmod(acc, mulmod(evals[j], x, P), P);
+                x = mulmod(x, step, P);
+            }
+            if (acc != 0) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // Mirrors Keccak256_::hash_elements, every value of the leaf is hashed as its own word
+    function hashLeaf(uint256[] calldata values, uint256 start, uint256 len) internal pure returns (bytes32) {
+        uint256[] memory leaf = new uint256[](len);
+        for (uint256 j = 0; j < len; j++) {
+            leaf[j] = values[start + j];
+        }
+        return keccak256(abi.encodePacked(LEAF_TAG, leaf));
+    }
+
+    function hashNode(bytes32 left, bytes32 right) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(NODE_TAG, left, right));
+    }
+
+    // Batched Merkle opening of one level against its root cap, mirrors verify_multi_proof
+    function verifyOpening(Proof calldata proof, Level memory lv, Opening memory op) internal pure returns (bool) {
+        uint256 n = op.numLeaves;
+        uint256[] memory idx = new uint256[](n);
+        bytes32[] memory hashes = new bytes32[](n);
+        for (uint256 i = 0; i < n; i++) {
+            idx[i] = proof.leafIndices[op.leafStart + i];
+            if (idx[i] >= lv.numLeaves || (i > 0 && idx[i] <= idx[i - 1])) {
+                return false;
+            }
+            hashes[i] = hashLeaf(proof.leafValues, op.valueStart + i * lv.reduction, lv.reduction);
+        }
+        uint256 s = op.siblingStart;
+        for (uint256 d = CAP_BITS; d < lv.depth; d++) {
+            uint256 m = 0;
+            for (uint256 i = 0; i < n; i++) {
+                if (idx[i] % 2 == 0 && i + 1 < n && idx[i + 1] == idx[i] + 1) {
+                    // both children opened
+                    hashes[m] = hashNode(hashes[i], hashes[i + 1]);
+                    i++;
+                } else {
+                    if (s == op.siblingStart + op.numSiblings) {
+                        return false;
+                    }
+                    hashes[m] = idx[i] % 2 == 0
+                        ? hashNode(hashes[i], proof.siblings[s])
+                        : hashNode(proof.siblings[s], hashes[i]);
+                    s++;
+                }
+                idx[m] = idx[i] / 2;
+                m++;
+            }
+            n = m;
+        }
+        if (s != op.siblingStart + op.numSiblings) {
+            return false;
+        }
+        for (uint256 i = 0; i < n; i++) {
+            if (hashes[i] != proof.levelRoots[op.rootStart + idx[i]]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function findLeaf(Proof calldata proof, Opening memory op, uint256 leafIndex, uint256 reduction) internal pure returns (uint256) {
+        for (uint256 i = 0; i < op.numLeaves; i++) {
+            if (proof.leafIndices[op.leafStart + i] == leafIndex) {
+                return op.valueStart + i * reduction;
+            }
+        }
+        return NOT_FOUND;
+    }
+
+    // Folds a query through every level, checking consistency with the previous level and the final evaluations
+    function verifyQuery(Proof calldata proof, Level[] memory ls, Opening[] memory os, uint256[] memory alphas, uint256 query) internal pure returns (bool) {
+        uint256 q = query % (ORIGINAL_DOMAIN_SIZE / 2);
+        uint256 value = 0;
+        for (uint256 l = 0; l < NUM_LEVELS; l++) {
+            uint256 pos = q % ls[l].domainSize;
+            uint256 start = findLeaf(proof, os[l], pos % ls[l].numLeaves, ls[l].reduction);
+            if (start == NOT_FOUND) {
+                return false;
+            }
+            if (l != 0 && proof.leafValues[start + pos / ls[l].numLeaves] != value) {
+                return false;
+            }
+            uint256 base = mulmod(ls[l].offset, expmod(ls[l].generator, pos % ls[l].numLeaves), P);
+            value = fold(proof, start, ls[l], base, alphas[l]);
+        }
+        return NUM_LEVELS == 0 || proof.finalEvaluations[query % FINAL_DOMAIN_SIZE] == value;
+    }
+
+    // Evaluates at alpha the interpolant of a leaf's values over base * reductionRoot^i
+    function fold(Proof calldata proof, uint256 start, Level memory lv, uint256 base, uint256 alpha) internal pure returns (uint256) {
+        uint256[] memory xs = new uint256[](lv.reduction);
+        xs[0] = base;
+        uint256 c = addmod(alpha, P - base, P);
+        for (uint256 i = 1; i < lv.reduction; i++) {
+            xs[i] = mulmod(xs[i - 1], lv.reductionRoot, P);
+            c = mulmod(c, addmod(alpha, P - xs[i], P), P);
+        }
+        uint256 num = 0;
+        uint256 den = 1;
+        for (uint256 i = 0; i < lv.reduction; i++) {
+            uint256 d = addmod(alpha, P - xs[i], P);
+            for (uint256 k = 0; k < lv.reduction; k++) {
+                if (k != i) {
+                    d = mulmod(d, addmod(xs[i], P - xs[k], P), P);
+                }
+            }
+            // num / den + values[i] * c / d
+            num = addmod(mulmod(num, d, P), mulmod(mulmod(proof.leafValues[start + i], c, P), den, P), P);
+            den = mulmod(den, d, P);
+        }
+        return mulmod(num, expmod(den, P - 2), P);
+    }
+
+    function expmod(uint256 base, uint256 exponent) internal pure returns (uint256 result) {
+        result = 1;
+        while (exponent > 0) {
+            if ((exponent & 1) == 1) {
+                result = mulmod(result, base, P);
+            }
+            base = mulmod(base, base, P);
+            exponent >>= 1;
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use ark_ff::PrimeField;
+
+// Trace cell `column` at `row` must equal `value`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundaryConstraint<F: PrimeField> {
+    pub column: usize,
+    pub row: usize,
+    pub value: F,
+}
+
+// Algebraic intermediate representation of a computation over a trace of `trace_length` rows and `trace_width` columns
+pub trait Air<F: PrimeField> {
+    fn trace_width(&self) -> usize;
+
+    // Must be a power of two
+    fn trace_length(&self) -> usize;
+
+    fn num_transition_constraints(&self) -> usize;
+
+    // Evaluations of the transition constraints on two consecutive rows, all zero on a valid trace
+    // for every row but the last
+    fn evaluate_transition(&self, current: &[F], next: &[F]) -> Vec<F>;
+
+    // Largest total degree of the transition constraints in the trace cells
+    fn transition_degree(&self) -> usize;
+
+    fn boundary_constraints(&self) -> Vec<BoundaryConstraint<F>>;
+}
+
+// Composition polynomial has less than composition_factor * trace_length coefficients
+pub fn composition_factor<F: PrimeField>(air: &impl Air<F>) -> usize {
+    (air.transition_degree().saturating_sub(1)).max(1).next_power_of_two()
+}
+
+// Degree bound of the DEEP composition polynomial, the one FRI is run on
+pub fn deep_degree<F: PrimeField>(air: &impl Air<F>) -> u32 {
+    (composition_factor(air) * air.trace_length()) as u32 - 1
+}
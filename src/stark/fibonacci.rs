@@ -0,0 +1,144 @@
+use ark_ff::PrimeField;
+
+use super::air::{Air, BoundaryConstraint};
+
+// Two column Fibonacci: rows (a, b) -> (b, a + b) starting from (1, 1), with b on the last row public
+#[derive(Clone, Debug)]
+pub struct FibonacciAir<F: PrimeField> {
+    pub trace_length: usize,
+    pub result: F,
+}
+
+impl<F: PrimeField> FibonacciAir<F> {
+    // Honest trace and the AIR claiming its result
+    pub fn with_trace(trace_length: usize) -> (Self, Vec<Vec<F>>) {
+        let mut a = vec![F::ONE];
+        let mut b = vec![F::ONE];
+        for i in 1..trace_length {
+            a.push(b[i-1]);
+            b.push(a[i-1] + b[i-1]);
+        }
+        (FibonacciAir { trace_length, result: b[trace_length-1] }, vec![a, b])
+    }
+}
+
+impl<F: PrimeField> Air<F> for FibonacciAir<F> {
+    fn trace_width(&self) -> usize {
+        2
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn num_transition_constraints(&self) -> usize {
+        2
+    }
+
+    fn evaluate_transition(&self, current: &[F], next: &[F]) -> Vec<F> {
+        vec![next[0] - current[1], next[1] - current[0] - current[1]]
+    }
+
+    fn transition_degree(&self) -> usize {
+        1
+    }
+
+    fn boundary_constraints(&self) -> Vec<BoundaryConstraint<F>> {
+        vec![
+            BoundaryConstraint { column: 0, row: 0, value: F::ONE },
+            BoundaryConstraint { column: 1, row: 0, value: F::ONE },
+            BoundaryConstraint { column: 1, row: self.trace_length - 1, value: self.result },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::types::FriConfig;
+    use crate::hashing::hasher::Sha256_;
+    use crate::stark::air::deep_degree;
+    use crate::fri::utils::new_fri_transcript;
+    use crate::ldt::{stir::StirLdt, LowDegreeTest};
+    use crate::stark::prover::{generate_stark_proof, generate_stark_proof_with_ldt};
+    use crate::stark::types::StarkProof;
+    use crate::stark::utils::STARK_DOMAIN_SEPARATOR;
+    use crate::stark::verifier::{verify_stark_proof, verify_stark_proof_with_ldt};
+    use crate::stir::types::StirConfig;
+    use super::FibonacciAir;
+
+    fn fri_config() -> FriConfig {
+        FriConfig { num_query: 8, blow_up_factor: 4, last_polynomial_degree: 1, merkle_cap_bits: 1, level_reductions_bits: vec![2, 2], pow_bits: 0 }
+    }
+
+    fn sample_proof() -> (FibonacciAir<Fq>, StarkProof<Fq, Sha256_<Fq>>) {
+        let (air, trace) = FibonacciAir::<Fq>::with_trace(32);
+        assert_eq!(deep_degree(&air), 31);
+        let proof = generate_stark_proof::<Fq, Sha256_<Fq>>(&air, &trace, fri_config());
+        (air, proof)
+    }
+
+    #[test]
+    fn test_fibonacci_stark() {
+        let (air, proof) = sample_proof();
+        // b holds F_(i+2), F_33 on the last row
+        assert_eq!(air.result, Fq::from(3524578u64));
+        assert!(verify_stark_proof(&air, fri_config(), proof.clone()));
+
+        // wrong public result
+        let wrong = FibonacciAir { trace_length: 32, result: air.result + Fq::from(1u64) };
+        assert!(!verify_stark_proof(&wrong, fri_config(), proof));
+    }
+
+    #[test]
+    fn test_fibonacci_stark_tampered() {
+        let (air, proof) = sample_proof();
+
+        let mut bad_ood = proof.clone();
+        bad_ood.ood_trace[0] += Fq::from(1u64);
+        assert!(!verify_stark_proof(&air, fri_config(), bad_ood));
+
+        let mut bad_composition = proof.clone();
+        bad_composition.ood_composition += Fq::from(1u64);
+        assert!(!verify_stark_proof(&air, fri_config(), bad_composition));
+
+        let mut bad_row = proof.clone();
+        bad_row.trace_openings[0].rows[0][1] += Fq::from(1u64);
+        assert!(!verify_stark_proof(&air, fri_config(), bad_row));
+
+        // value moved between the columns of a row
+        let mut moved = proof.clone();
+        moved.trace_openings[0].rows[0][0] += Fq::from(1000u64);
+        moved.trace_openings[0].rows[0][1] -= Fq::from(1000u64);
+        assert!(!verify_stark_proof(&air, fri_config(), moved));
+
+        let mut missing_row = proof.clone();
+        missing_row.trace_openings.pop();
+        assert!(!verify_stark_proof(&air, fri_config(), missing_row));
+
+        let mut swapped_roots = proof.clone();
+        swapped_roots.trace_root = proof.composition_root.clone();
+        assert!(!verify_stark_proof(&air, fri_config(), swapped_roots));
+    }
+
+    #[test]
+    fn test_fibonacci_stark_with_stir() {
+        let (air, trace) = FibonacciAir::<Fq>::with_trace(32);
+        let config = StirConfig { blow_up_factor: 4, folding_factor_bits: 2, num_rounds: 1, num_queries: vec![4, 3], ood_samples: 1, merkle_cap_bits: 1 };
+        let ldt = StirLdt::<Fq, Sha256_<Fq>>::new(config, deep_degree(&air)).unwrap();
+        let proof = generate_stark_proof_with_ldt::<Fq, Sha256_<Fq>, _>(&air, &trace, &ldt, &mut new_fri_transcript(STARK_DOMAIN_SEPARATOR));
+        assert!(verify_stark_proof_with_ldt(&air, &ldt, proof.clone(), &mut new_fri_transcript(STARK_DOMAIN_SEPARATOR)));
+
+        let mut bad_row = proof;
+        bad_row.trace_openings[0].rows[0][0] += Fq::from(1u64);
+        assert!(!verify_stark_proof_with_ldt(&air, &ldt, bad_row, &mut new_fri_transcript(STARK_DOMAIN_SEPARATOR)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Trace does not satisfy the AIR")]
+    fn test_fibonacci_invalid_trace() {
+        let (air, mut trace) = FibonacciAir::<Fq>::with_trace(32);
+        trace[0][5] += Fq::from(1u64);
+        generate_stark_proof::<Fq, Sha256_<Fq>>(&air, &trace, fri_config());
+    }
+}
@@ -0,0 +1,6 @@
+pub mod air;
+pub mod types;
+pub mod prover;
+pub mod verifier;
+pub mod utils;
+pub mod fibonacci;
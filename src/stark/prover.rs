@@ -0,0 +1,119 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+use tracing::debug_span;
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::{types::FriConfig, utils::new_fri_transcript};
+use crate::hashing::hasher::Hasher_;
+use crate::ldt::{fri::FriLdt, LowDegreeTest};
+use crate::merkle_tree::matrix::{MatrixMerkleTree, RowMajorMatrix};
+use crate::merkle_tree::merkle::MerkleTree;
+
+use super::air::{composition_factor, deep_degree, Air};
+use super::types::StarkProof;
+use super::utils::{evaluate_composition, evaluate_deep, get_challenges, observe_air_statement, STARK_DOMAIN_SEPARATOR};
+
+pub fn generate_stark_proof<F: PrimeField + From<i32>, H: Hasher_<F>>(air: &impl Air<F>, trace: &[Vec<F>], fri_config: FriConfig) -> StarkProof<F, H> {
+    let mut transcript = new_fri_transcript(STARK_DOMAIN_SEPARATOR);
+    generate_stark_proof_with_transcript(air, trace, fri_config, &mut transcript)
+}
+
+// Proves that `trace` (one vector per column) satisfies `air`, with FRI as the low degree test. The DEEP
+// composition polynomial has degree deep_degree(air), `fri_config` must be valid for it.
+pub fn generate_stark_proof_with_transcript<F: PrimeField + From<i32>, H: Hasher_<F>>(air: &impl Air<F>, trace: &[Vec<F>], fri_config: FriConfig, transcript: &mut impl TranscriptProtocol<F>) -> StarkProof<F, H> {
+    let ldt = match FriLdt::<F, H>::new(fri_config, deep_degree(air)) {
+        Ok(ldt) => ldt,
+        Err(e) => panic!("Invalid FRI config: {}", e),
+    };
+    generate_stark_proof_with_ldt(air, trace, &ldt, transcript)
+}
+
+// Same, with any low degree test for polynomials of degree deep_degree(air)
+pub fn generate_stark_proof_with_ldt<F: PrimeField, H: Hasher_<F>, L: LowDegreeTest<F, Commitment = Vec<H::Hash>>>(air: &impl Air<F>, trace: &[Vec<F>], ldt: &L, transcript: &mut impl TranscriptProtocol<F>) -> StarkProof<F, H, L::Proof> {
+    let width = air.trace_width();
+    let n = air.trace_length();
+    assert!(n.is_power_of_two() && n >= 2, "Trace length must be a power of two");
+    assert!(trace.len() == width && trace.iter().all(|column| column.len() == n), "Trace shape does not match the AIR");
+
+    let degree = deep_degree(air);
+    assert_eq!(ldt.degree(), degree, "Low degree test does not match the DEEP composition degree");
+
+    let _prove_span = debug_span!("stark_prove", width, n, degree).entered();
+
+    observe_air_statement(transcript, air);
+
+    // LDE over the domain of the low degree test
+    let trace_domain = GeneralEvaluationDomain::<F>::new(n).unwrap();
+    let lde_domain = ldt.domain();
+    let lde_size = lde_domain.size();
+
+    // 1. Commit to the trace LDE, one leaf per row hashed element by element
+    let lde_span = debug_span!("trace_lde", lde_size).entered();
+    let trace_polys: Vec<DensePolynomial<F>> = trace.iter()
+        .map(|column| DensePolynomial::from_coefficients_vec(trace_domain.ifft(column)))
+        .collect();
+    let trace_lde: Vec<Vec<F>> = trace_polys.iter().map(|poly| lde_domain.fft(&poly.coeffs)).collect();
+    let rows: Vec<Vec<F>> = (0..lde_size).map(|i| trace_lde.iter().map(|column| column[i]).collect()).collect();
+    let mut trace_tree = MatrixMerkleTree::<F, H>::new(ldt.merkle_cap_bits());
+    trace_tree.insert(RowMajorMatrix::new(rows.concat(), width));
+    let trace_root = trace_tree.compute_tree();
+    let trace_root_field: Vec<F> = trace_root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"trace_root", &trace_root_field);
+    lde_span.exit();
+
+    // 2. Random linear combination of the constraint quotients
+    let composition_span = debug_span!("composition").entered();
+    let boundary_constraints = air.boundary_constraints();
+    let alphas = get_challenges(transcript, b"composition coefficient", air.num_transition_constraints() + boundary_constraints.len());
+    let trace_generator = trace_domain.group_gen();
+    // w * x is `step` positions further in the LDE domain
+    let step = lde_size / n;
+    let composition_evaluations: Vec<F> = (0..lde_size).map(|i| {
+        evaluate_composition(air, &alphas, lde_domain.element(i), &rows[i], &rows[(i + step) % lde_size], trace_generator)
+            .expect("LDE domain is disjoint from the trace domain")
+    }).collect();
+    let composition_coeffs = lde_domain.ifft(&composition_evaluations);
+    let composition_len = composition_factor(air) * n;
+    assert!(composition_coeffs[composition_len..].iter().all(|c| c.is_zero()), "Trace does not satisfy the AIR");
+    let composition_poly = DensePolynomial::from_coefficients_slice(&composition_coeffs[..composition_len]);
+
+    let mut composition_tree = MerkleTree::<F, H>::new(ldt.merkle_cap_bits());
+    composition_tree.insert(composition_evaluations.iter().map(|c| vec![*c]).collect()).expect("Tree is memory backed");
+    let composition_root = composition_tree.compute_tree().expect("Tree is memory backed");
+    let composition_root_field: Vec<F> = composition_root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"composition_root", &composition_root_field);
+    composition_span.exit();
+
+    // 3. Out of domain evaluations and the DEEP composition polynomial
+    let z: F = transcript.get_challenge(b"ood point");
+    let z_next = z * trace_generator;
+    let ood_trace: Vec<F> = trace_polys.iter().map(|poly| poly.evaluate(&z)).collect();
+    let ood_trace_next: Vec<F> = trace_polys.iter().map(|poly| poly.evaluate(&z_next)).collect();
+    let ood_composition = composition_poly.evaluate(&z);
+    transcript.observe_elements(b"ood trace", &ood_trace);
+    transcript.observe_elements(b"ood trace next", &ood_trace_next);
+    transcript.observe_element(b"ood composition", &ood_composition);
+
+    let gammas = get_challenges(transcript, b"deep coefficient", 2 * width + 1);
+    let deep_evaluations: Vec<F> = (0..lde_size).map(|i| {
+        evaluate_deep(&gammas, lde_domain.element(i), &rows[i], composition_evaluations[i], z, z_next, &ood_trace, &ood_trace_next, ood_composition)
+            .expect("Out of domain point inside the LDE domain")
+    }).collect();
+
+    // 4. Low degree test of the DEEP codeword, then open the trace and composition where it was opened
+    let (deep_root, ldt_proof) = ldt.prove(&deep_evaluations, transcript);
+    let positions: Vec<usize> = ldt.opened_values(&ldt_proof).expect("Low degree proof is malformed")
+        .iter().map(|(position, _)| *position).collect();
+
+    StarkProof {
+        trace_root,
+        composition_root,
+        ood_trace,
+        ood_trace_next,
+        ood_composition,
+        trace_openings: positions.iter().map(|position| trace_tree.open(*position)).collect(),
+        composition_openings: composition_tree.multi_proof(&positions),
+        deep_root,
+        ldt_proof,
+    }
+}
@@ -0,0 +1,22 @@
+use ark_ff::PrimeField;
+
+use crate::fri::types::FRIProof;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::matrix::MatrixOpening;
+use crate::merkle_tree::merkle::MerkleMultiProof;
+
+// `P` is the proof of the low degree test, FRI by default
+#[derive(Debug, Clone)]
+pub struct StarkProof<F: PrimeField, H: Hasher_<F>, P = FRIProof<F, H>> {
+    pub trace_root: Vec<H::Hash>, // leaf i is the row of trace LDE values at the i-th LDE point
+    pub composition_root: Vec<H::Hash>,
+    pub ood_trace: Vec<F>, // trace columns at the out of domain point z
+    pub ood_trace_next: Vec<F>, // trace columns at w * z
+    pub ood_composition: F,
+    // opened at every position of the DEEP codeword the low degree test opened, one trace row opening per
+    // position in increasing order
+    pub trace_openings: Vec<MatrixOpening<F, H>>,
+    pub composition_openings: MerkleMultiProof<F, H>,
+    pub deep_root: Vec<H::Hash>, // commitment of the low degree test to the DEEP codeword
+    pub ldt_proof: P, // low degree proof of the DEEP composition polynomial
+}
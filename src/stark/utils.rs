@@ -0,0 +1,60 @@
+use ark_ff::PrimeField;
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+
+use super::air::Air;
+
+// Domain separator used by generate_stark_proof / verify_stark_proof
+pub const STARK_DOMAIN_SEPARATOR: &[u8] = b"fri-commitment-stark";
+
+// Binds the AIR shape and its boundary constraints (the public inputs) to the transcript
+pub fn observe_air_statement<F: PrimeField>(transcript: &mut impl TranscriptProtocol<F>, air: &impl Air<F>) {
+    transcript.observe_u64(b"trace width", air.trace_width() as u64);
+    transcript.observe_u64(b"trace length", air.trace_length() as u64);
+    transcript.observe_u64(b"num transition constraints", air.num_transition_constraints() as u64);
+    transcript.observe_u64(b"transition degree", air.transition_degree() as u64);
+    for constraint in air.boundary_constraints() {
+        transcript.observe_u64(b"boundary column", constraint.column as u64);
+        transcript.observe_u64(b"boundary row", constraint.row as u64);
+        transcript.observe_element(b"boundary value", &constraint.value);
+    }
+}
+
+pub fn get_challenges<F: PrimeField>(transcript: &mut impl TranscriptProtocol<F>, label: &'static [u8], n: usize) -> Vec<F> {
+    (0..n).map(|_| transcript.get_challenge(label)).collect()
+}
+
+// Composition polynomial value at `x` from the trace values at x and w * x:
+// sum of alpha_i * transition_i / Z(x) over the transition constraints, with Z vanishing on every row but the last,
+// plus alpha_k * (T_column(x) - value) / (x - w^row) over the boundary constraints.
+// None if x is a root of a denominator.
+pub fn evaluate_composition<F: PrimeField>(air: &impl Air<F>, alphas: &[F], x: F, current: &[F], next: &[F], trace_generator: F) -> Option<F> {
+    let n = air.trace_length() as u64;
+    let last_row = trace_generator.pow([n - 1]);
+    let transition_divisor = (x - last_row) * (x.pow([n]) - F::ONE).inverse()?;
+    let mut value = F::ZERO;
+    let transitions = air.evaluate_transition(current, next);
+    for (alpha, t) in alphas.iter().zip(transitions.iter()) {
+        value += *alpha * t * transition_divisor;
+    }
+    for (alpha, constraint) in alphas[transitions.len()..].iter().zip(air.boundary_constraints()) {
+        let root = trace_generator.pow([constraint.row as u64]);
+        value += *alpha * (current[constraint.column] - constraint.value) * (x - root).inverse()?;
+    }
+    Some(value)
+}
+
+// DEEP composition polynomial value at `x` from the trace row and composition value at x:
+// sum of gamma_j * (T_j(x) - T_j(z)) / (x - z) + gamma_{w+j} * (T_j(x) - T_j(w * z)) / (x - w * z)
+// over the columns, plus gamma_{2w} * (C(x) - C(z)) / (x - z).
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_deep<F: PrimeField>(gammas: &[F], x: F, row: &[F], composition: F, z: F, z_next: F, ood_trace: &[F], ood_trace_next: &[F], ood_composition: F) -> Option<F> {
+    let width = row.len();
+    let inv_z = (x - z).inverse()?;
+    let inv_z_next = (x - z_next).inverse()?;
+    let mut value = gammas[2*width] * (composition - ood_composition) * inv_z;
+    for j in 0..width {
+        value += gammas[j] * (row[j] - ood_trace[j]) * inv_z + gammas[width+j] * (row[j] - ood_trace_next[j]) * inv_z_next;
+    }
+    Some(value)
+}
@@ -0,0 +1,104 @@
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use tracing::{debug, debug_span};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::{types::FriConfig, utils::new_fri_transcript};
+use crate::hashing::hasher::Hasher_;
+use crate::ldt::{fri::FriLdt, LowDegreeTest};
+use crate::merkle_tree::matrix::{verify_matrix_opening, MatrixDimensions};
+use crate::merkle_tree::merkle::verify_multi_proof;
+
+use super::air::{deep_degree, Air};
+use super::types::StarkProof;
+use super::utils::{evaluate_composition, evaluate_deep, get_challenges, observe_air_statement, STARK_DOMAIN_SEPARATOR};
+
+pub fn verify_stark_proof<F: PrimeField + From<i32>, H: Hasher_<F>>(air: &impl Air<F>, fri_config: FriConfig, proof: StarkProof<F, H>) -> bool {
+    let mut transcript = new_fri_transcript(STARK_DOMAIN_SEPARATOR);
+    verify_stark_proof_with_transcript(air, fri_config, proof, &mut transcript)
+}
+
+// Verifies a proof produced by generate_stark_proof_with_transcript
+pub fn verify_stark_proof_with_transcript<F: PrimeField + From<i32>, H: Hasher_<F>>(air: &impl Air<F>, fri_config: FriConfig, proof: StarkProof<F, H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    match FriLdt::<F, H>::new(fri_config, deep_degree(air)) {
+        Ok(ldt) => verify_stark_proof_with_ldt(air, &ldt, proof, transcript),
+        Err(_) => false,
+    }
+}
+
+// Verifies a proof produced by generate_stark_proof_with_ldt
+pub fn verify_stark_proof_with_ldt<F: PrimeField, H: Hasher_<F>, L: LowDegreeTest<F, Commitment = Vec<H::Hash>>>(air: &impl Air<F>, ldt: &L, proof: StarkProof<F, H, L::Proof>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    let width = air.trace_width();
+    let n = air.trace_length();
+    let degree = deep_degree(air);
+    if !n.is_power_of_two() || n < 2 || ldt.degree() != degree {
+        return false;
+    }
+    if proof.ood_trace.len() != width || proof.ood_trace_next.len() != width {
+        return false;
+    }
+
+    let _verify_span = debug_span!("stark_verify", width, n, degree).entered();
+
+    // Replay the transcript up to the low degree test
+    observe_air_statement(transcript, air);
+    let trace_root_field: Vec<F> = proof.trace_root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"trace_root", &trace_root_field);
+    let boundary_constraints = air.boundary_constraints();
+    let alphas = get_challenges(transcript, b"composition coefficient", air.num_transition_constraints() + boundary_constraints.len());
+    let composition_root_field: Vec<F> = proof.composition_root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"composition_root", &composition_root_field);
+    let z: F = transcript.get_challenge(b"ood point");
+    transcript.observe_elements(b"ood trace", &proof.ood_trace);
+    transcript.observe_elements(b"ood trace next", &proof.ood_trace_next);
+    transcript.observe_element(b"ood composition", &proof.ood_composition);
+    let gammas = get_challenges(transcript, b"deep coefficient", 2 * width + 1);
+
+    // Constraints hold at z
+    let trace_generator = GeneralEvaluationDomain::<F>::new(n).unwrap().group_gen();
+    let z_next = z * trace_generator;
+    match evaluate_composition(air, &alphas, z, &proof.ood_trace, &proof.ood_trace_next, trace_generator) {
+        Some(value) if value == proof.ood_composition => {},
+        _ => {
+            debug!("out of domain constraint check failed");
+            return false;
+        }
+    }
+
+    let lde_domain = ldt.domain();
+    // DEEP codeword values the low degree test opened
+    let opened = match ldt.opened_values(&proof.ldt_proof) {
+        Some(opened) if !opened.is_empty() => opened,
+        _ => return false,
+    };
+    if !ldt.verify(&proof.deep_root, proof.ldt_proof, transcript) {
+        return false;
+    }
+
+    // Openings are authentic, and agree with the DEEP values the test opened
+    let depth = lde_domain.size().trailing_zeros();
+    let trace_dimensions = [MatrixDimensions { width, height: lde_domain.size() }];
+    if proof.trace_openings.len() != opened.len()
+        || !verify_multi_proof(&proof.composition_root, depth, &proof.composition_openings) {
+        return false;
+    }
+    for ((position, value), trace_opening) in opened.into_iter().zip(proof.trace_openings.iter()) {
+        if !verify_matrix_opening(&proof.trace_root, &trace_dimensions, position, trace_opening) {
+            return false;
+        }
+        let row = &trace_opening.rows[0];
+        let composition = match proof.composition_openings.leaf(position) {
+            Some(composition) if composition.len() == 1 => composition[0],
+            _ => return false,
+        };
+        let deep = match evaluate_deep(&gammas, lde_domain.element(position), row, composition, z, z_next, &proof.ood_trace, &proof.ood_trace_next, proof.ood_composition) {
+            Some(deep) => deep,
+            None => return false,
+        };
+        if deep != value {
+            debug!(position, "DEEP value does not match the low degree test opening");
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,5 @@
+pub mod types;
+pub mod utils;
+pub mod prover;
+pub mod verifier;
+pub mod security;
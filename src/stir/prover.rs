@@ -0,0 +1,116 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::{DenseOrSparsePolynomial, DensePolynomial}, EvaluationDomain, Polynomial};
+use tracing::{debug, debug_span, trace};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::prover::fold_polynomial;
+use crate::fri::types::QueryEvalProofs;
+use crate::fri::utils::new_fri_transcript;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::MerkleTree;
+
+use super::types::{StirConfig, StirProof, StirRoundProof};
+use super::utils::{
+    degree_correction_polynomial, fiber_leaves, interpolate, observe_stir_statement, shift_queries, stir_domain,
+    vanishing_polynomial, STIR_DOMAIN_SEPARATOR,
+};
+
+// Commits to the evaluations of `polynomial` over the domain of size `domain_size`
+fn commit<F: PrimeField, H: Hasher_<F>>(polynomial: &DensePolynomial<F>, domain_size: usize, config: &StirConfig, transcript: &mut impl TranscriptProtocol<F>) -> MerkleTree<F, H> {
+    let _commit_span = debug_span!("commit_level", domain_size).entered();
+    let evaluations = stir_domain::<F>(domain_size).fft(&polynomial.coeffs);
+    let mut tree = MerkleTree::<F, H>::new(config.merkle_cap_bits);
+    tree.insert(fiber_leaves(&evaluations, config.folding_factor_bits)).expect("Tree is memory backed");
+    let root_field: Vec<F> = tree.compute_tree().expect("Tree is memory backed").iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"merkle_root", &root_field);
+    tree
+}
+
+pub fn generate_stir_proof<F: PrimeField, H: Hasher_<F>>(polynomial: DensePolynomial<F>, stir_config: StirConfig) -> StirProof<F, H> {
+    let mut transcript = new_fri_transcript(STIR_DOMAIN_SEPARATOR);
+    generate_stir_proof_with_transcript(polynomial, stir_config, &mut transcript)
+}
+
+// Generates the proof on a caller supplied transcript, after binding the config and degree to it.
+// Leaves the transcript after the final queries.
+pub fn generate_stir_proof_with_transcript<F: PrimeField, H: Hasher_<F>>(polynomial: DensePolynomial<F>, stir_config: StirConfig, transcript: &mut impl TranscriptProtocol<F>) -> StirProof<F, H> {
+    let degree = polynomial.coeffs.len().saturating_sub(1) as u32;
+    generate_stir_proof_with_degree(polynomial, degree, stir_config, transcript)
+}
+
+// Proves `polynomial` against the degree bound `degree`, which may exceed its actual degree
+pub fn generate_stir_proof_with_degree<F: PrimeField, H: Hasher_<F>>(polynomial: DensePolynomial<F>, degree: u32, stir_config: StirConfig, transcript: &mut impl TranscriptProtocol<F>) -> StirProof<F, H> {
+    if let Err(e) = stir_config.validate::<F>(degree) {
+        panic!("Invalid STIR config: {}", e);
+    }
+    assert!(polynomial.coeffs.len() <= degree as usize + 1, "Polynomial exceeds the degree bound");
+    prove(polynomial, degree, &stir_config, transcript)
+}
+
+// Proves `polynomial` against the degree bound `degree`, which it is assumed to meet
+pub(super) fn prove<F: PrimeField, H: Hasher_<F>>(polynomial: DensePolynomial<F>, degree: u32, stir_config: &StirConfig, transcript: &mut impl TranscriptProtocol<F>) -> StirProof<F, H> {
+    let _prove_span = debug_span!("stir_prove", degree, num_rounds = stir_config.num_rounds).entered();
+    observe_stir_statement::<F>(transcript, stir_config, degree);
+
+    let folding_factor = stir_config.folding_factor();
+    let mut domain_size = stir_config.domain_size(degree, 0) as usize;
+    let mut current_polynomial = polynomial;
+    let mut current_tree = commit::<F, H>(&current_polynomial, domain_size, stir_config, transcript);
+    let initial_root = current_tree.root_cap.clone().unwrap();
+    let mut rounds = Vec::with_capacity(stir_config.num_rounds as usize);
+
+    for round in 0..stir_config.num_rounds as usize {
+        let _round_span = debug_span!("stir_round", round, domain_size).entered();
+        let r_fold: F = transcript.get_challenge(b"alpha");
+        let folded = fold_polynomial(current_polynomial, r_fold, folding_factor);
+
+        // Commit the fold over the next domain, half the size of the current one
+        let next_tree = commit::<F, H>(&folded, domain_size / 2, stir_config, transcript);
+
+        let ood_points: Vec<F> = (0..stir_config.ood_samples).map(|_| transcript.get_challenge(b"ood point")).collect();
+        let ood_answers: Vec<F> = ood_points.iter().map(|p| folded.evaluate(p)).collect();
+        transcript.observe_elements(b"ood answers", &ood_answers);
+        let r_comb: F = transcript.get_challenge(b"combination");
+
+        let queries = shift_queries(transcript, stir_config.num_queries[round], domain_size, folding_factor);
+        trace!(?queries, "sampled shift queries");
+        let domain = stir_domain::<F>(domain_size);
+        let shift_points: Vec<F> = queries.iter().map(|q| domain.element(*q).pow([folding_factor as u64])).collect();
+
+        // The next polynomial is the degree corrected quotient of the fold by its values at the sampled points
+        let points: Vec<F> = ood_points.iter().chain(shift_points.iter()).copied().collect();
+        let answers: Vec<F> = points.iter().map(|p| folded.evaluate(p)).collect();
+        let numerator = &folded - &interpolate(&points, &answers);
+        let (quotient, remainder) = DenseOrSparsePolynomial::from(numerator)
+            .divide_with_q_and_r(&DenseOrSparsePolynomial::from(vanishing_polynomial(&points)))
+            .unwrap();
+        debug_assert!(remainder.coeffs.is_empty(), "Fold does not match its answers");
+        let next_polynomial = quotient.naive_mul(&degree_correction_polynomial(r_comb, points.len()));
+
+        rounds.push(StirRoundProof {
+            root: next_tree.root_cap.clone().unwrap(),
+            ood_answers,
+            query_proof: QueryEvalProofs { merkle_proof: current_tree.multi_proof(&queries) },
+        });
+        current_polynomial = next_polynomial;
+        current_tree = next_tree;
+        domain_size /= 2;
+    }
+
+    let r_fold: F = transcript.get_challenge(b"alpha");
+    let final_polynomial = fold_polynomial(current_polynomial, r_fold, folding_factor);
+    // sent with all the coefficients of its degree bound
+    let mut final_coeffs = final_polynomial.coeffs;
+    final_coeffs.resize(stir_config.coeffs_length(degree, stir_config.num_rounds as usize + 1) as usize, F::ZERO);
+    transcript.observe_elements(b"final polynomial", &final_coeffs);
+    debug!(final_coeffs = final_coeffs.len(), "reached final polynomial");
+
+    let _query_span = debug_span!("query_phase", num_query = stir_config.num_queries[stir_config.num_rounds as usize]).entered();
+    let queries = shift_queries(transcript, stir_config.num_queries[stir_config.num_rounds as usize], domain_size, folding_factor);
+    StirProof {
+        initial_root,
+        rounds,
+        final_polynomial: final_coeffs,
+        final_query_proof: QueryEvalProofs { merkle_proof: current_tree.multi_proof(&queries) },
+    }
+}
@@ -0,0 +1,119 @@
+use ark_ff::PrimeField;
+
+use crate::fri::utils::FriConfigError;
+
+use super::types::{StirConfig, StirConfigError};
+
+// Conservative field size in bits (|F| >= 2^(MODULUS_BIT_SIZE-1))
+fn field_bits<F: PrimeField>() -> f64 {
+    (F::MODULUS_BIT_SIZE - 1) as f64
+}
+
+// Extra bits asked of each round, as the errors of num_rounds + 1 query phases and challenge rounds add up
+fn slack_bits(num_rounds: u32) -> f64 {
+    (2.0 * (num_rounds as f64 + 1.0)).log2().ceil()
+}
+
+impl StirConfig {
+    // Builds a config reaching `target_bits` of conjectured security (as SoundnessRegime::Conjectured for FRI)
+    // for polynomials of degree `degree`, with as many rounds as the degree and query counts allow.
+    pub fn for_security<F: PrimeField>(target_bits: u32, degree: u32, blow_up_factor: u32, folding_factor_bits: u32) -> Result<StirConfig, StirConfigError> {
+        if blow_up_factor < 2 || !blow_up_factor.is_power_of_two() {
+            return Err(FriConfigError::InvalidBlowUpFactor(blow_up_factor).into());
+        }
+        if folding_factor_bits == 0 {
+            return Err(FriConfigError::ZeroReduction { level: 0 }.into());
+        }
+        let coeffs_length = (degree as u64 + 1).next_power_of_two();
+        let log_coeffs = coeffs_length.trailing_zeros();
+        if log_coeffs < folding_factor_bits {
+            return Err(FriConfigError::ExcessReductions { required_bits: log_coeffs, provided_bits: folding_factor_bits }.into());
+        }
+        let degree = (coeffs_length - 1) as u32;
+        let log_domain = (coeffs_length * blow_up_factor as u64).ilog2() as f64;
+
+        for num_rounds in (0..log_coeffs / folding_factor_bits).rev() {
+            let round_bits = target_bits as f64 + slack_bits(num_rounds);
+            if field_bits::<F>() - log_domain < round_bits {
+                continue;
+            }
+            let mut config = StirConfig {
+                blow_up_factor,
+                folding_factor_bits,
+                num_rounds,
+                num_queries: vec![],
+                ood_samples: 1,
+                merkle_cap_bits: 0,
+            };
+            config.num_queries = (0..=num_rounds as usize)
+                .map(|round| (round_bits / config.rate_bits(degree, round)).ceil() as u32)
+                .collect();
+            if config.validate::<F>(degree).is_ok() && config.security_bits::<F>(degree) >= target_bits as f64 {
+                return Ok(config);
+            }
+        }
+        Err(FriConfigError::ChallengeFieldTooSmall { target_bits, max_bits: (field_bits::<F>() - log_domain - 1.0).max(0.0) as u32 }.into())
+    }
+
+    // -log2 of the rate of the polynomial tested in a round
+    fn rate_bits(&self, degree: u32, round: usize) -> f64 {
+        (self.domain_size(degree, round) as f64 / self.coeffs_length(degree, round) as f64).log2()
+    }
+
+    // Conjectured security of this config for polynomials of degree `degree` over the field F: the queries of
+    // round i contribute log2(1/rate_i) bits each, and each round's challenges have an error of |D|/|F|
+    pub fn security_bits<F: PrimeField>(&self, degree: u32) -> f64 {
+        let log_domain = (self.domain_size(degree, 0) as f64).log2();
+        let error: f64 = self.num_queries.iter().enumerate()
+            .map(|(round, t)| 2f64.powf(-(*t as f64) * self.rate_bits(degree, round)) + 2f64.powf(log_domain - field_bits::<F>()))
+            .sum();
+        -error.log2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_serialize::CanonicalSerialize;
+
+    use crate::fields::goldilocks_field::Fq;
+    use crate::fri::prover::generate_fri_proof;
+    use crate::fri::security::SoundnessRegime;
+    use crate::fri::types::FriConfig;
+    use crate::fri::utils::FriConfigError;
+    use crate::hashing::hasher::Sha256_;
+    use crate::stir::prover::generate_stir_proof;
+    use crate::stir::types::{StirConfig, StirConfigError};
+    use crate::stir::verifier::verify_stir_proof;
+
+    #[test]
+    fn test_stir_for_security() {
+        let config = StirConfig::for_security::<Fq>(40, 4095, 4, 2).unwrap();
+        assert!(config.security_bits::<Fq>(4095) >= 40.0);
+        // the rate drops every round, and so does the number of queries
+        assert!(config.num_queries.windows(2).all(|w| w[0] > w[1]));
+
+        assert_eq!(StirConfig::for_security::<Fq>(40, 4095, 3, 2), Err(FriConfigError::InvalidBlowUpFactor(3).into()));
+        assert!(matches!(StirConfig::for_security::<Fq>(100, 4095, 4, 2), Err(StirConfigError::Fri(FriConfigError::ChallengeFieldTooSmall { .. }))));
+
+        // STIR specific reasons
+        let mut missing_round = config.clone();
+        missing_round.num_queries.pop();
+        assert!(matches!(missing_round.validate::<Fq>(4095), Err(StirConfigError::QueryRoundsMismatch { .. })));
+        let mut too_many = config;
+        too_many.num_queries[0] = 4096;
+        assert!(matches!(too_many.validate::<Fq>(4095), Err(StirConfigError::TooManyQueries { round: 0, .. })));
+    }
+
+    #[test]
+    fn test_stir_smaller_than_fri() {
+        let degree = (1 << 10) - 1;
+        let polynomial = DensePolynomial::from_coefficients_vec((0..=degree as u64).map(Fq::from).collect());
+        let fri_config = FriConfig::for_security::<Fq>(40, degree, 4, 0, SoundnessRegime::Conjectured).unwrap();
+        let stir_config = StirConfig::for_security::<Fq>(40, degree, 4, 2).unwrap();
+        let fri_proof = generate_fri_proof::<Fq, Sha256_<Fq>>(polynomial.clone(), fri_config);
+        let stir_proof = generate_stir_proof::<Fq, Sha256_<Fq>>(polynomial, stir_config.clone());
+        assert!(stir_proof.compressed_size() < fri_proof.compressed_size());
+        assert!(verify_stir_proof(stir_config, degree, stir_proof));
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+
+use ark_ff::{FftField, PrimeField};
+use ark_serialize::{CanonicalSerialize, Compress, SerializationError, Write};
+
+use crate::fri::types::QueryEvalProofs;
+use crate::fri::utils::{FriConfigError, MAX_DOMAIN_BITS};
+use crate::hashing::hasher::Hasher_;
+
+// Every round folds the polynomial by 2^folding_factor_bits while the evaluation domain only halves, so the
+// rate drops and later rounds need fewer queries. After num_rounds rounds the last fold is sent in the clear.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StirConfig {
+    pub blow_up_factor: u32,
+    pub folding_factor_bits: u32,
+    pub num_rounds: u32,
+    pub num_queries: Vec<u32>, // shift queries of each round, then the final queries
+    pub ood_samples: u32, // out of domain samples per round
+    pub merkle_cap_bits: u32,
+}
+
+// Reasons a StirConfig cannot be used for a polynomial of a given degree, on top of those shared with FRI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StirConfigError {
+    Fri(FriConfigError),
+    QueryRoundsMismatch { num_rounds: u32, query_rounds: usize }, // one query count per round and the final one
+    TooManyQueries { round: usize, constraints: u32, coeffs_length: u64 }, // quotient degree would be negative
+}
+
+impl From<FriConfigError> for StirConfigError {
+    fn from(e: FriConfigError) -> Self {
+        StirConfigError::Fri(e)
+    }
+}
+
+impl fmt::Display for StirConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StirConfigError::Fri(e) => e.fmt(f),
+            StirConfigError::QueryRoundsMismatch { num_rounds, query_rounds } =>
+                write!(f, "{} rounds need {} query counts, {} given", num_rounds, num_rounds + 1, query_rounds),
+            StirConfigError::TooManyQueries { round, constraints, coeffs_length } =>
+                write!(f, "round {} constrains a polynomial of {} coefficients at {} points", round, coeffs_length, constraints),
+        }
+    }
+}
+
+impl std::error::Error for StirConfigError {}
+
+impl StirConfig {
+    pub fn folding_factor(&self) -> usize {
+        1 << self.folding_factor_bits
+    }
+
+    // Coefficients of the polynomial tested in round i (i = num_rounds for the final queries)
+    pub fn coeffs_length(&self, degree: u32, round: usize) -> u64 {
+        (degree as u64 + 1) >> (self.folding_factor_bits as usize * round)
+    }
+
+    // Evaluation domain of round i, halving each round
+    pub fn domain_size(&self, degree: u32, round: usize) -> u64 {
+        ((degree as u64 + 1) * self.blow_up_factor as u64) >> round
+    }
+
+    /// Validates whether the STIR config can be used for a polynomial of degree `degree` over F
+    pub fn validate<F: PrimeField>(&self, degree: u32) -> Result<(), StirConfigError> {
+        if self.num_queries.len() != self.num_rounds as usize + 1 {
+            return Err(StirConfigError::QueryRoundsMismatch { num_rounds: self.num_rounds, query_rounds: self.num_queries.len() });
+        }
+        if self.num_queries.contains(&0) {
+            return Err(FriConfigError::NoQueries.into());
+        }
+        if !self.blow_up_factor.is_power_of_two() || self.blow_up_factor < 2 {
+            return Err(FriConfigError::InvalidBlowUpFactor(self.blow_up_factor).into());
+        }
+        let log_coeffs = match degree.checked_add(1) {
+            Some(coeffs) if coeffs.is_power_of_two() => coeffs.trailing_zeros(),
+            _ => return Err(FriConfigError::DegreeNotPowerOfTwo(degree).into()),
+        };
+        if self.folding_factor_bits == 0 {
+            return Err(FriConfigError::ZeroReduction { level: 0 }.into());
+        }
+        // the final fold must keep at least one coefficient
+        let provided_bits = self.folding_factor_bits.saturating_mul(self.num_rounds + 1);
+        if provided_bits > log_coeffs {
+            return Err(FriConfigError::ExcessReductions { required_bits: log_coeffs, provided_bits }.into());
+        }
+        let domain_bits = log_coeffs + self.blow_up_factor.trailing_zeros();
+        let max_bits = <F as FftField>::TWO_ADICITY.min(MAX_DOMAIN_BITS);
+        if domain_bits > max_bits {
+            return Err(FriConfigError::DomainTooLarge { domain_bits, max_bits }.into());
+        }
+        // the answers of round i are interpolated and divided out of the next polynomial
+        for round in 0..self.num_rounds as usize {
+            let constraints = self.ood_samples.saturating_add(self.num_queries[round]);
+            let coeffs_length = self.coeffs_length(degree, round + 1);
+            if constraints as u64 >= coeffs_length {
+                return Err(StirConfigError::TooManyQueries { round, constraints, coeffs_length });
+            }
+        }
+        // smallest tree is the last round one, its leaves are the fibers of the folding
+        let final_leaves = self.domain_size(degree, self.num_rounds as usize) >> self.folding_factor_bits;
+        if final_leaves < 1 << self.merkle_cap_bits.saturating_add(1).min(63) {
+            return Err(FriConfigError::MerkleCapTooLarge { merkle_cap_bits: self.merkle_cap_bits, final_domain_size: final_leaves }.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StirRoundProof<F: PrimeField, H: Hasher_<F>> {
+    pub root: Vec<H::Hash>, // the folded polynomial over the next, shifted, domain
+    pub ood_answers: Vec<F>, // folded polynomial at the out of domain samples
+    pub query_proof: QueryEvalProofs<F, H>, // fibers of the previous commitment at the shift queries
+}
+
+#[derive(Debug, Clone)]
+pub struct StirProof<F: PrimeField, H: Hasher_<F>> {
+    pub initial_root: Vec<H::Hash>,
+    pub rounds: Vec<StirRoundProof<F, H>>,
+    pub final_polynomial: Vec<F>, // coefficients of the last fold
+    pub final_query_proof: QueryEvalProofs<F, H>,
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for StirRoundProof<F, H> {
+    fn serialize_with_mode<W: Write>(&self, mut writer: W, compress: Compress) -> Result<(), SerializationError> {
+        self.root.serialize_with_mode(&mut writer, compress)?;
+        self.ood_answers.serialize_with_mode(&mut writer, compress)?;
+        self.query_proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.root.serialized_size(compress) + self.ood_answers.serialized_size(compress) + self.query_proof.serialized_size(compress)
+    }
+}
+
+// Layout: initial root, rounds, final polynomial, final queries. Only encoded (e.g. to measure proof sizes).
+impl<F: PrimeField, H: Hasher_<F>> CanonicalSerialize for StirProof<F, H> {
+    fn serialize_with_mode<W: Write>(&self, mut writer: W, compress: Compress) -> Result<(), SerializationError> {
+        self.initial_root.serialize_with_mode(&mut writer, compress)?;
+        self.rounds.serialize_with_mode(&mut writer, compress)?;
+        self.final_polynomial.serialize_with_mode(&mut writer, compress)?;
+        self.final_query_proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.initial_root.serialized_size(compress)
+            + self.rounds.serialized_size(compress)
+            + self.final_polynomial.serialized_size(compress)
+            + self.final_query_proof.serialized_size(compress)
+    }
+}
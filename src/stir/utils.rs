@@ -0,0 +1,90 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::prover::leaf_groupings;
+
+use super::types::StirConfig;
+
+// Domain separator used by generate_stir_proof / verify_stir_proof
+pub const STIR_DOMAIN_SEPARATOR: &[u8] = b"fri-commitment-stir";
+
+// Evaluation domain of size `size`. Every round uses the coset g <w>, g = F::GENERATOR. Round i+1's domain
+// g <w^2> is disjoint from g^k <w^k>, the k-th powers of round i's domain where the shift queries land, as long
+// as g^(1-k) is outside the 2-adic subgroup <w>, i.e. its order (p - 1) / gcd(p - 1, k - 1) is not a power of
+// two. That holds whenever the odd part of p - 1 does not divide k - 1, for Goldilocks any folding factor below 2^32.
+pub fn stir_domain<F: PrimeField>(size: usize) -> GeneralEvaluationDomain<F> {
+    GeneralEvaluationDomain::<F>::new(size).unwrap().get_coset(F::GENERATOR).expect("Error in getting coset")
+}
+
+// Leaves of a commitment over a domain: leaf j holds the fiber of x_j^k, positions j, j + n/k, ...
+pub fn fiber_leaves<F: PrimeField>(evaluations: &[F], folding_factor_bits: u32) -> Vec<Vec<F>> {
+    leaf_groupings(vec![folding_factor_bits], evaluations.len(), 1)[0].chunks(1 << folding_factor_bits)
+        .map(|leaf| leaf.iter().map(|l| evaluations[*l]).collect())
+        .collect()
+}
+
+// Positions of the fiber of leaf j in a domain of size n
+pub fn fiber_positions(j: usize, domain_size: usize, folding_factor: usize) -> Vec<usize> {
+    (0..folding_factor).map(|m| j + m * domain_size / folding_factor).collect()
+}
+
+// Shift queries of a round, as sorted distinct leaf indices of a domain of size n
+pub fn shift_queries<F: PrimeField>(transcript: &mut impl TranscriptProtocol<F>, num_queries: u32, domain_size: usize, folding_factor: usize) -> Vec<usize> {
+    let mut queries: Vec<usize> = transcript.get_challenge_indices(b"challenge indices", num_queries as usize).iter()
+        .map(|q| *q as usize % (domain_size / folding_factor))
+        .collect();
+    queries.sort_unstable();
+    queries.dedup();
+    queries
+}
+
+// Lagrange interpolation of (points, values), the points being distinct
+pub fn interpolate<F: PrimeField>(points: &[F], values: &[F]) -> DensePolynomial<F> {
+    let mut result = DensePolynomial::from_coefficients_vec(vec![]);
+    for (i, (xi, yi)) in points.iter().zip(values.iter()).enumerate() {
+        let mut basis = DensePolynomial::from_coefficients_vec(vec![F::ONE]);
+        let mut denominator = F::ONE;
+        for (j, xj) in points.iter().enumerate() {
+            if i != j {
+                basis = basis.naive_mul(&DensePolynomial::from_coefficients_vec(vec![-*xj, F::ONE]));
+                denominator *= *xi - xj;
+            }
+        }
+        result += &(&basis * (*yi * denominator.inverse().expect("Interpolation points are not distinct")));
+    }
+    result
+}
+
+pub fn vanishing_polynomial<F: PrimeField>(points: &[F]) -> DensePolynomial<F> {
+    points.iter().fold(DensePolynomial::from_coefficients_vec(vec![F::ONE]), |acc, x| {
+        acc.naive_mul(&DensePolynomial::from_coefficients_vec(vec![-*x, F::ONE]))
+    })
+}
+
+// sum_{j <= e} (r x)^j, lifting a quotient by e points back to the degree bound of the round
+pub fn degree_correction_polynomial<F: PrimeField>(r_comb: F, e: usize) -> DensePolynomial<F> {
+    DensePolynomial::from_coefficients_vec(std::iter::successors(Some(F::ONE), |c| Some(*c * r_comb)).take(e + 1).collect())
+}
+
+// Value at x of the next round's polynomial, from the committed fold g(x):
+// (g(x) - answers(x)) / prod (x - p) * sum_{j <= |points|} (r x)^j
+pub fn quotient_value<F: PrimeField>(g_x: F, x: F, points: &[F], answers: &DensePolynomial<F>, r_comb: F) -> Option<F> {
+    let vanishing: F = points.iter().map(|p| x - p).product();
+    let correction = degree_correction_polynomial(r_comb, points.len()).evaluate(&x);
+    Some((g_x - answers.evaluate(&x)) * vanishing.inverse()? * correction)
+}
+
+// Binds the config and degree to the transcript before any commitment
+pub fn observe_stir_statement<F: PrimeField>(transcript: &mut impl TranscriptProtocol<F>, config: &StirConfig, degree: u32) {
+    transcript.observe_u64(b"blow up factor", config.blow_up_factor as u64);
+    transcript.observe_u64(b"folding factor bits", config.folding_factor_bits as u64);
+    transcript.observe_u64(b"num rounds", config.num_rounds as u64);
+    for num_query in config.num_queries.iter() {
+        transcript.observe_u64(b"num query", *num_query as u64);
+    }
+    transcript.observe_u64(b"ood samples", config.ood_samples as u64);
+    transcript.observe_u64(b"merkle cap bits", config.merkle_cap_bits as u64);
+    transcript.observe_u64(b"degree", degree as u64);
+    transcript.observe_element(b"coset offset", &F::GENERATOR);
+}
@@ -0,0 +1,199 @@
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+use tracing::{debug, debug_span};
+
+use crate::fiat_shamir::fiat_shamir::TranscriptProtocol;
+use crate::fri::types::QueryEvalProofs;
+use crate::fri::utils::new_fri_transcript;
+use crate::fri::verifier::calcualate_next_level_value;
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::merkle::verify_multi_proof;
+
+use super::types::{StirConfig, StirProof};
+use super::utils::{fiber_positions, interpolate, observe_stir_statement, quotient_value, shift_queries, stir_domain, STIR_DOMAIN_SEPARATOR};
+
+// Constraints of the previous round, turning openings of its committed fold into values of the polynomial
+// tested in this round (None in the first round, where the polynomial itself is committed)
+struct RoundConstraints<F: PrimeField> {
+    points: Vec<F>,
+    answers: DensePolynomial<F>,
+    r_comb: F,
+}
+
+// Opens the fibers of `queries` in the commitment over the domain of size `domain_size`, and folds each with `r_fold`.
+// Returns the k-th powers of the fibers' points and the folded values.
+fn fold_queries<F: PrimeField + From<i32>, H: Hasher_<F>>(
+    root: &[H::Hash],
+    query_proof: &QueryEvalProofs<F, H>,
+    queries: &[usize],
+    domain_size: usize,
+    config: &StirConfig,
+    constraints: &Option<RoundConstraints<F>>,
+    r_fold: F,
+) -> Option<(Vec<F>, Vec<F>)> {
+    let folding_factor = config.folding_factor();
+    let depth = (domain_size / folding_factor).trailing_zeros();
+    if !verify_multi_proof::<F, H>(root, depth, &query_proof.merkle_proof) {
+        return None;
+    }
+    let domain = stir_domain::<F>(domain_size);
+    let mut points = Vec::with_capacity(queries.len());
+    let mut values = Vec::with_capacity(queries.len());
+    for q in queries {
+        let leaf = match query_proof.merkle_proof.leaf(*q) {
+            Some(leaf) if leaf.len() == folding_factor => leaf,
+            _ => return None,
+        };
+        let positions = fiber_positions(*q, domain_size, folding_factor);
+        let evaluations = match constraints {
+            None => leaf.clone(),
+            Some(c) => positions.iter().zip(leaf.iter())
+                .map(|(p, g_x)| quotient_value(*g_x, domain.element(*p), &c.points, &c.answers, c.r_comb))
+                .collect::<Option<Vec<F>>>()?,
+        };
+        points.push(domain.element(*q).pow([folding_factor as u64]));
+        values.push(calcualate_next_level_value(positions, evaluations, r_fold, domain));
+    }
+    Some((points, values))
+}
+
+pub fn verify_stir_proof<F: PrimeField + From<i32>, H: Hasher_<F>>(stir_config: StirConfig, degree: u32, stir_proof: StirProof<F, H>) -> bool {
+    let mut transcript = new_fri_transcript(STIR_DOMAIN_SEPARATOR);
+    verify_stir_proof_with_transcript(stir_config, degree, stir_proof, &mut transcript)
+}
+
+// Verifies a proof produced by generate_stir_proof_with_transcript, `transcript` must be in the same
+// state as the prover's one was. Leaves the transcript after the final queries.
+pub fn verify_stir_proof_with_transcript<F: PrimeField + From<i32>, H: Hasher_<F>>(stir_config: StirConfig, degree: u32, stir_proof: StirProof<F, H>, transcript: &mut impl TranscriptProtocol<F>) -> bool {
+    if stir_config.validate::<F>(degree).is_err() {
+        return false;
+    }
+    let num_rounds = stir_config.num_rounds as usize;
+    let final_coeffs_length = stir_config.coeffs_length(degree, num_rounds + 1) as usize;
+    if stir_proof.rounds.len() != num_rounds || stir_proof.final_polynomial.len() != final_coeffs_length
+        || stir_proof.rounds.iter().any(|round| round.ood_answers.len() != stir_config.ood_samples as usize) {
+        return false;
+    }
+    let _verify_span = debug_span!("stir_verify", degree, num_rounds).entered();
+    observe_stir_statement::<F>(transcript, &stir_config, degree);
+
+    let folding_factor = stir_config.folding_factor();
+    let mut domain_size = stir_config.domain_size(degree, 0) as usize;
+    let root_field: Vec<F> = stir_proof.initial_root.iter().map(|r| H::hash_as_field(*r)).collect();
+    transcript.observe_elements(b"merkle_root", &root_field);
+    let mut root = &stir_proof.initial_root;
+    let mut constraints: Option<RoundConstraints<F>> = None;
+
+    for (round, round_proof) in stir_proof.rounds.iter().enumerate() {
+        let _round_span = debug_span!("verify_round", round, domain_size).entered();
+        let r_fold: F = transcript.get_challenge(b"alpha");
+        let root_field: Vec<F> = round_proof.root.iter().map(|r| H::hash_as_field(*r)).collect();
+        transcript.observe_elements(b"merkle_root", &root_field);
+        let ood_points: Vec<F> = (0..stir_config.ood_samples).map(|_| transcript.get_challenge(b"ood point")).collect();
+        transcript.observe_elements(b"ood answers", &round_proof.ood_answers);
+        let r_comb: F = transcript.get_challenge(b"combination");
+        let queries = shift_queries(transcript, stir_config.num_queries[round], domain_size, folding_factor);
+
+        let (shift_points, shift_answers) = match fold_queries(root, &round_proof.query_proof, &queries, domain_size, &stir_config, &constraints, r_fold) {
+            Some(folded) => folded,
+            None => {
+                debug!(round, "invalid shift query openings");
+                return false;
+            },
+        };
+        let points: Vec<F> = ood_points.iter().chain(shift_points.iter()).copied().collect();
+        let answers: Vec<F> = round_proof.ood_answers.iter().chain(shift_answers.iter()).copied().collect();
+        // points only repeat with negligible probability, and then cannot be interpolated
+        if (0..points.len()).any(|i| points[..i].contains(&points[i])) {
+            return false;
+        }
+        constraints = Some(RoundConstraints { answers: interpolate(&points, &answers), points, r_comb });
+        root = &round_proof.root;
+        domain_size /= 2;
+    }
+
+    let r_fold: F = transcript.get_challenge(b"alpha");
+    transcript.observe_elements(b"final polynomial", &stir_proof.final_polynomial);
+    let queries = shift_queries(transcript, stir_config.num_queries[num_rounds], domain_size, folding_factor);
+    let (points, values) = match fold_queries(root, &stir_proof.final_query_proof, &queries, domain_size, &stir_config, &constraints, r_fold) {
+        Some(folded) => folded,
+        None => {
+            debug!("invalid final query openings");
+            return false;
+        },
+    };
+    let final_polynomial = DensePolynomial::from_coefficients_slice(&stir_proof.final_polynomial);
+    points.iter().zip(values.iter()).all(|(p, v)| final_polynomial.evaluate(p) == *v)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_std::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::fields::goldilocks_field::Fq;
+    use crate::hashing::hasher::Sha256_;
+    use crate::fri::utils::new_fri_transcript;
+    use crate::stir::prover::{generate_stir_proof, prove};
+    use crate::stir::types::StirConfig;
+    use crate::stir::utils::STIR_DOMAIN_SEPARATOR;
+    use super::verify_stir_proof;
+
+    fn config() -> StirConfig {
+        StirConfig { blow_up_factor: 4, folding_factor_bits: 2, num_rounds: 2, num_queries: vec![6, 4, 3], ood_samples: 1, merkle_cap_bits: 1 }
+    }
+
+    fn random_polynomial(rng: &mut StdRng, coeffs_length: usize) -> DensePolynomial<Fq> {
+        DensePolynomial::from_coefficients_vec((0..coeffs_length).map(|_| Fq::rand(rng)).collect())
+    }
+
+    #[test]
+    fn test_stir() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let polynomial = random_polynomial(&mut rng, 256);
+        let proof = generate_stir_proof::<Fq, Sha256_<Fq>>(polynomial, config());
+        assert_eq!(proof.rounds.len(), 2);
+        // 256 coefficients folded 3 times by 4
+        assert_eq!(proof.final_polynomial.len(), 4);
+        assert!(verify_stir_proof(config(), 255, proof.clone()));
+        assert!(!verify_stir_proof(config(), 511, proof));
+
+        // a single round, folding by 8
+        let single = StirConfig { folding_factor_bits: 3, num_rounds: 1, num_queries: vec![8, 4], ..config() };
+        let polynomial = random_polynomial(&mut rng, 512);
+        let proof = generate_stir_proof::<Fq, Sha256_<Fq>>(polynomial, single.clone());
+        assert!(verify_stir_proof(single, 511, proof));
+    }
+
+    #[test]
+    fn test_stir_tampered() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let proof = generate_stir_proof::<Fq, Sha256_<Fq>>(random_polynomial(&mut rng, 256), config());
+
+        let mut bad_final = proof.clone();
+        bad_final.final_polynomial[1] += Fq::from(1u64);
+        assert!(!verify_stir_proof(config(), 255, bad_final));
+
+        let mut bad_ood = proof.clone();
+        bad_ood.rounds[1].ood_answers[0] += Fq::from(1u64);
+        assert!(!verify_stir_proof(config(), 255, bad_ood));
+
+        let mut bad_root = proof.clone();
+        bad_root.rounds.swap(0, 1);
+        assert!(!verify_stir_proof(config(), 255, bad_root));
+
+        let mut bad_queries = config();
+        bad_queries.num_queries[2] += 1;
+        assert!(!verify_stir_proof(bad_queries, 255, proof));
+    }
+
+    #[test]
+    fn test_stir_high_degree_rejected() {
+        // 1024 coefficients against a bound of 256, every fold drops the coefficients past the bound
+        let mut rng = StdRng::seed_from_u64(2);
+        let polynomial = random_polynomial(&mut rng, 1024);
+        let proof = prove::<Fq, Sha256_<Fq>>(polynomial, 255, &config(), &mut new_fri_transcript(STIR_DOMAIN_SEPARATOR));
+        assert!(!verify_stir_proof(config(), 255, proof));
+    }
+}
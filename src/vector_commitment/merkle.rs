@@ -0,0 +1,185 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+
+use crate::hashing::hasher::Hasher_;
+use crate::merkle_tree::kary::{kary_depth, verify_kary_multi_proof, KaryMerkleTree, KaryMultiProof};
+use crate::merkle_tree::merkle::{verify_multi_proof, MerkleMultiProof, MerkleTree};
+
+use super::VectorCommitment;
+
+// Binary Merkle tree committed by its root. Needs at least two rows.
+pub struct BinaryMerkle<F: PrimeField, H: Hasher_<F>> {
+    _h: PhantomData<(F, H)>,
+}
+
+// Binary Merkle tree committed by the 2^cap_bits nodes of its cap, this is what FRIProof uses
+pub struct CappedMerkle<F: PrimeField, H: Hasher_<F>> {
+    pub cap_bits: u32,
+    _h: PhantomData<(F, H)>,
+}
+
+// Merkle tree with `arity` children per node, committed by its root
+pub struct KaryMerkle<F: PrimeField, H: Hasher_<F>> {
+    pub arity: usize,
+    _h: PhantomData<(F, H)>,
+}
+
+impl<F: PrimeField, H: Hasher_<F>> BinaryMerkle<F, H> {
+    pub fn new() -> Self {
+        BinaryMerkle { _h: PhantomData }
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> Default for BinaryMerkle<F, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> CappedMerkle<F, H> {
+    pub fn new(cap_bits: u32) -> Self {
+        CappedMerkle { cap_bits, _h: PhantomData }
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> KaryMerkle<F, H> {
+    pub fn new(arity: usize) -> Self {
+        assert!(arity >= 2, "Arity must be at least 2");
+        KaryMerkle { arity, _h: PhantomData }
+    }
+}
+
+fn merkle_depth(num_rows: usize) -> u32 {
+    num_rows.next_power_of_two().trailing_zeros()
+}
+
+impl<F: PrimeField, H: Hasher_<F>> VectorCommitment<F> for BinaryMerkle<F, H> {
+    type Commitment = H::Hash;
+    type ProverData = MerkleTree<F, H>;
+    type Opening = MerkleMultiProof<F, H>;
+
+    fn commit(&self, rows: Vec<Vec<F>>) -> (H::Hash, MerkleTree<F, H>) {
+        let mut tree = MerkleTree::new(0);
+        tree.insert(rows).expect("Tree is memory backed");
+        let root = tree.compute_tree().expect("Tree is memory backed")[0];
+        (root, tree)
+    }
+
+    fn open(&self, data: &MerkleTree<F, H>, indices: &[usize]) -> MerkleMultiProof<F, H> {
+        data.multi_proof(indices)
+    }
+
+    fn verify(&self, commitment: &H::Hash, num_rows: usize, opening: &MerkleMultiProof<F, H>) -> bool {
+        verify_multi_proof(std::slice::from_ref(commitment), merkle_depth(num_rows), opening)
+    }
+
+    fn opened_row<'a>(&self, opening: &'a MerkleMultiProof<F, H>, idx: usize) -> Option<&'a Vec<F>> {
+        opening.leaf(idx)
+    }
+
+    fn commitment_elements(&self, commitment: &H::Hash) -> Vec<F> {
+        vec![H::hash_as_field(*commitment)]
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> VectorCommitment<F> for CappedMerkle<F, H> {
+    type Commitment = Vec<H::Hash>;
+    type ProverData = MerkleTree<F, H>;
+    type Opening = MerkleMultiProof<F, H>;
+
+    fn commit(&self, rows: Vec<Vec<F>>) -> (Vec<H::Hash>, MerkleTree<F, H>) {
+        let mut tree = MerkleTree::new(self.cap_bits);
+        tree.insert(rows).expect("Tree is memory backed");
+        let root_cap = tree.compute_tree().expect("Tree is memory backed");
+        (root_cap, tree)
+    }
+
+    fn open(&self, data: &MerkleTree<F, H>, indices: &[usize]) -> MerkleMultiProof<F, H> {
+        data.multi_proof(indices)
+    }
+
+    fn verify(&self, commitment: &Vec<H::Hash>, num_rows: usize, opening: &MerkleMultiProof<F, H>) -> bool {
+        commitment.len() == 1 << self.cap_bits && verify_multi_proof(commitment, merkle_depth(num_rows), opening)
+    }
+
+    fn opened_row<'a>(&self, opening: &'a MerkleMultiProof<F, H>, idx: usize) -> Option<&'a Vec<F>> {
+        opening.leaf(idx)
+    }
+
+    fn commitment_elements(&self, commitment: &Vec<H::Hash>) -> Vec<F> {
+        commitment.iter().map(|r| H::hash_as_field(*r)).collect()
+    }
+}
+
+impl<F: PrimeField, H: Hasher_<F>> VectorCommitment<F> for KaryMerkle<F, H> {
+    type Commitment = H::Hash;
+    type ProverData = KaryMerkleTree<F, H>;
+    type Opening = KaryMultiProof<F, H>;
+
+    fn commit(&self, rows: Vec<Vec<F>>) -> (H::Hash, KaryMerkleTree<F, H>) {
+        let mut tree = KaryMerkleTree::new(self.arity);
+        tree.insert(rows);
+        let root = tree.compute_tree();
+        (root, tree)
+    }
+
+    fn open(&self, data: &KaryMerkleTree<F, H>, indices: &[usize]) -> KaryMultiProof<F, H> {
+        data.multi_proof(indices)
+    }
+
+    fn verify(&self, commitment: &H::Hash, num_rows: usize, opening: &KaryMultiProof<F, H>) -> bool {
+        verify_kary_multi_proof(commitment, self.arity, kary_depth(self.arity, num_rows), opening)
+    }
+
+    fn opened_row<'a>(&self, opening: &'a KaryMultiProof<F, H>, idx: usize) -> Option<&'a Vec<F>> {
+        opening.leaf(idx)
+    }
+
+    fn commitment_elements(&self, commitment: &H::Hash) -> Vec<F> {
+        vec![H::hash_as_field(*commitment)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields::goldilocks_field::Fq, hashing::hasher::Sha256_};
+
+    fn rows(num_rows: usize) -> Vec<Vec<Fq>> {
+        (0..num_rows).map(|i| vec![Fq::from(i as u64), Fq::from(i as u64 * 5 + 2)]).collect()
+    }
+
+    // Commits to 32 rows, opens some of them and checks the opening, and that it fails for other rows
+    // and once `move_value` moves a value between the columns of an opened row
+    fn check_backend<V: VectorCommitment<Fq>>(vc: V, move_value: fn(&mut V::Opening)) where V::Opening: Clone {
+        let (commitment, data) = vc.commit(rows(32));
+        let opening = vc.open(&data, &[3, 17, 3, 30]);
+        assert!(vc.verify(&commitment, 32, &opening));
+        assert_eq!(vc.opened_row(&opening, 17), Some(&rows(32)[17]));
+        assert_eq!(vc.opened_row(&opening, 4), None);
+
+        let mut moved = opening.clone();
+        move_value(&mut moved);
+        assert!(vc.opened_row(&moved, 17) != Some(&rows(32)[17]));
+        assert!(!vc.verify(&commitment, 32, &moved));
+
+        let (other, _) = vc.commit(rows(31));
+        assert!(other != commitment);
+        assert!(!vc.verify(&other, 32, &opening));
+    }
+
+    // Moves one unit from the first to the second column of row 17, keeping the row's sum
+    fn move_in_row(leaves: &mut [Vec<Fq>]) {
+        leaves[1][0] -= Fq::from(1);
+        leaves[1][1] += Fq::from(1);
+    }
+
+    #[test]
+    fn test_vector_commitment_backends() {
+        check_backend(BinaryMerkle::<Fq, Sha256_<Fq>>::new(), |o| move_in_row(&mut o.leaves));
+        check_backend(CappedMerkle::<Fq, Sha256_<Fq>>::new(2), |o| move_in_row(&mut o.leaves));
+        check_backend(KaryMerkle::<Fq, Sha256_<Fq>>::new(4), |o| move_in_row(&mut o.leaves));
+        check_backend(KaryMerkle::<Fq, Sha256_<Fq>>::new(3), |o| move_in_row(&mut o.leaves));
+    }
+}
@@ -0,0 +1,25 @@
+use ark_ff::PrimeField;
+
+pub mod merkle;
+
+// Commitment to a vector of rows, opened at several indices at once. The FRI prover and verifier
+// are generic over it (see fri::prover::generate_fri_proof_with_vc).
+pub trait VectorCommitment<F: PrimeField> {
+    type Commitment: Clone + PartialEq;
+    type ProverData;
+    type Opening;
+
+    fn commit(&self, rows: Vec<Vec<F>>) -> (Self::Commitment, Self::ProverData);
+
+    // Opens the rows at `indices`, duplicates are opened once
+    fn open(&self, data: &Self::ProverData, indices: &[usize]) -> Self::Opening;
+
+    // Checks `opening` against a commitment to `num_rows` rows
+    fn verify(&self, commitment: &Self::Commitment, num_rows: usize, opening: &Self::Opening) -> bool;
+
+    // Row `idx` carried by `opening`, None if it is not opened
+    fn opened_row<'a>(&self, opening: &'a Self::Opening, idx: usize) -> Option<&'a Vec<F>>;
+
+    // Commitment as field elements, as observed by the transcript
+    fn commitment_elements(&self, commitment: &Self::Commitment) -> Vec<F>;
+}